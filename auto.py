@@ -72,15 +72,45 @@ REGISTER_CONFIG = {
     "email_domain": os.getenv("EMAIL_DOMAIN", "@example.com"),
 }
 
+def app_data_dir():
+    """Kiro Account Manager 数据目录（与应用内 paths.rs 的解析规则一致）"""
+    data_dir = os.getenv("KIRO_AM_DATA_DIR")
+    if data_dir:
+        return data_dir
+    home = os.getenv("KIRO_AM_HOME")
+    system = platform.system()
+    if system == "Windows":
+        base = os.path.join(home, "AppData", "Roaming") if home else os.getenv("APPDATA", os.path.expanduser("~"))
+    elif system == "Darwin":
+        base = os.path.join(home or os.path.expanduser("~"), "Library", "Application Support")
+    elif home:
+        base = os.path.join(home, ".local", "share")
+    else:
+        base = os.getenv("XDG_DATA_HOME") or os.path.expanduser("~/.local/share")
+    return os.path.join(base, ".kiro-account-manager")
+
+
+def read_api_token_file():
+    """读取应用写入数据目录的本地 API 令牌文件，不存在时返回空字符串"""
+    try:
+        with open(os.path.join(app_data_dir(), "http-api-token"), encoding="utf-8") as f:
+            return f.read().strip()
+    except OSError:
+        return ""
+
+
 # 服务配置
 SERVICE_CONFIG = {
-    "base_url": "http://127.0.0.1:23847",
+    # 实际监听地址（由 Kiro Account Manager 启动脚本时传入，端口被占用时会变化）
+    "base_url": os.getenv("KIRO_MANAGER_API_URL") or "http://127.0.0.1:23847",
     "get_url_endpoint": "/get_device_auth_url",
     "start_auth_endpoint": "/start_device_auth",
     "poll_auth_endpoint": "/poll_device_auth",
     "reload_accounts_endpoint": "/reload_accounts",
     "reset_machine_id_endpoint": "/reset_machine_id",
     "status_endpoint": "/status",
+    # 本地 API 令牌（由 Kiro Account Manager 启动脚本时传入，单独运行时读取数据目录中的令牌文件）
+    "api_token": os.getenv("KIRO_MANAGER_API_TOKEN") or read_api_token_file(),
     "poll_interval": 3,
     "max_wait_time": 600,
 }
@@ -451,6 +481,13 @@ def close_browser(page):
 # 服务通信函数
 # ============================================================
 
+def api_headers():
+    """本地 API 请求头（携带 Bearer 令牌）"""
+    if SERVICE_CONFIG["api_token"]:
+        return {"Authorization": f"Bearer {SERVICE_CONFIG['api_token']}"}
+    return {}
+
+
 def check_service_status():
     """检查服务是否运行"""
     try:
@@ -466,14 +503,19 @@ def check_service_status():
 def reload_accounts():
     """通知应用重新加载账号列表"""
     try:
-        response = requests.get(
+        response = requests.post(
             f"{SERVICE_CONFIG['base_url']}{SERVICE_CONFIG['reload_accounts_endpoint']}",
+            headers=api_headers(),
             timeout=10
         )
         response.raise_for_status()
         data = response.json()
         if data.get("success"):
-            print(f"账号列表已刷新，共 {data.get('count', 0)} 个账号")
+            print(
+                f"账号列表已刷新，共 {data.get('count', 0)} 个账号"
+                f"（新增 {len(data.get('added', []))}，删除 {len(data.get('removed', []))}，"
+                f"变更 {len(data.get('changed', []))}）"
+            )
             return True
     except Exception as e:
         print(f"刷新账号列表失败: {e}")
@@ -484,8 +526,9 @@ def start_device_auth():
     """触发设备授权流程"""
     try:
         print("正在触发设备授权流程...")
-        response = requests.get(
+        response = requests.post(
             f"{SERVICE_CONFIG['base_url']}{SERVICE_CONFIG['start_auth_endpoint']}",
+            headers=api_headers(),
             timeout=60
         )
         response.raise_for_status()
@@ -515,6 +558,7 @@ def wait_for_account_saved(timeout=60, interval=3):
         try:
             response = requests.get(
                 f"{SERVICE_CONFIG['base_url']}{SERVICE_CONFIG['get_url_endpoint']}",
+                headers=api_headers(),
                 timeout=10
             )
             response.raise_for_status()
@@ -1,9 +1,10 @@
 use chrono::{DateTime, Local};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use std::path::PathBuf;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Account {
     pub id: String,
@@ -62,26 +63,79 @@ impl Account {
     }
 }
 
+/// 脱敏后替换密钥字段的占位符
+pub const REDACTED: &str = "***";
+
+impl Account {
+    /// 返回隐藏了 token / secret 的副本（用于对外接口）
+    pub fn redacted(&self) -> Self {
+        let mask = |v: &Option<String>| v.as_ref().map(|_| REDACTED.to_string());
+        Self {
+            access_token: mask(&self.access_token),
+            refresh_token: mask(&self.refresh_token),
+            csrf_token: mask(&self.csrf_token),
+            session_token: mask(&self.session_token),
+            client_secret: mask(&self.client_secret),
+            sso_session_id: mask(&self.sso_session_id),
+            id_token: mask(&self.id_token),
+            ..self.clone()
+        }
+    }
+}
+
+/// 两个账号列表之间的差异（按 ID）
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct AccountDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+impl AccountDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// 比较新旧账号列表
+pub fn diff_accounts(old: &[Account], new: &[Account]) -> AccountDiff {
+    let mut diff = AccountDiff::default();
+    for account in new {
+        match old.iter().find(|a| a.id == account.id) {
+            None => diff.added.push(account.id.clone()),
+            Some(prev) if prev != account => diff.changed.push(account.id.clone()),
+            Some(_) => {}
+        }
+    }
+    for account in old {
+        if !new.iter().any(|a| a.id == account.id) {
+            diff.removed.push(account.id.clone());
+        }
+    }
+    diff
+}
+
 pub struct AccountStore {
     pub accounts: Vec<Account>,
     file_path: PathBuf,
 }
 
+impl Default for AccountStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AccountStore {
     pub fn new() -> Self {
         let file_path = Self::get_storage_path();
         let accounts = Self::load_from_file(&file_path);
+        crate::events::observe_accounts(&accounts);
         Self { accounts, file_path }
     }
 
     fn get_storage_path() -> PathBuf {
-        let data_dir = dirs::data_dir().unwrap_or_else(|| {
-            let home = std::env::var("USERPROFILE")
-                .or_else(|_| std::env::var("HOME"))
-                .unwrap_or_else(|_| ".".to_string());
-            PathBuf::from(home)
-        });
-        data_dir.join(".kiro-account-manager").join("accounts.json")
+        crate::paths::app_data_file("accounts.json")
     }
 
     fn load_from_file(path: &PathBuf) -> Vec<Account> {
@@ -97,13 +151,16 @@ impl AccountStore {
             let _ = std::fs::create_dir_all(parent);
         }
         if let Ok(json) = serde_json::to_string_pretty(&self.accounts) {
-            let _ = std::fs::write(&self.file_path, json);
+            if std::fs::write(&self.file_path, json).is_ok() {
+                crate::events::observe_accounts(&self.accounts);
+            }
         }
     }
 
     /// 从文件重新加载账号列表
     pub fn reload_from_file(&mut self) {
         self.accounts = Self::load_from_file(&self.file_path);
+        crate::events::observe_accounts(&self.accounts);
     }
 
     pub fn get_all(&self) -> Vec<Account> {
@@ -0,0 +1,1063 @@
+// 账号的添加、验证、刷新与同步 - 供 Tauri 命令、本地 HTTP API、无界面模式与命令行共用
+
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use crate::account::{Account, AccountStore};
+use crate::auth::{refresh_token_desktop, get_usage_limits_desktop};
+use crate::auth_social;
+use crate::aws_sso_client::{AWSSSOClient, DevicePollResult, TokenResponse};
+use crate::codewhisperer_client::CodeWhispererClient;
+use crate::kiro::{get_client_registration, get_kiro_local_token, get_machine_id};
+use crate::providers::{
+    create_idc_provider, create_social_provider, get_provider_config, AuthMethod, AuthProvider, IdcProvider,
+    ProviderConfig, RefreshMetadata, SocialProvider,
+};
+use crate::providers::web_oauth::{KiroWebPortalClient, WebOAuthInitResult, WebOAuthProvider};
+use crate::sso_import;
+
+/// BuilderId 的 SSO 起始地址
+const BUILDER_ID_START_URL: &str = "https://view.awsapps.com/start";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyAccountResponse {
+    #[serde(rename = "usageLimit")]
+    pub usage_limit: Option<i32>,
+    #[serde(rename = "currentUsage")]
+    pub current_usage: Option<i32>,
+    #[serde(rename = "subscriptionType")]
+    pub subscription_type: Option<String>,
+    #[serde(rename = "accessToken")]
+    pub access_token: String,
+    #[serde(rename = "refreshToken")]
+    pub refresh_token: String,
+}
+
+/// BuilderId 账号的 clientIdHash（起始地址的 SHA-256，与 IdC provider 保持一致）
+fn builder_id_client_id_hash() -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(BUILDER_ID_START_URL.as_bytes()))
+}
+
+/// 刷新 token 并获取最新 usage
+pub async fn sync_account(store: &Mutex<AccountStore>, id: &str) -> Result<Account, String> {
+    let account = {
+        let store = store.lock().unwrap();
+        store.accounts.iter().find(|a| a.id == id).cloned()
+    }.ok_or("Account not found")?;
+
+    let provider_str = account.provider.as_deref().unwrap_or("Google");
+    let refresh_token_str = account.refresh_token.as_ref().ok_or("No refresh token")?;
+    
+    println!("[sync_account] Refreshing {} account", provider_str);
+    
+    // 根据 provider 选择刷新接口
+    // 注意：Web OAuth 的 refresh_token 也是 aor 开头的 RefreshToken Cookie，可以用 Desktop API
+    let (new_access_token, new_refresh_token, expires_in, new_profile_arn, new_id_token, new_sso_session_id) = 
+        if provider_str == "BuilderId" {
+            // BuilderId -> AWS OIDC
+            let metadata = RefreshMetadata {
+                client_id: account.client_id.clone(),
+                client_secret: account.client_secret.clone(),
+                region: account.region.clone(),
+                ..Default::default()
+            };
+            let idc_provider = IdcProvider::new("BuilderId", metadata.region.as_deref().unwrap_or("us-east-1"), None);
+            let auth_result = idc_provider.refresh_token(refresh_token_str, metadata).await?;
+            (auth_result.access_token, Some(auth_result.refresh_token), auth_result.expires_in, None, auth_result.id_token, auth_result.sso_session_id)
+        } else {
+            // Google/Github (Desktop OAuth 或 Web OAuth) -> Desktop API
+            // Web OAuth 的 refresh_token 是 RefreshToken Cookie (aor开头)，跟 Desktop OAuth 相同
+            let metadata = RefreshMetadata {
+                profile_arn: account.profile_arn.clone(),
+                ..Default::default()
+            };
+            let social_provider = SocialProvider::new(provider_str);
+            let auth_result = social_provider.refresh_token(refresh_token_str, metadata).await?;
+            (auth_result.access_token, Some(auth_result.refresh_token), auth_result.expires_in, auth_result.profile_arn, None, None)
+        };
+    
+    // 获取 usage 数据
+    let (usage_data, is_banned): (serde_json::Value, bool) = if provider_str == "BuilderId" {
+        let machine_id = get_machine_id();
+        let cw_client = CodeWhispererClient::new(&machine_id);
+        let usage_call = cw_client.get_usage_limits(&new_access_token).await;
+        let (usage, banned) = match &usage_call {
+            Ok(u) => (Some(u.clone()), false),
+            Err(e) if e.starts_with("BANNED:") => (None, true),
+            Err(_) => (None, false),
+        };
+        (serde_json::to_value(&usage).unwrap_or(serde_json::Value::Null), banned)
+    } else {
+        let usage_call = get_usage_limits_desktop(&new_access_token).await;
+        let (usage, banned) = match &usage_call {
+            Ok(u) => (Some(u.clone()), false),
+            Err(e) if e.starts_with("BANNED:") => (None, true),
+            Err(_) => (None, false),
+        };
+        (serde_json::to_value(&usage).unwrap_or(serde_json::Value::Null), banned)
+    };
+
+    let expires_at = chrono::Local::now() + chrono::Duration::seconds(expires_in);
+    let expires_at_str = expires_at.format("%Y/%m/%d %H:%M:%S").to_string();
+
+    // 更新账号
+    let mut store = store.lock().unwrap();
+    if let Some(a) = store.accounts.iter_mut().find(|a| a.id == id) {
+        a.access_token = Some(new_access_token);
+        if let Some(rt) = new_refresh_token {
+            a.refresh_token = Some(rt);
+        }
+        if let Some(arn) = new_profile_arn {
+            a.profile_arn = Some(arn);
+        }
+        if let Some(id_token) = new_id_token {
+            a.id_token = Some(id_token);
+        }
+        if let Some(session_id) = new_sso_session_id {
+            a.sso_session_id = Some(session_id);
+        }
+        a.expires_at = Some(expires_at_str);
+        a.usage_data = Some(usage_data);
+        a.status = if is_banned { "已封禁".to_string() } else { "正常".to_string() };
+        
+        let result = a.clone();
+        store.save_to_file();
+        return Ok(result);
+    }
+
+    Err("Account not found after update".to_string())
+}
+
+/// 只刷新 token，不获取 usage（启动时快速刷新用）
+pub async fn refresh_account_token(store: &Mutex<AccountStore>, id: &str) -> Result<Account, String> {
+    let account = {
+        let store = store.lock().unwrap();
+        store.accounts.iter().find(|a| a.id == id).cloned()
+    }.ok_or("Account not found")?;
+
+    let provider_str = account.provider.as_deref().unwrap_or("Google");
+    let refresh_token_str = account.refresh_token.as_ref().ok_or("No refresh token")?;
+    
+    println!("[refresh_token] Refreshing {} token only", provider_str);
+    
+    let (new_access_token, new_refresh_token, expires_in) = 
+        if provider_str == "BuilderId" {
+            let metadata = RefreshMetadata {
+                client_id: account.client_id.clone(),
+                client_secret: account.client_secret.clone(),
+                region: account.region.clone(),
+                ..Default::default()
+            };
+            let idc_provider = IdcProvider::new("BuilderId", metadata.region.as_deref().unwrap_or("us-east-1"), None);
+            let auth_result = idc_provider.refresh_token(refresh_token_str, metadata).await?;
+            (auth_result.access_token, Some(auth_result.refresh_token), auth_result.expires_in)
+        } else {
+            let metadata = RefreshMetadata {
+                profile_arn: account.profile_arn.clone(),
+                ..Default::default()
+            };
+            let social_provider = SocialProvider::new(provider_str);
+            let auth_result = social_provider.refresh_token(refresh_token_str, metadata).await?;
+            (auth_result.access_token, Some(auth_result.refresh_token), auth_result.expires_in)
+        };
+
+    let expires_at = chrono::Local::now() + chrono::Duration::seconds(expires_in);
+    let expires_at_str = expires_at.format("%Y/%m/%d %H:%M:%S").to_string();
+
+    let mut store = store.lock().unwrap();
+    if let Some(a) = store.accounts.iter_mut().find(|a| a.id == id) {
+        a.access_token = Some(new_access_token);
+        if let Some(rt) = new_refresh_token {
+            a.refresh_token = Some(rt);
+        }
+        a.expires_at = Some(expires_at_str);
+        
+        let result = a.clone();
+        store.save_to_file();
+        println!("[refresh_token] {} token refreshed", provider_str);
+        return Ok(result);
+    }
+
+    Err("Account not found after update".to_string())
+}
+
+/// 用 refresh_token 验证账号并返回最新额度
+pub async fn verify_account(
+    store: &Mutex<AccountStore>,
+    refresh_token: String,
+    provider: String,
+    // IdC 账号需要的额外参数
+    client_id: Option<String>,
+    client_secret: Option<String>,
+    region: Option<String>,
+) -> Result<VerifyAccountResponse, String> {
+    // 判断是否是 IdC 账号
+    let is_idc = provider == "BuilderId" || provider == "Enterprise";
+    
+    let (new_access_token, new_refresh_token, quota, used, subscription_type) = if is_idc {
+        // IdC 账号使用 AWS OIDC 刷新
+        // 优先使用传入的参数，否则从数据库查找
+        let (cid, csec, reg) = if client_id.is_some() && client_secret.is_some() {
+            (client_id, client_secret, region)
+        } else {
+            // 从数据库查找
+            let store = store.lock().unwrap();
+            store.accounts.iter().find(|a| {
+                a.refresh_token.as_ref() == Some(&refresh_token)
+            }).map(|a| (
+                a.client_id.clone(),
+                a.client_secret.clone(),
+                a.region.clone(),
+            )).unwrap_or((None, None, None))
+        };
+        
+        let cid = cid.ok_or("IdC 账号缺少 client_id，请重新添加账号")?;
+        let csec = csec.ok_or("IdC 账号缺少 client_secret，请重新添加账号")?;
+        
+        let metadata = RefreshMetadata {
+            client_id: Some(cid),
+            client_secret: Some(csec),
+            region: reg.clone(),
+            ..Default::default()
+        };
+        
+        let region_str = reg.as_deref().unwrap_or("us-east-1");
+        let idc_provider = IdcProvider::new(&provider, region_str, None);
+        let auth_result = idc_provider.refresh_token(&refresh_token, metadata).await?;
+        
+        // 使用 CodeWhisperer API 获取 usage
+        let machine_id = get_machine_id();
+        let cw_client = CodeWhispererClient::new(&machine_id);
+        let usage = cw_client.get_usage_limits(&auth_result.access_token).await?;
+        
+        let (q, u) = usage.usage_breakdown_list.as_ref()
+            .and_then(|list| list.first())
+            .map(|b| (b.usage_limit, b.current_usage))
+            .unwrap_or((None, None));
+        
+        (auth_result.access_token, auth_result.refresh_token, q, u, usage.subscription_info.and_then(|s| s.subscription_type))
+    } else {
+        // Social 账号使用 Desktop API 刷新
+        let refresh_result = refresh_token_desktop(&refresh_token).await?;
+        let usage = get_usage_limits_desktop(&refresh_result.access_token).await?;
+        
+        let (q, u) = usage.usage_breakdown_list.as_ref()
+            .and_then(|list| list.first())
+            .map(|b| (b.usage_limit, b.current_usage))
+            .unwrap_or((None, None));
+        
+        (refresh_result.access_token, refresh_result.refresh_token, q, u, usage.subscription_info.and_then(|s| s.subscription_type))
+    };
+    
+    // 更新数据库中的 token
+    {
+        let mut store = store.lock().unwrap();
+        if let Some(account) = store.accounts.iter_mut().find(|a| {
+            a.refresh_token.as_ref() == Some(&refresh_token)
+        }) {
+            account.access_token = Some(new_access_token.clone());
+            account.refresh_token = Some(new_refresh_token.clone());
+            store.save_to_file();
+        }
+    }
+    
+    Ok(VerifyAccountResponse {
+        usage_limit: quota,
+        current_usage: used,
+        subscription_type,
+        access_token: new_access_token,
+        refresh_token: new_refresh_token,
+    })
+}
+
+/// 通过 Social refresh_token 添加账号（按 email + provider 去重）
+pub async fn add_account_by_social(
+    store: &Mutex<AccountStore>,
+    refresh_token: String,
+    provider: Option<String>,
+) -> Result<Account, String> {
+    println!("Adding account by refresh (desktop API)");
+    
+    let refresh_result = refresh_token_desktop(&refresh_token).await?;
+    let access_token = refresh_result.access_token;
+    let new_refresh_token = refresh_result.refresh_token;
+    
+    let usage_call = get_usage_limits_desktop(&access_token).await;
+    let (usage_result, ban_reason) = match &usage_call {
+        Ok(usage) => (Some(usage.clone()), None),
+        Err(e) if e.starts_with("BANNED:") => (None, Some(e.strip_prefix("BANNED:").unwrap_or("UNKNOWN").to_string())),
+        Err(_) => (None, None),
+    };
+    let usage_data = serde_json::to_value(&usage_result).unwrap_or(serde_json::Value::Null);
+    let is_banned = ban_reason.is_some();
+    
+    let email = usage_result.as_ref()
+        .and_then(|u| u.user_info.as_ref())
+        .and_then(|u| u.email.clone())
+        .unwrap_or_else(|| "unknown@kiro.dev".to_string());
+    let user_id = usage_result.as_ref()
+        .and_then(|u| u.user_info.as_ref())
+        .and_then(|u| u.user_id.clone());
+    
+    let idp = provider.unwrap_or_else(|| {
+        if email.contains("gmail") { "Google".to_string() }
+        else if email.contains("github") { "Github".to_string() }
+        else { "Google".to_string() }
+    });
+    
+    let mut store = store.lock().unwrap();
+    
+    // 按 email + provider 去重
+    let account = if let Some(existing) = store.accounts.iter_mut().find(|a| a.email == email && a.provider.as_deref() == Some(&idp)) {
+        existing.access_token = Some(access_token.clone());
+        existing.refresh_token = Some(new_refresh_token);
+        existing.user_id = user_id;
+        existing.usage_data = Some(usage_data);
+        existing.status = if is_banned { "已封禁".to_string() } else { "正常".to_string() };
+        existing.clone()
+    } else {
+        let mut account = Account::new(email.clone(), format!("Kiro {} 账号", idp));
+        account.access_token = Some(access_token.clone());
+        account.refresh_token = Some(new_refresh_token);
+        account.provider = Some(idp.clone());
+        account.user_id = user_id;
+        account.usage_data = Some(usage_data);
+        account.status = if is_banned { "已封禁".to_string() } else { "正常".to_string() };
+        store.accounts.insert(0, account.clone());
+        account
+    };
+    
+    store.save_to_file();
+    
+    Ok(account)
+}
+
+/// 手动添加 BuilderId 账号
+pub async fn add_account_by_idc(
+    store: &Mutex<AccountStore>,
+    refresh_token: String,
+    client_id: String,
+    client_secret: String,
+    region: Option<String>,
+) -> Result<Account, String> {
+    let region = region.unwrap_or_else(|| "us-east-1".to_string());
+    let metadata = RefreshMetadata {
+        client_id: Some(client_id.clone()),
+        client_secret: Some(client_secret.clone()),
+        region: Some(region.clone()),
+        ..Default::default()
+    };
+    
+    let idc_provider = IdcProvider::new("BuilderId", &region, None);
+    let auth_result = idc_provider.refresh_token(&refresh_token, metadata).await?;
+    
+    let machine_id = get_machine_id();
+    let cw_client = CodeWhispererClient::new(&machine_id);
+    let usage_call = cw_client.get_usage_limits(&auth_result.access_token).await;
+    let (usage, is_banned) = match &usage_call {
+        Ok(u) => (Some(u.clone()), false),
+        Err(e) if e.starts_with("BANNED:") => (None, true),
+        Err(_) => (None, false),
+    };
+    let usage_data = serde_json::to_value(&usage).unwrap_or(serde_json::Value::Null);
+    
+    let email = usage.as_ref()
+        .and_then(|u| u.user_info.as_ref())
+        .and_then(|u| u.email.clone())
+        .unwrap_or_else(|| "builderid@kiro.dev".to_string());
+    let user_id = usage.as_ref()
+        .and_then(|u| u.user_info.as_ref())
+        .and_then(|u| u.user_id.clone());
+    
+    let client_id_hash = builder_id_client_id_hash();
+    
+    let expires_at = chrono::Local::now() + chrono::Duration::seconds(auth_result.expires_in);
+    
+    let mut store = store.lock().unwrap();
+    
+    // 按 email + provider 去重
+    let account = if let Some(existing) = store.accounts.iter_mut().find(|a| a.email == email && a.provider.as_deref() == Some("BuilderId")) {
+        existing.access_token = Some(auth_result.access_token);
+        existing.refresh_token = Some(auth_result.refresh_token);
+        existing.user_id = user_id;
+        existing.expires_at = Some(expires_at.format("%Y/%m/%d %H:%M:%S").to_string());
+        existing.client_id = Some(client_id);
+        existing.client_secret = Some(client_secret);
+        existing.region = Some(region);
+        existing.client_id_hash = Some(client_id_hash);
+        existing.id_token = auth_result.id_token;
+        existing.sso_session_id = auth_result.sso_session_id;
+        existing.usage_data = Some(usage_data);
+        existing.status = if is_banned { "已封禁".to_string() } else { "正常".to_string() };
+        existing.clone()
+    } else {
+        let mut account = Account::new(email.clone(), "Kiro BuilderId 账号".to_string());
+        account.access_token = Some(auth_result.access_token);
+        account.refresh_token = Some(auth_result.refresh_token);
+        account.provider = Some("BuilderId".to_string());
+        account.user_id = user_id;
+        account.expires_at = Some(expires_at.format("%Y/%m/%d %H:%M:%S").to_string());
+        account.client_id = Some(client_id);
+        account.client_secret = Some(client_secret);
+        account.region = Some(region);
+        account.client_id_hash = Some(client_id_hash);
+        account.id_token = auth_result.id_token;
+        account.sso_session_id = auth_result.sso_session_id;
+        account.usage_data = Some(usage_data);
+        account.status = if is_banned { "已封禁".to_string() } else { "正常".to_string() };
+        store.accounts.insert(0, account.clone());
+        account
+    };
+    
+    store.save_to_file();
+    
+    Ok(account)
+}
+
+/// 添加本地 Kiro IDE 账号
+pub async fn add_local_kiro_account(store: &Mutex<AccountStore>) -> Result<Account, String> {
+    let local_token = get_kiro_local_token(None)
+        .ok_or("未找到本地 Kiro 账号，请先在 Kiro IDE 中登录")?;
+    
+    let refresh_token = local_token.refresh_token
+        .ok_or("本地账号缺少 refresh_token")?;
+    
+    let auth_method = local_token.auth_method.as_deref().unwrap_or("social");
+    let provider = local_token.provider.clone().unwrap_or_else(|| "Google".to_string());
+    
+    // 根据 auth_method 调用对应的添加函数
+    if auth_method == "IdC" {
+        let hash = local_token.client_id_hash.clone()
+            .ok_or("IdC 账号缺少 clientIdHash")?;
+        let region = local_token.region.clone().unwrap_or_else(|| "us-east-1".to_string());
+        
+        let client_reg = get_client_registration(None, &hash)
+            .ok_or(format!("未找到客户端注册信息: {}.json", hash))?;
+        
+        add_account_by_idc(
+            store,
+            refresh_token,
+            client_reg.client_id,
+            client_reg.client_secret,
+            Some(region),
+        ).await
+    } else {
+        add_account_by_social(
+            store,
+            refresh_token,
+            Some(provider),
+        ).await
+    }
+}
+
+/// 登录结果（调用方据此更新当前用户并通知界面）
+#[derive(Debug, Clone)]
+pub struct LoginResult {
+    pub account: Account,
+    pub provider: String,
+    pub message: String,
+}
+
+/// 通过浏览器登录指定 provider 并保存账号（按 email + provider 去重）
+pub async fn login(store: &Mutex<AccountStore>, provider: &str) -> Result<LoginResult, String> {
+    let config = get_provider_config(provider)
+        .ok_or_else(|| format!("Unsupported provider: {}", provider))?;
+
+    match config.auth_method {
+        AuthMethod::Social => login_social(store, &config).await,
+        AuthMethod::Idc => login_idc(store, &config).await,
+    }
+}
+
+async fn login_social(store: &Mutex<AccountStore>, config: &ProviderConfig) -> Result<LoginResult, String> {
+    let social_provider = create_social_provider(config);
+    let provider_id = social_provider.get_provider_id().to_string();
+    let auth_method = social_provider.get_auth_method();
+    
+    let auth_result = social_provider.login().await?;
+    
+    // 获取 usage，失败不影响登录（账号可能被暂停但仍可保存）
+    let usage = get_usage_limits_desktop(&auth_result.access_token).await.ok();
+    let usage_data = serde_json::to_value(&usage).unwrap_or(serde_json::Value::Null);
+
+    // 优先从 usage 获取 email，否则用默认值
+    let email = usage.as_ref()
+        .and_then(|u| u.user_info.as_ref())
+        .and_then(|ui| ui.email.clone())
+        .unwrap_or_else(|| format!("user@{}.social", provider_id.to_lowercase()));
+    let user_id = usage.as_ref()
+        .and_then(|u| u.user_info.as_ref())
+        .and_then(|ui| ui.user_id.clone());
+
+    let mut store = store.lock().unwrap();
+    
+    // 按 email + provider 去重
+    let account = if let Some(existing) = store.accounts.iter_mut().find(|a| a.email == email && a.provider.as_deref() == Some(&provider_id)) {
+        // 更新现有账号
+        existing.access_token = Some(auth_result.access_token.clone());
+        existing.refresh_token = Some(auth_result.refresh_token.clone());
+        existing.user_id = user_id;
+        existing.expires_at = Some(auth_result.expires_at.clone());
+        existing.profile_arn = auth_result.profile_arn;
+        existing.label = format!("Kiro {} 账号", provider_id);
+        // 不覆盖 csrfToken，保留 Web OAuth 的
+        existing.usage_data = Some(usage_data);
+        existing.status = "正常".to_string();
+        existing.clone()
+    } else {
+        // 新建账号
+        let mut account = Account::new(email.clone(), format!("Kiro {} 账号", provider_id));
+        account.access_token = Some(auth_result.access_token.clone());
+        account.refresh_token = Some(auth_result.refresh_token.clone());
+        account.provider = Some(provider_id.clone());
+        account.user_id = user_id;
+        account.expires_at = Some(auth_result.expires_at.clone());
+        account.profile_arn = auth_result.profile_arn;
+        account.csrf_token = auth_result.csrf_token;
+        account.usage_data = Some(usage_data);
+        store.accounts.insert(0, account.clone());
+        account
+    };
+    
+    store.save_to_file();
+    println!("\n[{}] LOGIN SUCCESS: {}", auth_method, account.email);
+
+    Ok(LoginResult {
+        account,
+        message: format!("{} login completed for {}", auth_method, provider_id),
+        provider: provider_id,
+    })
+}
+
+async fn login_idc(store: &Mutex<AccountStore>, config: &ProviderConfig) -> Result<LoginResult, String> {
+    let idc_provider = create_idc_provider(config);
+    let provider_id = idc_provider.get_provider_id().to_string();
+    let auth_method = idc_provider.get_auth_method();
+    
+    let auth_result = idc_provider.login().await?;
+
+    let machine_id = get_machine_id();
+    let cw_client = CodeWhispererClient::new(&machine_id);
+    let usage_call = cw_client.get_usage_limits(&auth_result.access_token).await;
+    let (usage, is_banned) = match &usage_call {
+        Ok(u) => (Some(u.clone()), false),
+        Err(e) if e.starts_with("BANNED:") => (None, true),
+        Err(_) => (None, false),
+    };
+    let usage_data = serde_json::to_value(&usage).unwrap_or(serde_json::Value::Null);
+
+    let email = usage.as_ref()
+        .and_then(|u| u.user_info.as_ref())
+        .and_then(|ui| ui.email.clone())
+        .unwrap_or_else(|| "user@builder.id".to_string());
+    let user_id = usage.as_ref()
+        .and_then(|u| u.user_info.as_ref())
+        .and_then(|ui| ui.user_id.clone());
+
+    let mut store = store.lock().unwrap();
+    
+    // 按 email + provider 去重
+    let account = if let Some(existing) = store.accounts.iter_mut().find(|a| a.email == email && a.provider.as_deref() == Some(&provider_id)) {
+        existing.access_token = Some(auth_result.access_token.clone());
+        existing.refresh_token = Some(auth_result.refresh_token.clone());
+        existing.user_id = user_id;
+        existing.expires_at = Some(auth_result.expires_at.clone());
+        existing.client_id_hash = auth_result.client_id_hash;
+        existing.client_id = auth_result.client_id;
+        existing.client_secret = auth_result.client_secret;
+        existing.region = auth_result.region;
+        existing.sso_session_id = auth_result.sso_session_id;
+        existing.id_token = auth_result.id_token;
+        existing.profile_arn = auth_result.profile_arn;
+        existing.usage_data = Some(usage_data);
+        existing.status = if is_banned { "已封禁".to_string() } else { "正常".to_string() };
+        existing.clone()
+    } else {
+        let mut account = Account::new(email.clone(), format!("Kiro {} 账号", provider_id));
+        account.access_token = Some(auth_result.access_token.clone());
+        account.refresh_token = Some(auth_result.refresh_token.clone());
+        account.provider = Some(provider_id.clone());
+        account.user_id = user_id;
+        account.expires_at = Some(auth_result.expires_at.clone());
+        account.client_id_hash = auth_result.client_id_hash;
+        account.client_id = auth_result.client_id;
+        account.client_secret = auth_result.client_secret;
+        account.region = auth_result.region;
+        account.sso_session_id = auth_result.sso_session_id;
+        account.id_token = auth_result.id_token;
+        account.profile_arn = auth_result.profile_arn;
+        account.usage_data = Some(usage_data);
+        account.status = if is_banned { "已封禁".to_string() } else { "正常".to_string() };
+        store.accounts.insert(0, account.clone());
+        account
+    };
+    
+    store.save_to_file();
+    println!("\n[{}] LOGIN SUCCESS: {}", auth_method, account.email);
+
+    Ok(LoginResult {
+        message: format!("{} login completed for {}", auth_method, email),
+        account,
+        provider: provider_id,
+    })
+}
+
+/// 用 kiro:// 回调中的授权码换取 token 并保存 Social 账号（按 email 去重）
+pub async fn complete_social_callback(
+    store: &Mutex<AccountStore>,
+    code: &str,
+    code_verifier: &str,
+    machine_id: &str,
+    provider: &str,
+) -> Result<Account, String> {
+    let redirect_uri = "kiro://app/callback";
+    let token_response = auth_social::exchange_social_code_for_token(
+        code, code_verifier, redirect_uri, machine_id,
+    ).await?;
+    
+    let usage = get_usage_limits_desktop(&token_response.access_token).await.ok();
+    let usage_data = serde_json::to_value(&usage).unwrap_or(serde_json::Value::Null);
+    
+    let email = usage.as_ref()
+        .and_then(|u| u.user_info.as_ref())
+        .and_then(|ui| ui.email.clone())
+        .unwrap_or_else(|| format!("user@{}.com", provider.to_lowercase()));
+    let user_id = usage.as_ref()
+        .and_then(|u| u.user_info.as_ref())
+        .and_then(|ui| ui.user_id.clone());
+
+    let mut store = store.lock().unwrap();
+    
+    let account = if let Some(existing) = store.accounts.iter_mut().find(|a| a.email == email) {
+        existing.access_token = Some(token_response.access_token.clone());
+        existing.refresh_token = Some(token_response.refresh_token.clone());
+        existing.provider = Some(provider.to_string());
+        existing.user_id = user_id;
+        existing.usage_data = Some(usage_data);
+        existing.status = "正常".to_string();
+        existing.clone()
+    } else {
+        let mut account = Account::new(email.clone(), format!("Kiro {} 账号", provider));
+        account.access_token = Some(token_response.access_token.clone());
+        account.refresh_token = Some(token_response.refresh_token.clone());
+        account.provider = Some(provider.to_string());
+        account.user_id = user_id;
+        account.usage_data = Some(usage_data);
+        store.accounts.insert(0, account.clone());
+        account
+    };
+    
+    store.save_to_file();
+    println!("Social callback login completed: {}", email);
+    Ok(account)
+}
+
+/// 用已有的 token 添加账号（email 以 usage 返回的为准，按 email 去重）
+pub async fn add_account_by_tokens(
+    store: &Mutex<AccountStore>,
+    email: String,
+    access_token: String,
+    refresh_token: String,
+    csrf_token: String,
+    idp: String,
+) -> Account {
+    println!("Adding Kiro account: email={}, idp={}", email, idp);
+    
+    let usage = if !access_token.is_empty() {
+        get_usage_limits_desktop(&access_token).await.ok()
+    } else {
+        None
+    };
+    let usage_data = serde_json::to_value(&usage).unwrap_or(serde_json::Value::Null);
+    
+    let final_email = usage.as_ref()
+        .and_then(|u| u.user_info.as_ref())
+        .and_then(|ui| ui.email.clone())
+        .unwrap_or(email);
+    let user_id = usage.as_ref()
+        .and_then(|u| u.user_info.as_ref())
+        .and_then(|ui| ui.user_id.clone());
+
+    let mut store = store.lock().unwrap();
+    
+    let account = if let Some(existing) = store.accounts.iter_mut().find(|a| a.email == final_email) {
+        existing.access_token = Some(access_token);
+        existing.refresh_token = Some(refresh_token);
+        existing.provider = Some(idp);
+        existing.user_id = user_id;
+        existing.csrf_token = Some(csrf_token);
+        existing.usage_data = Some(usage_data);
+        existing.status = "正常".to_string();
+        existing.clone()
+    } else {
+        let mut account = Account::new(final_email.clone(), format!("Kiro {} 账号", idp));
+        account.access_token = Some(access_token);
+        account.refresh_token = Some(refresh_token);
+        account.provider = Some(idp);
+        account.user_id = user_id;
+        account.csrf_token = Some(csrf_token);
+        account.usage_data = Some(usage_data);
+        store.accounts.insert(0, account.clone());
+        account
+    };
+    
+    store.save_to_file();
+    account
+}
+
+/// 设备授权信息（用于自动注册）
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceAuthInfo {
+    pub verification_uri: String,
+    pub verification_uri_complete: Option<String>,
+    pub user_code: String,
+    pub device_code: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub expires_in: i64,
+    pub interval: i64,
+}
+
+impl DeviceAuthInfo {
+    /// 用户需要打开的授权地址
+    pub fn url(&self) -> String {
+        self.verification_uri_complete.clone()
+            .unwrap_or_else(|| self.verification_uri.clone())
+    }
+}
+
+/// 单次轮询设备授权的结果
+#[derive(Debug, Clone)]
+pub enum DeviceAuthPoll {
+    /// 授权成功，账号已保存
+    Success(Box<Account>),
+    Pending,
+    SlowDown,
+    Expired,
+    Denied,
+}
+
+/// 注册 OIDC 客户端并发起 BuilderId 设备授权（不打开浏览器，不轮询）
+pub async fn start_device_auth(region: &str) -> Result<DeviceAuthInfo, String> {
+    println!("[DeviceAuth] Getting device auth URL (region: {})", region);
+    
+    let sso_client = AWSSSOClient::new(region);
+    
+    // Step 1: 注册客户端
+    let client_reg = sso_client.register_device_client(BUILDER_ID_START_URL).await?;
+    println!("[DeviceAuth] Client registered: {}", &client_reg.client_id);
+    
+    // Step 2: 发起设备授权
+    let device_auth = sso_client.start_device_authorization(
+        &client_reg.client_id,
+        &client_reg.client_secret,
+        BUILDER_ID_START_URL,
+    ).await?;
+    
+    let info = DeviceAuthInfo {
+        verification_uri: device_auth.verification_uri,
+        verification_uri_complete: device_auth.verification_uri_complete,
+        user_code: device_auth.user_code,
+        device_code: device_auth.device_code,
+        client_id: client_reg.client_id,
+        client_secret: client_reg.client_secret,
+        expires_in: device_auth.expires_in,
+        interval: device_auth.interval.unwrap_or(5),
+    };
+    println!("[DeviceAuth] URL: {}", info.url());
+    println!("[DeviceAuth] User Code: {}", info.user_code);
+    Ok(info)
+}
+
+/// 轮询一次设备授权，成功时保存账号
+pub async fn poll_device_auth(
+    store: &Mutex<AccountStore>,
+    device_code: &str,
+    client_id: &str,
+    client_secret: &str,
+    region: &str,
+) -> Result<DeviceAuthPoll, String> {
+    let sso_client = AWSSSOClient::new(region);
+    
+    Ok(match sso_client.poll_device_token(client_id, client_secret, device_code).await? {
+        DevicePollResult::Success(token) => {
+            println!("[DeviceAuth] Authorization successful!");
+            DeviceAuthPoll::Success(Box::new(save_device_auth_account(store, token, client_id, client_secret, region).await))
+        }
+        DevicePollResult::Pending => DeviceAuthPoll::Pending,
+        DevicePollResult::SlowDown => DeviceAuthPoll::SlowDown,
+        DevicePollResult::Expired => DeviceAuthPoll::Expired,
+        DevicePollResult::Denied => DeviceAuthPoll::Denied,
+    })
+}
+
+/// 授权成功后获取用户信息并保存 BuilderId 账号（按 email + provider 去重）
+pub async fn save_device_auth_account(
+    store: &Mutex<AccountStore>,
+    token: TokenResponse,
+    client_id: &str,
+    client_secret: &str,
+    region: &str,
+) -> Account {
+    let client_id_hash = builder_id_client_id_hash();
+
+    // 获取用户信息
+    let machine_id = get_machine_id();
+    let cw_client = CodeWhispererClient::new(&machine_id);
+    let usage = cw_client.get_usage_limits(&token.access_token).await.ok();
+    let usage_data = serde_json::to_value(&usage).unwrap_or(serde_json::Value::Null);
+
+    let email = usage.as_ref()
+        .and_then(|u| u.user_info.as_ref())
+        .and_then(|ui| ui.email.clone())
+        .unwrap_or_else(|| "user@builder.id".to_string());
+    let user_id = usage.as_ref()
+        .and_then(|u| u.user_info.as_ref())
+        .and_then(|ui| ui.user_id.clone());
+
+    let expires_at = chrono::Local::now() + chrono::Duration::seconds(token.expires_in);
+
+    let mut store = store.lock().unwrap();
+    let account = if let Some(existing) = store.accounts.iter_mut()
+        .find(|a| a.email == email && a.provider.as_deref() == Some("BuilderId"))
+    {
+        existing.access_token = Some(token.access_token.clone());
+        existing.refresh_token = Some(token.refresh_token.clone());
+        existing.user_id = user_id;
+        existing.expires_at = Some(expires_at.format("%Y/%m/%d %H:%M:%S").to_string());
+        existing.client_id_hash = Some(client_id_hash);
+        existing.client_id = Some(client_id.to_string());
+        existing.client_secret = Some(client_secret.to_string());
+        existing.region = Some(region.to_string());
+        existing.sso_session_id = token.aws_sso_app_session_id;
+        existing.id_token = token.id_token;
+        existing.usage_data = Some(usage_data);
+        existing.status = "正常".to_string();
+        existing.clone()
+    } else {
+        let mut account = Account::new(email.clone(), "Kiro BuilderId 账号".to_string());
+        account.access_token = Some(token.access_token.clone());
+        account.refresh_token = Some(token.refresh_token.clone());
+        account.provider = Some("BuilderId".to_string());
+        account.user_id = user_id;
+        account.expires_at = Some(expires_at.format("%Y/%m/%d %H:%M:%S").to_string());
+        account.client_id_hash = Some(client_id_hash);
+        account.client_id = Some(client_id.to_string());
+        account.client_secret = Some(client_secret.to_string());
+        account.region = Some(region.to_string());
+        account.sso_session_id = token.aws_sso_app_session_id;
+        account.id_token = token.id_token;
+        account.usage_data = Some(usage_data);
+        store.accounts.insert(0, account.clone());
+        account
+    };
+
+    store.save_to_file();
+    println!("[DeviceAuth] Account saved: {}", email);
+    account
+}
+
+/// 完成 Web OAuth 登录：校验回调地址、换取 token 并保存账号（按 email 去重）
+pub async fn complete_web_oauth(
+    store: &Mutex<AccountStore>,
+    init_result: &WebOAuthInitResult,
+    callback_url: &str,
+) -> Result<Account, String> {
+    let url = url::Url::parse(callback_url)
+        .map_err(|e| format!("Invalid callback URL: {}", e))?;
+    
+    let code = url.query_pairs()
+        .find(|(k, _)| k == "code")
+        .map(|(_, v)| v.to_string())
+        .ok_or("No 'code' parameter in callback URL")?;
+    
+    let returned_state = url.query_pairs()
+        .find(|(k, _)| k == "state")
+        .map(|(_, v)| v.to_string())
+        .ok_or("No 'state' parameter in callback URL")?;
+    
+    let web_provider = WebOAuthProvider::new(&init_result.provider_id);
+    let auth_result = web_provider.complete_login(
+        &code,
+        &returned_state,
+        &init_result.code_verifier,
+        &init_result.state,
+    ).await?;
+
+    let csrf_token = auth_result.csrf_token.as_ref()
+        .ok_or("No csrf_token from ExchangeToken")?;
+    let refresh_token = &auth_result.refresh_token;
+
+    let portal_client = KiroWebPortalClient::new();
+    let user_info = portal_client.get_user_info(
+        &auth_result.access_token,
+        csrf_token,
+        refresh_token,
+        &init_result.idp,
+    ).await?;
+
+    let provider = &init_result.provider_id;
+    let email = user_info.email.clone()
+        .ok_or("No email in GetUserInfo response")?;
+    let user_id = user_info.user_id.clone();
+
+    let usage = portal_client.get_user_usage_and_limits(
+        &auth_result.access_token,
+        csrf_token,
+        refresh_token,
+        &init_result.idp,
+    ).await?;
+    let usage_data = serde_json::to_value(&usage).unwrap_or(serde_json::Value::Null);
+
+    let mut store = store.lock().unwrap();
+    
+    let account = if let Some(existing) = store.accounts.iter_mut().find(|a| a.email == email) {
+        // 更新现有账号
+        existing.access_token = Some(auth_result.access_token.clone());
+        existing.refresh_token = Some(auth_result.refresh_token.clone());
+        existing.provider = Some(provider.clone());
+        existing.user_id = user_id;
+        existing.expires_at = Some(auth_result.expires_at.clone());
+        existing.profile_arn = auth_result.profile_arn.clone();
+        existing.csrf_token = auth_result.csrf_token.clone();
+        existing.usage_data = Some(usage_data);
+        existing.status = "正常".to_string();
+        existing.clone()
+    } else {
+        // 新建账号
+        let mut account = Account::new(email.clone(), format!("Kiro {} (Web OAuth)", provider));
+        account.access_token = Some(auth_result.access_token.clone());
+        account.refresh_token = Some(auth_result.refresh_token.clone());
+        account.provider = Some(provider.clone());
+        account.user_id = user_id;
+        account.expires_at = Some(auth_result.expires_at.clone());
+        account.profile_arn = auth_result.profile_arn.clone();
+        account.csrf_token = auth_result.csrf_token.clone();
+        account.usage_data = Some(usage_data);
+        store.accounts.insert(0, account.clone());
+        account
+    };
+    
+    store.save_to_file();
+    println!("[WebOAuth] LOGIN SUCCESS: email={}, provider={}", account.email, provider);
+    Ok(account)
+}
+
+/// 刷新 Web OAuth 账号的 token 与 usage
+pub async fn refresh_web_oauth_account(store: &Mutex<AccountStore>, account_id: &str) -> Result<Account, String> {
+    let account = {
+        let store = store.lock().unwrap();
+        store.accounts.iter()
+            .find(|a| a.id == account_id)
+            .cloned()
+            .ok_or("Account not found")?
+    };
+
+    // Web OAuth 账号必须有 csrfToken
+    if account.csrf_token.is_none() {
+        return Err("This account is not a Web OAuth account (no csrfToken)".to_string());
+    }
+
+    let access_token = account.access_token.as_ref().ok_or("No access_token found")?;
+    let csrf_token = account.csrf_token.as_ref().ok_or("No csrf_token found")?;
+    let provider = account.provider.as_ref().ok_or("No provider found")?;
+    
+    let refresh_token = account.refresh_token.as_ref().ok_or("No refresh_token found")?;
+    let web_provider = WebOAuthProvider::new(provider);
+    let auth_result = web_provider.refresh_token_impl(access_token, csrf_token, refresh_token).await?;
+
+    let new_csrf = auth_result.csrf_token.clone();
+    
+    let portal_client = KiroWebPortalClient::new();
+    let usage = portal_client.get_user_usage_and_limits(
+        &auth_result.access_token,
+        new_csrf.as_deref().unwrap_or(""),
+        refresh_token,
+        provider,
+    ).await.ok();
+    let usage_data = serde_json::to_value(&usage).unwrap_or(serde_json::Value::Null);
+
+    let mut store = store.lock().unwrap();
+    if let Some(a) = store.accounts.iter_mut().find(|a| a.id == account_id) {
+        a.access_token = Some(auth_result.access_token);
+        a.refresh_token = Some(auth_result.refresh_token);
+        a.csrf_token = auth_result.csrf_token;
+        a.expires_at = Some(auth_result.expires_at);
+        a.usage_data = Some(usage_data);
+        a.status = "正常".to_string();
+        if auth_result.profile_arn.is_some() {
+            a.profile_arn = auth_result.profile_arn;
+        }
+        
+        let result = a.clone();
+        store.save_to_file();
+        println!("[WebOAuth] Account refreshed: {}", result.email);
+        return Ok(result);
+    }
+
+    Err("Account not found after refresh".to_string())
+}
+
+/// 从 x-amz-sso_authn Cookie 的 Bearer Token 导入 BuilderId 账号（按 email 去重）
+pub async fn import_from_sso_token(
+    store: &Mutex<AccountStore>,
+    bearer_token: &str,
+    region: Option<String>,
+) -> Result<Account, String> {
+    let region = region.unwrap_or_else(|| "us-east-1".to_string());
+    let tokens = sso_import::exchange_sso_token(bearer_token, &region).await?;
+
+    // 获取用量信息
+    let machine_id = get_machine_id();
+    let cw_client = CodeWhispererClient::new(&machine_id);
+    
+    let usage = cw_client.get_usage_limits(&tokens.access_token).await.ok();
+    let usage_data = serde_json::to_value(&usage).unwrap_or(serde_json::Value::Null);
+    
+    // 从 usage 中提取 email
+    let email = usage.as_ref()
+        .and_then(|u| u.user_info.as_ref())
+        .and_then(|ui| ui.email.clone())
+        .unwrap_or_else(|| "unknown@builderid".to_string());
+    
+    let user_id = usage.as_ref()
+        .and_then(|u| u.user_info.as_ref())
+        .and_then(|ui| ui.user_id.clone());
+
+    let client_id_hash = builder_id_client_id_hash();
+    let expires_at = chrono::Utc::now() + chrono::Duration::hours(1);
+    
+    let mut store = store.lock().map_err(|e| format!("锁定存储失败: {}", e))?;
+    
+    // 检查是否已存在
+    let account = if let Some(existing) = store.accounts.iter_mut().find(|a| a.email == email) {
+        existing.access_token = Some(tokens.access_token);
+        existing.refresh_token = Some(tokens.refresh_token);
+        existing.client_id = Some(tokens.client_id);
+        existing.client_secret = Some(tokens.client_secret);
+        existing.client_id_hash = Some(client_id_hash);
+        existing.region = Some(region);
+        existing.expires_at = Some(expires_at.to_rfc3339());
+        existing.usage_data = Some(usage_data);
+        existing.status = "正常".to_string();
+        existing.user_id = user_id;
+        existing.clone()
+    } else {
+        let mut account = Account::new(email.clone(), email.clone());
+        account.provider = Some("BuilderId".to_string());
+        account.access_token = Some(tokens.access_token);
+        account.refresh_token = Some(tokens.refresh_token);
+        account.client_id = Some(tokens.client_id);
+        account.client_secret = Some(tokens.client_secret);
+        account.client_id_hash = Some(client_id_hash);
+        account.region = Some(region);
+        account.expires_at = Some(expires_at.to_rfc3339());
+        account.usage_data = Some(usage_data);
+        account.user_id = user_id;
+        store.accounts.insert(0, account.clone());
+        account
+    };
+    
+    store.save_to_file();
+    Ok(account)
+}
@@ -23,6 +23,12 @@ pub struct AuthState {
     pub refresh_token: Mutex<Option<String>>,
 }
 
+impl Default for AuthState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AuthState {
     pub fn new() -> Self {
         Self {
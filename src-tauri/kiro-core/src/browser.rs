@@ -1,6 +1,6 @@
 // 浏览器打开工具
 
-use crate::commands::app_settings_cmd::get_browser_path;
+use crate::settings::get_browser_path;
 use serde::Serialize;
 
 #[cfg(target_os = "windows")]
@@ -97,11 +97,11 @@ fn open_with_custom_browser(browser_path: &str, url: &str) -> Result<(), String>
         return Err("浏览器路径为空".to_string());
     }
 
-    let (exe_path, rest) = if browser_path.starts_with('"') {
+    let (exe_path, rest) = if let Some(quoted) = browser_path.strip_prefix('"') {
         // 路径被引号包裹: "C:\Program Files\...\chrome.exe" --incognito
-        if let Some(end_quote) = browser_path[1..].find('"') {
-            let path = &browser_path[1..=end_quote];
-            let remaining = browser_path[end_quote + 2..].trim();
+        if let Some(end_quote) = quoted.find('"') {
+            let path = &quoted[..end_quote];
+            let remaining = quoted[end_quote + 1..].trim();
             (path, remaining)
         } else {
             // 没有结束引号，整个当作路径
@@ -153,11 +153,3 @@ fn open_with_default_browser(url: &str) -> Result<(), String> {
 
     Ok(())
 }
-
-
-// ===== Tauri Command =====
-
-#[tauri::command]
-pub async fn detect_installed_browsers() -> Vec<DetectedBrowser> {
-    detect_browsers()
-}
@@ -0,0 +1,229 @@
+// 领域事件总线 - 推送给本地 API 的 SSE 订阅者，以及注册的监听器（GUI 用来转发给 WebView）
+
+use std::collections::VecDeque;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Mutex, OnceLock};
+use schemars::JsonSchema;
+use serde::Serialize;
+use crate::account::{diff_accounts, Account};
+
+/// 保留的历史事件数量（用于断线重连后补发）
+const HISTORY_SIZE: usize = 500;
+
+/// 领域事件
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DomainEvent {
+    #[serde(rename_all = "camelCase")]
+    AccountAdded { account_id: String, email: String },
+    #[serde(rename_all = "camelCase")]
+    AccountUpdated { account_id: String, email: String },
+    #[serde(rename_all = "camelCase")]
+    AccountDeleted { account_id: String, email: String },
+    #[serde(rename_all = "camelCase")]
+    TokenRefreshed { account_id: String, expires_at: Option<String> },
+    #[serde(rename_all = "camelCase")]
+    UsageChanged { account_id: String },
+    #[serde(rename_all = "camelCase")]
+    IdeSwitched { account_id: Option<String>, email: Option<String>, provider: String },
+    #[serde(rename_all = "camelCase")]
+    DeviceAuth { state: DeviceAuthState, url: Option<String>, email: Option<String> },
+    #[serde(rename_all = "camelCase")]
+    IdeShutdown { stage: IdeShutdownStage, pids: Vec<u32>, installation_id: Option<String> },
+}
+
+impl DomainEvent {
+    /// SSE 的 event 字段
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::AccountAdded { .. } => "account_added",
+            Self::AccountUpdated { .. } => "account_updated",
+            Self::AccountDeleted { .. } => "account_deleted",
+            Self::TokenRefreshed { .. } => "token_refreshed",
+            Self::UsageChanged { .. } => "usage_changed",
+            Self::IdeSwitched { .. } => "ide_switched",
+            Self::DeviceAuth { .. } => "device_auth",
+            Self::IdeShutdown { .. } => "ide_shutdown",
+        }
+    }
+}
+
+/// 设备授权状态
+#[derive(Debug, Clone, Copy, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceAuthState {
+    Started,
+    Succeeded,
+    Expired,
+    Denied,
+    Cancelled,
+}
+
+/// 关闭 IDE 的阶段
+#[derive(Debug, Clone, Copy, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IdeShutdownStage {
+    /// 已请求 IDE 正常退出
+    CloseRequested,
+    /// IDE 已正常退出
+    Closed,
+    /// 超时仍未退出，等待用户确认是否强制关闭
+    TimedOut,
+    /// 用户确认后已强制关闭
+    ForceKilled,
+}
+
+/// 带序号的事件记录
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct EventRecord {
+    pub seq: u64,
+    pub timestamp: String,
+    #[serde(flatten)]
+    pub event: DomainEvent,
+}
+
+struct EventBus {
+    next_seq: u64,
+    history: VecDeque<EventRecord>,
+    subscribers: Vec<Sender<EventRecord>>,
+}
+
+static EVENT_BUS: Mutex<EventBus> = Mutex::new(EventBus {
+    next_seq: 1,
+    history: VecDeque::new(),
+    subscribers: Vec::new(),
+});
+
+type Listener = Box<dyn Fn(&EventRecord) + Send + Sync>;
+
+static LISTENER: OnceLock<Listener> = OnceLock::new();
+
+/// 最近一次观察到的账号列表，用于生成账号变更事件
+static LAST_ACCOUNTS: Mutex<Option<Vec<Account>>> = Mutex::new(None);
+
+/// 设置事件监听器（只能设置一次），之后发布的事件都会回调
+pub fn set_listener(listener: impl Fn(&EventRecord) + Send + Sync + 'static) {
+    let _ = LISTENER.set(Box::new(listener));
+}
+
+/// 发布事件
+pub fn publish(event: DomainEvent) {
+    let record = {
+        let mut bus = EVENT_BUS.lock().unwrap();
+        let record = EventRecord {
+            seq: bus.next_seq,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            event,
+        };
+        bus.next_seq += 1;
+        if bus.history.len() >= HISTORY_SIZE {
+            bus.history.pop_front();
+        }
+        bus.history.push_back(record.clone());
+        // 发送失败说明订阅者已断开
+        bus.subscribers.retain(|tx| tx.send(record.clone()).is_ok());
+        record
+    };
+
+    if let Some(listener) = LISTENER.get() {
+        listener(&record);
+    }
+}
+
+/// 订阅结果
+pub struct Subscription {
+    /// 需要补发的历史事件
+    pub backlog: Vec<EventRecord>,
+    /// 请求的序号已不在历史中，客户端需重新拉取完整状态
+    pub missed: bool,
+    pub receiver: Receiver<EventRecord>,
+}
+
+/// 订阅事件；`since` 为客户端收到的最后一个序号
+pub fn subscribe(since: Option<u64>) -> Subscription {
+    let (tx, rx) = mpsc::channel();
+    let mut bus = EVENT_BUS.lock().unwrap();
+
+    let (backlog, missed) = match since {
+        Some(seq) => {
+            let oldest = bus.history.front().map(|r| r.seq).unwrap_or(bus.next_seq);
+            let backlog = bus.history.iter().filter(|r| r.seq > seq).cloned().collect();
+            (backlog, seq + 1 < oldest)
+        }
+        None => (Vec::new(), false),
+    };
+
+    bus.subscribers.push(tx);
+    Subscription { backlog, missed, receiver: rx }
+}
+
+/// 断开所有订阅者（HTTP 服务器停止时调用）
+pub fn disconnect_subscribers() {
+    EVENT_BUS.lock().unwrap().subscribers.clear();
+}
+
+/// 当前订阅者数量
+pub fn subscriber_count() -> usize {
+    EVENT_BUS.lock().unwrap().subscribers.len()
+}
+
+/// 与上次观察到的账号列表比较，发布新增/删除/更新事件
+///
+/// 所有 AccountStore 实例读写文件时都会调用，因此外部脚本直接修改文件也能被感知。
+/// 首次调用只记录基线，不发布事件。
+pub fn observe_accounts(accounts: &[Account]) {
+    let previous = {
+        let mut last = LAST_ACCOUNTS.lock().unwrap();
+        last.replace(accounts.to_vec())
+    };
+    let previous = match previous {
+        Some(p) => p,
+        None => return,
+    };
+
+    let diff = diff_accounts(&previous, accounts);
+    if diff.is_empty() {
+        return;
+    }
+
+    let find = |list: &[Account], id: &str| list.iter().find(|a| a.id == id).cloned();
+
+    for id in &diff.added {
+        if let Some(a) = find(accounts, id) {
+            publish(DomainEvent::AccountAdded { account_id: a.id, email: a.email });
+        }
+    }
+    for id in &diff.removed {
+        if let Some(a) = find(&previous, id) {
+            publish(DomainEvent::AccountDeleted { account_id: a.id, email: a.email });
+        }
+    }
+    for id in &diff.changed {
+        let (old, new) = match (find(&previous, id), find(accounts, id)) {
+            (Some(o), Some(n)) => (o, n),
+            _ => continue,
+        };
+        let token_changed = old.access_token != new.access_token;
+        let usage_changed = old.usage_data != new.usage_data;
+        if token_changed {
+            publish(DomainEvent::TokenRefreshed {
+                account_id: new.id.clone(),
+                expires_at: new.expires_at.clone(),
+            });
+        }
+        if usage_changed {
+            publish(DomainEvent::UsageChanged { account_id: new.id.clone() });
+        }
+        // 除 token / usage 外的其他字段变化
+        let rest_changed = Account {
+            access_token: new.access_token.clone(),
+            refresh_token: new.refresh_token.clone(),
+            expires_at: new.expires_at.clone(),
+            usage_data: new.usage_data.clone(),
+            ..old
+        } != new;
+        if rest_changed {
+            publish(DomainEvent::AccountUpdated { account_id: new.id, email: new.email });
+        }
+    }
+}
@@ -0,0 +1,114 @@
+// Kiro IDE 安装登记 - 支持稳定版与预览版并存、便携数据目录（存到 app-settings.json）
+
+use serde::{Deserialize, Serialize};
+use crate::paths::KiroPaths;
+use crate::settings::{get_app_settings_inner, save_app_settings_inner, AppSettings};
+
+/// 默认安装的 ID（系统默认位置，不需要登记）
+pub const DEFAULT_INSTALLATION_ID: &str = "default";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KiroInstallation {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    /// 可执行文件（Windows: Kiro.exe，macOS: .app 包，Linux: 可执行文件或 .desktop 文件）
+    #[serde(default)]
+    pub executable_path: Option<String>,
+    /// IDE 数据目录（包含 User/settings.json），为空时以 home_dir 或默认位置为准
+    #[serde(default)]
+    pub data_dir: Option<String>,
+    /// IDE 使用的用户目录（决定 .aws/sso/cache 的位置），启动时作为 HOME / USERPROFILE 传入
+    #[serde(default)]
+    pub home_dir: Option<String>,
+}
+
+impl KiroInstallation {
+    pub fn paths(&self) -> KiroPaths {
+        KiroPaths::for_installation(Some(self))
+    }
+
+    /// 非空的可执行文件路径
+    pub fn executable(&self) -> Option<std::path::PathBuf> {
+        self.executable_path.as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(std::path::PathBuf::from)
+    }
+
+    /// 非空的用户目录
+    pub fn home(&self) -> Option<std::path::PathBuf> {
+        self.home_dir.as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(std::path::PathBuf::from)
+    }
+}
+
+/// 已登记的安装（不含默认安装）
+pub fn list_installations() -> Vec<KiroInstallation> {
+    get_app_settings_inner()
+        .ok()
+        .and_then(|s| s.kiro_installations)
+        .unwrap_or_default()
+}
+
+/// 按 ID 查找安装；未指定或为 "default" 时返回 None，表示默认安装
+pub fn find_installation(id: Option<&str>) -> Result<Option<KiroInstallation>, String> {
+    let id = match id.map(str::trim) {
+        None | Some("") | Some(DEFAULT_INSTALLATION_ID) => return Ok(None),
+        Some(id) => id,
+    };
+    list_installations()
+        .into_iter()
+        .find(|i| i.id == id)
+        .map(Some)
+        .ok_or_else(|| format!("未找到 Kiro 安装: {}", id))
+}
+
+/// 按 ID 解析安装的路径
+pub fn installation_paths(id: Option<&str>) -> Result<KiroPaths, String> {
+    Ok(KiroPaths::for_installation(find_installation(id)?.as_ref()))
+}
+
+fn save_installations(installations: Vec<KiroInstallation>) -> Result<(), String> {
+    save_app_settings_inner(AppSettings {
+        kiro_installations: Some(installations),
+        ..Default::default()
+    })
+}
+
+/// 新增或更新安装（id 为空时新增）
+pub fn save_installation(mut installation: KiroInstallation) -> Result<KiroInstallation, String> {
+    installation.name = installation.name.trim().to_string();
+    if installation.name.is_empty() {
+        return Err("安装名称不能为空".to_string());
+    }
+    if installation.id == DEFAULT_INSTALLATION_ID {
+        return Err("默认安装不能修改，请在设置中修改路径".to_string());
+    }
+
+    let mut installations = list_installations();
+    if installation.id.is_empty() {
+        installation.id = uuid::Uuid::new_v4().to_string();
+        installations.push(installation.clone());
+    } else {
+        let existing = installations.iter_mut()
+            .find(|i| i.id == installation.id)
+            .ok_or_else(|| format!("未找到 Kiro 安装: {}", installation.id))?;
+        *existing = installation.clone();
+    }
+    save_installations(installations)?;
+    Ok(installation)
+}
+
+pub fn remove_installation(id: &str) -> Result<(), String> {
+    let mut installations = list_installations();
+    let before = installations.len();
+    installations.retain(|i| i.id != id);
+    if installations.len() == before {
+        return Err(format!("未找到 Kiro 安装: {}", id));
+    }
+    save_installations(installations)
+}
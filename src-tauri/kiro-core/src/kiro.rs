@@ -0,0 +1,427 @@
+// Kiro IDE 相关功能
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use rusqlite::{Connection, OpenFlags};
+use crate::installations::{find_installation, installation_paths};
+use crate::paths::KiroPaths;
+
+// ===== Kiro IDE 本地 Token =====
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KiroLocalToken {
+    pub access_token: Option<String>,
+    pub refresh_token: Option<String>,
+    pub expires_at: Option<String>,
+    pub auth_method: Option<String>,
+    pub provider: Option<String>,
+    // Social 专用
+    pub profile_arn: Option<String>,
+    // IdC 专用
+    pub client_id_hash: Option<String>,
+    pub region: Option<String>,
+}
+
+/// IdC 客户端注册信息 (从 {clientIdHash}.json 读取)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientRegistration {
+    pub client_id: String,
+    pub client_secret: String,
+    pub expires_at: Option<String>,
+}
+
+/// 读取 Kiro IDE 当前登录的本地 Token（installation_id 为空时读取默认安装）
+pub fn get_kiro_local_token(installation_id: Option<&str>) -> Option<KiroLocalToken> {
+    let path = installation_paths(installation_id).ok()?.auth_token_path()?;
+    
+    let content = std::fs::read_to_string(&path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// 读取 IdC 客户端注册信息
+pub fn get_client_registration(installation_id: Option<&str>, client_id_hash: &str) -> Option<ClientRegistration> {
+    let path = installation_paths(installation_id).ok()?.client_registration_path(client_id_hash)?;
+    
+    let content = std::fs::read_to_string(&path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+// ===== Kiro IDE 遥测信息 =====
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KiroTelemetryInfo {
+    pub machine_id: Option<String>,
+    pub sqm_id: Option<String>,
+    pub dev_device_id: Option<String>,
+    pub service_machine_id: Option<String>,
+}
+
+/// 读取 Kiro IDE 的遥测信息（机器 ID 等）
+pub fn get_kiro_telemetry_info_inner(installation_id: Option<&str>) -> Option<KiroTelemetryInfo> {
+    let paths = installation_paths(installation_id).ok()?;
+    // 从 storage.json 读取
+    let storage_path = paths.storage_json_path()?;
+    
+    let content = std::fs::read_to_string(&storage_path).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&content).ok()?;
+    
+    let mut info = KiroTelemetryInfo {
+        machine_id: json.get("telemetry.machineId").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        sqm_id: json.get("telemetry.sqmId").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        dev_device_id: json.get("telemetry.devDeviceId").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        service_machine_id: None,
+    };
+    
+    // 从 state.vscdb 读取 serviceMachineId
+    let db_path = paths.state_db_path()?;
+    
+    if db_path.exists() {
+        // 只读模式打开，避免被 Kiro IDE 占用时出错
+        if let Ok(conn) = Connection::open_with_flags(&db_path, OpenFlags::SQLITE_OPEN_READ_ONLY) {
+            if let Ok(value) = conn.query_row(
+                "SELECT value FROM ItemTable WHERE key = 'storage.serviceMachineId'",
+                [],
+                |row| row.get::<_, String>(0)
+            ) {
+                info.service_machine_id = Some(value);
+            }
+        }
+    }
+    
+    Some(info)
+}
+
+/// 获取默认安装的机器 ID（供其他模块使用）
+pub fn get_machine_id() -> String {
+    get_kiro_telemetry_info_inner(None)
+        .and_then(|info| info.machine_id)
+        .unwrap_or_else(|| {
+            // 如果获取失败，生成一个随机的
+            use uuid::Uuid;
+            let uuid_bytes = Uuid::new_v4();
+            use sha2::{Digest, Sha256};
+            let mut hasher = Sha256::new();
+            hasher.update(uuid_bytes.as_bytes());
+            hex::encode(hasher.finalize())
+        })
+}
+
+// ===== 切换账号 =====
+
+use crate::process::{check_kiro_running, launch_kiro, shutdown_kiro, ShutdownOutcome};
+use crate::account::{Account, AccountStore};
+use crate::events::{publish, DomainEvent};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchAccountResult {
+    pub success: bool,
+    pub message: String,
+    pub kiro_was_running: bool,
+    pub kiro_restarted: bool,
+    /// 被关闭的 IDE 进程（未关闭 IDE 时为空）
+    #[serde(default)]
+    pub closed_pids: Vec<u32>,
+    /// 重新启动的 IDE 进程（macOS 经由 open 启动，无法获取）
+    #[serde(default)]
+    pub launched_pid: Option<u32>,
+    /// IDE 未在超时内正常退出，尚未切换；用户确认后以 forceClose 重新调用
+    #[serde(default)]
+    pub awaiting_force_close: bool,
+}
+
+/// 切换账号参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchAccountParams {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub provider: String,
+    #[serde(default)]
+    pub auth_method: Option<String>,
+    // Social 专用
+    #[serde(default)]
+    pub profile_arn: Option<String>,
+    // IdC 专用
+    #[serde(default)]
+    pub client_id_hash: Option<String>,
+    #[serde(default)]
+    pub client_id: Option<String>,
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    #[serde(default)]
+    pub region: Option<String>,
+    // 选项
+    #[serde(default)]
+    pub reset_machine_id: Option<bool>,
+    #[serde(default)]
+    pub auto_restart: Option<bool>,
+    /// 用户已确认可强制关闭 IDE（可能丢失未保存内容）
+    #[serde(default)]
+    pub force_close: Option<bool>,
+    /// 目标 Kiro 安装（为空时为默认安装）
+    #[serde(default)]
+    pub installation_id: Option<String>,
+}
+
+impl SwitchAccountParams {
+    /// 按账号构建切换参数（与前端切换账号时的规则一致）
+    pub fn from_account(account: &Account, reset_machine_id: bool, auto_restart: bool) -> Result<Self, String> {
+        let access_token = account.access_token.clone().ok_or("No access token")?;
+        let refresh_token = account.refresh_token.clone().ok_or("No refresh token")?;
+        let provider = account.provider.clone().unwrap_or_else(|| "Google".to_string());
+        let is_idc = provider == "BuilderId" || provider == "Enterprise" || account.client_id_hash.is_some();
+
+        let mut params = SwitchAccountParams {
+            access_token,
+            refresh_token,
+            provider,
+            auth_method: Some(if is_idc { "IdC" } else { "social" }.to_string()),
+            profile_arn: None,
+            client_id_hash: None,
+            client_id: None,
+            client_secret: None,
+            region: None,
+            reset_machine_id: Some(reset_machine_id),
+            auto_restart: Some(auto_restart),
+            force_close: None,
+            installation_id: None,
+        };
+        if is_idc {
+            params.client_id_hash = account.client_id_hash.clone();
+            params.region = Some(account.region.clone().unwrap_or_else(|| "us-east-1".to_string()));
+            params.client_id = account.client_id.clone();
+            params.client_secret = account.client_secret.clone();
+        } else {
+            params.profile_arn = account.profile_arn.clone();
+        }
+        Ok(params)
+    }
+}
+
+/// 切换 Kiro 账号（直接写入 Token 文件，仅重置机器ID时才关闭IDE）
+pub fn switch_kiro_account_inner(params: SwitchAccountParams) -> Result<SwitchAccountResult, String> {
+    let installation = find_installation(params.installation_id.as_deref())?;
+    let installation = installation.as_ref();
+    let paths = KiroPaths::for_installation(installation);
+    let kiro_was_running = check_kiro_running(installation);
+    let should_reset = params.reset_machine_id.unwrap_or(false);
+    let should_restart = params.auto_restart.unwrap_or(true);
+    let force_close = params.force_close.unwrap_or(false);
+    let auth_method = params.auth_method.unwrap_or_else(|| "social".to_string());
+    let access_token = params.access_token;
+    let refresh_token = params.refresh_token;
+    let provider = params.provider;
+    let profile_arn = params.profile_arn;
+    let client_id_hash = params.client_id_hash;
+    let client_id = params.client_id;
+    let client_secret = params.client_secret;
+    let region = params.region;
+    
+    // 1. 只在需要重置机器 ID 时才关闭 IDE
+    let mut closed_pids = Vec::new();
+    if should_reset && kiro_was_running {
+        match shutdown_kiro(installation, force_close)? {
+            ShutdownOutcome::Closed(pids) => closed_pids = pids,
+            // IDE 可能在提示保存，先不动 token，交给用户决定是否强制关闭
+            ShutdownOutcome::AwaitingConfirmation(pids) => {
+                return Ok(SwitchAccountResult {
+                    success: false,
+                    message: format!("Kiro IDE (pid {:?}) did not close in time, it may have unsaved changes", pids),
+                    kiro_was_running,
+                    kiro_restarted: false,
+                    closed_pids: Vec::new(),
+                    launched_pid: None,
+                    awaiting_force_close: true,
+                });
+            }
+        }
+    }
+    
+    // 2. 如果需要重置机器 ID
+    if should_reset {
+        let _ = reset_machine_id_at(&paths);
+    }
+    
+    // 3. 替换 Token
+    let dir_path = paths.aws_sso_cache_dir()
+        .ok_or("Cannot find home directory")?;
+    
+    std::fs::create_dir_all(&dir_path)
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
+    
+    let file_path = dir_path.join("kiro-auth-token.json");
+    
+    let expires_at = chrono::Utc::now() + chrono::Duration::hours(1);
+    
+    // 根据 auth_method 构建不同的 token 数据
+    let token_data = if auth_method == "IdC" {
+        // IdC 账号: clientIdHash + region
+        let hash = client_id_hash.clone().unwrap_or_default();
+        let data = serde_json::json!({
+            "accessToken": access_token,
+            "refreshToken": refresh_token,
+            "expiresAt": expires_at.to_rfc3339(),
+            "authMethod": "IdC",
+            "provider": provider,
+            "clientIdHash": hash,
+            "region": region.clone().unwrap_or_else(|| "us-east-1".to_string())
+        });
+        data
+    } else {
+        // Social 账号: profileArn
+        let arn = profile_arn.unwrap_or_else(|| 
+            "arn:aws:codewhisperer:us-east-1:699475941385:profile/EHGA3GRVQMUK".to_string()
+        );
+        serde_json::json!({
+            "accessToken": access_token,
+            "refreshToken": refresh_token,
+            "profileArn": arn,
+            "expiresAt": expires_at.to_rfc3339(),
+            "authMethod": "social",
+            "provider": provider
+        })
+    };
+    
+    let content = serde_json::to_string_pretty(&token_data)
+        .map_err(|e| format!("Failed to serialize: {}", e))?;
+    
+    // 原子写入：先写临时文件，再覆盖
+    let temp_file_path = dir_path.join("kiro-auth-token.json.tmp");
+    std::fs::write(&temp_file_path, &content)
+        .map_err(|e| format!("Failed to write temp file: {}", e))?;
+    std::fs::rename(&temp_file_path, &file_path)
+        .map_err(|e| format!("Failed to rename file: {}", e))?;
+    
+    // IdC 账号还需要写入 Client Registration 文件
+    if auth_method == "IdC" {
+        if let (Some(hash), Some(cid), Some(csec)) = (client_id_hash, client_id, client_secret) {
+            let client_reg_path = dir_path.join(format!("{}.json", hash));
+            let client_reg_temp_path = dir_path.join(format!("{}.json.tmp", hash));
+            let client_expires = chrono::Utc::now() + chrono::Duration::days(90);
+            let client_reg_data = serde_json::json!({
+                "clientId": cid,
+                "clientSecret": csec,
+                "expiresAt": client_expires.to_rfc3339()
+            });
+            let client_reg_content = serde_json::to_string_pretty(&client_reg_data)
+                .map_err(|e| format!("Failed to serialize client registration: {}", e))?;
+            // 原子写入
+            std::fs::write(&client_reg_temp_path, client_reg_content)
+                .map_err(|e| format!("Failed to write client registration temp: {}", e))?;
+            std::fs::rename(&client_reg_temp_path, &client_reg_path)
+                .map_err(|e| format!("Failed to rename client registration: {}", e))?;
+        }
+    }
+    
+    // 4. 切换完成
+    let launched = if kiro_was_running && should_restart {
+        match launch_kiro(installation) {
+            Ok(pid) => Some(pid),
+            Err(e) => {
+                eprintln!("[Kiro] Failed to restart Kiro IDE: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    
+    // 按 token 反查账号，用于事件通知
+    let switched = AccountStore::new().accounts.into_iter()
+        .find(|a| a.access_token.as_deref() == Some(access_token.as_str()));
+    publish(DomainEvent::IdeSwitched {
+        account_id: switched.as_ref().map(|a| a.id.clone()),
+        email: switched.map(|a| a.email),
+        provider: provider.clone(),
+    });
+    
+    Ok(SwitchAccountResult {
+        success: true,
+        message: format!("Switched to {} ({}) account", provider, auth_method),
+        kiro_was_running,
+        kiro_restarted: launched.is_some(),
+        closed_pids,
+        launched_pid: launched.flatten(),
+        awaiting_force_close: false,
+    })
+}
+
+// ===== 重置机器 ID =====
+
+/// 生成新的机器 ID（64位十六进制字符串）
+fn generate_machine_id() -> String {
+    use sha2::{Sha256, Digest};
+    let random_bytes: [u8; 32] = rand::random();
+    let mut hasher = Sha256::new();
+    hasher.update(random_bytes);
+    hasher.update(chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0).to_le_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// 生成新的 SQM ID（GUID 格式）
+fn generate_sqm_id() -> String {
+    format!("{{{}}}", uuid::Uuid::new_v4().to_string().to_uppercase())
+}
+
+/// 生成新的 Dev Device ID（UUID 格式）
+fn generate_dev_device_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// 重置机器 ID（内部函数，公开供其他模块使用；installation_id 为空时为默认安装）
+pub fn reset_kiro_machine_id_inner(installation_id: Option<&str>) -> Result<KiroTelemetryInfo, String> {
+    reset_machine_id_at(&installation_paths(installation_id)?)
+}
+
+fn reset_machine_id_at(paths: &KiroPaths) -> Result<KiroTelemetryInfo, String> {
+    let storage_path = paths.storage_json_path()
+        .ok_or("Cannot find Kiro data directory")?;
+    let db_path = paths.state_db_path()
+        .ok_or("Cannot find Kiro data directory")?;
+    
+    let new_machine_id = generate_machine_id();
+    let new_sqm_id = generate_sqm_id();
+    let new_dev_device_id = generate_dev_device_id();
+    
+    let content = std::fs::read_to_string(&storage_path)
+        .map_err(|e| format!("Failed to read storage.json: {}", e))?;
+    let mut json: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse storage.json: {}", e))?;
+    
+    if let Some(obj) = json.as_object_mut() {
+        obj.insert("telemetry.machineId".to_string(), serde_json::json!(new_machine_id));
+        obj.insert("telemetry.sqmId".to_string(), serde_json::json!(new_sqm_id));
+        obj.insert("telemetry.devDeviceId".to_string(), serde_json::json!(new_dev_device_id));
+    }
+    
+    let new_content = serde_json::to_string_pretty(&json)
+        .map_err(|e| format!("Failed to serialize: {}", e))?;
+    std::fs::write(&storage_path, new_content)
+        .map_err(|e| format!("Failed to write storage.json: {}", e))?;
+    
+    let mut new_service_machine_id = None;
+    if db_path.exists() {
+        if let Ok(conn) = Connection::open(&db_path) {
+            let service_id = generate_machine_id();
+            if conn.execute(
+                "UPDATE ItemTable SET value = ? WHERE key = 'storage.serviceMachineId'",
+                [&service_id]
+            ).is_ok() {
+                new_service_machine_id = Some(service_id);
+            }
+        }
+    }
+    
+    Ok(KiroTelemetryInfo {
+        machine_id: Some(new_machine_id),
+        sqm_id: Some(new_sqm_id),
+        dev_device_id: Some(new_dev_device_id),
+        service_machine_id: new_service_machine_id,
+    })
+}
+
+
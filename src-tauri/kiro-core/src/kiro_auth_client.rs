@@ -10,6 +10,12 @@ pub struct KiroAuthServiceClient {
     client: Client,
 }
 
+impl Default for KiroAuthServiceClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl KiroAuthServiceClient {
     pub fn new() -> Self {
         let endpoint = "https://prod.us-east-1.auth.desktop.kiro.dev".to_string();
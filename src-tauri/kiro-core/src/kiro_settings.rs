@@ -0,0 +1,91 @@
+// Kiro IDE 设置 (读写 Kiro IDE 的 settings.json)
+
+use serde::{Deserialize, Serialize};
+use crate::installations::installation_paths;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct KiroSettings {
+    pub http_proxy: Option<String>,
+    pub model_selection: Option<String>,
+}
+
+/// 指定安装的 settings.json（installation_id 为空时为默认安装）
+fn kiro_settings_path(installation_id: Option<&str>) -> Result<std::path::PathBuf, String> {
+    installation_paths(installation_id)?
+        .settings_path()
+        .ok_or_else(|| "无法获取 Kiro 设置路径".to_string())
+}
+
+pub fn get_kiro_settings_inner(installation_id: Option<&str>) -> Result<KiroSettings, String> {
+    let path = kiro_settings_path(installation_id)?;
+    
+    if !path.exists() {
+        return Ok(KiroSettings::default());
+    }
+    
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("读取设置文件失败: {}", e))?;
+    
+    let json: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("解析设置文件失败: {}", e))?;
+    
+    Ok(KiroSettings {
+        http_proxy: json.get("http.proxy").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        model_selection: json.get("kiroAgent.modelSelection").and_then(|v| v.as_str()).map(|s| s.to_string()),
+    })
+}
+
+pub fn set_kiro_proxy_inner(installation_id: Option<&str>, proxy: String) -> Result<(), String> {
+    let path = kiro_settings_path(installation_id)?;
+    
+    let mut settings: serde_json::Value = if path.exists() {
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("读取设置文件失败: {}", e))?;
+        serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
+    } else {
+        serde_json::json!({})
+    };
+    
+    if let Some(obj) = settings.as_object_mut() {
+        if proxy.is_empty() {
+            obj.remove("http.proxy");
+        } else {
+            obj.insert("http.proxy".to_string(), serde_json::Value::String(proxy));
+            obj.insert("http.proxyStrictSSL".to_string(), serde_json::Value::Bool(false));
+            obj.insert("http.proxySupport".to_string(), serde_json::Value::String("on".to_string()));
+        }
+    }
+    
+    let content = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("序列化设置失败: {}", e))?;
+    
+    std::fs::write(&path, content)
+        .map_err(|e| format!("写入设置文件失败: {}", e))?;
+    
+    Ok(())
+}
+
+pub fn set_kiro_model_inner(installation_id: Option<&str>, model: String) -> Result<(), String> {
+    let path = kiro_settings_path(installation_id)?;
+    
+    let mut settings: serde_json::Value = if path.exists() {
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("读取设置文件失败: {}", e))?;
+        serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
+    } else {
+        serde_json::json!({})
+    };
+    
+    if let Some(obj) = settings.as_object_mut() {
+        obj.insert("kiroAgent.modelSelection".to_string(), serde_json::Value::String(model));
+    }
+    
+    let content = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("序列化设置失败: {}", e))?;
+    
+    std::fs::write(&path, content)
+        .map_err(|e| format!("写入设置文件失败: {}", e))?;
+    
+    Ok(())
+}
@@ -0,0 +1,34 @@
+// Kiro Account Manager 核心库 - 与 Tauri 无关的账号、认证与 Kiro IDE 相关逻辑
+// GUI（Tauri 命令）、本地 HTTP API、无界面模式与命令行都基于此库
+
+pub mod account;
+pub mod account_service;
+pub mod auth;
+pub mod auth_social;
+pub mod aws_sso_client;
+pub mod browser;
+pub mod codewhisperer_client;
+pub mod deep_link_handler;
+pub mod events;
+pub mod installations;
+pub mod kiro;
+pub mod kiro_auth_client;
+pub mod kiro_settings;
+pub mod mcp;
+pub mod mcp_effective;
+pub mod mcp_health;
+pub mod mcp_interop;
+pub mod mcp_profiles;
+pub mod mcp_secrets;
+pub mod mcp_tools;
+pub mod mcp_validation;
+pub mod paths;
+pub mod powers;
+pub mod process;
+pub mod providers;
+pub mod proxy_utils;
+pub mod settings;
+pub mod sso_import;
+pub mod steering;
+pub mod usage;
+pub mod workspaces;
@@ -0,0 +1,298 @@
+// MCP 配置文件读写
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::mcp_validation::{validate_mcp_json, McpValidationReport};
+
+/// 类型模型覆盖的服务器字段；编辑时只替换这些字段，其余未知字段原样保留
+const SERVER_FIELDS: &[&str] = &[
+    "command", "args", "env", "url", "headers",
+    "disabled", "autoApprove", "disabledTools", "timeout",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct McpConfig {
+    #[serde(rename = "mcpServers", default)]
+    pub mcp_servers: IndexMap<String, McpServer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub powers: Option<PowersMcpConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PowersMcpConfig {
+    #[serde(rename = "mcpServers", default)]
+    pub mcp_servers: IndexMap<String, PowerMcpServer>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum McpServer {
+    Command(McpServerCommand),
+    Url(McpServerUrl),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpServerCommand {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: IndexMap<String, String>,
+    #[serde(default)]
+    pub disabled: bool,
+    #[serde(default, rename = "autoApprove", skip_serializing_if = "Vec::is_empty")]
+    pub auto_approve: Vec<String>,
+    #[serde(default, rename = "disabledTools", skip_serializing_if = "Vec::is_empty")]
+    pub disabled_tools: Vec<String>,
+    /// 请求超时（毫秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpServerUrl {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub headers: IndexMap<String, String>,
+    #[serde(default)]
+    pub disabled: bool,
+    #[serde(default, rename = "autoApprove", skip_serializing_if = "Vec::is_empty")]
+    pub auto_approve: Vec<String>,
+    #[serde(default, rename = "disabledTools", skip_serializing_if = "Vec::is_empty")]
+    pub disabled_tools: Vec<String>,
+    /// 请求超时（毫秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerMcpServer {
+    pub url: String,
+    #[serde(default)]
+    pub disabled: bool,
+    #[serde(default, rename = "disabledTools")]
+    pub disabled_tools: Vec<String>,
+}
+
+impl McpConfig {
+    /// 获取 MCP 配置文件路径
+    pub fn config_path() -> Option<PathBuf> {
+        crate::paths::mcp_config_path()
+    }
+
+    /// 按作用域解析配置文件：None 为用户级，否则为已登记工作区的 .kiro/settings/mcp.json
+    pub fn scope_path(workspace: Option<&str>) -> Result<PathBuf, String> {
+        match workspace.map(str::trim).filter(|w| !w.is_empty()) {
+            None => Self::config_path().ok_or_else(|| "无法获取用户目录".to_string()),
+            Some(workspace) => {
+                let dir = crate::workspaces::find_workspace(workspace)?;
+                Ok(crate::paths::workspace_mcp_config_path(&dir))
+            }
+        }
+    }
+
+    /// 读取配置文件（保留原始 JSON）
+    pub fn load_raw() -> Result<Value, String> {
+        let path = Self::config_path().ok_or("无法获取用户目录")?;
+        Self::load_raw_from(&path)
+    }
+
+    pub fn load_raw_from(path: &Path) -> Result<Value, String> {
+        if !path.exists() {
+            return Ok(serde_json::json!({"mcpServers": {}}));
+        }
+        
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("读取配置文件失败: {}", e))?;
+        
+        serde_json::from_str(&content)
+            .map_err(|e| parse_error(&content, e))
+    }
+
+    /// 读取配置文件
+    pub fn load() -> Result<Self, String> {
+        let path = Self::config_path().ok_or("无法获取用户目录")?;
+        Self::load_from(&path)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("读取配置文件失败: {}", e))?;
+        
+        // 返回给界面的配置中，密钥值还原为 ${secret:NAME} 引用
+        let mut value: Value = serde_json::from_str(&content)
+            .map_err(|e| parse_error(&content, e))?;
+        crate::mcp_secrets::conceal(&mut value);
+        serde_json::from_value(value)
+            .map_err(|e| parse_error(&content, e))
+    }
+
+    /// 校验配置文件（文件不存在时视为空配置）
+    pub fn validate_file(path: &Path) -> Result<McpValidationReport, String> {
+        if !path.exists() {
+            return Ok(McpValidationReport { valid: true, diagnostics: Vec::new() });
+        }
+        
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("读取配置文件失败: {}", e))?;
+        let mut report = validate_mcp_json(&content);
+        for diagnostic in &mut report.diagnostics {
+            diagnostic.message = crate::mcp_secrets::conceal_text(&diagnostic.message);
+        }
+        Ok(report)
+    }
+
+    /// 保存原始 JSON
+    pub fn save_raw(value: &Value) -> Result<(), String> {
+        let path = Self::config_path().ok_or("无法获取用户目录")?;
+        Self::save_raw_to(&path, value)
+    }
+
+    pub fn save_raw_to(path: &Path, value: &Value) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(value)
+            .map_err(|e| format!("序列化配置失败: {}", e))?;
+        let private = crate::mcp_secrets::contains_secret_values(&content);
+        write_config(path, &content, private)
+    }
+
+    /// 以仅本用户可读的权限保存（内容含有尚未存入密钥库的密钥值时使用，如轮换密钥）
+    pub(crate) fn save_private_raw_to(path: &Path, value: &Value) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(value)
+            .map_err(|e| format!("序列化配置失败: {}", e))?;
+        write_config(path, &content, true)
+    }
+
+    /// 新增或更新服务器：在原始文档上只替换模型覆盖的字段，保留未知字段与键顺序
+    pub fn upsert_server(path: &Path, name: &str, server: &McpServer) -> Result<(), String> {
+        let mut value = serde_json::to_value(server)
+            .map_err(|e| format!("序列化配置失败: {}", e))?;
+        crate::mcp_secrets::resolve(&mut value)?;
+        let Value::Object(fields) = value else {
+            return Err("服务器配置必须是对象".to_string());
+        };
+
+        let mut raw = Self::load_raw_from(path)?;
+        let servers = servers_mut(&mut raw)?;
+        match servers.get_mut(name).and_then(|s| s.as_object_mut()) {
+            Some(existing) => {
+                existing.retain(|key, _| !SERVER_FIELDS.contains(&key.as_str()) || fields.contains_key(key));
+                for (key, value) in fields {
+                    existing.insert(key, value);
+                }
+            }
+            None => {
+                servers.insert(name.to_string(), Value::Object(fields));
+            }
+        }
+        Self::save_raw_to(path, &raw)
+    }
+
+    /// 删除服务器，其余条目保持原顺序
+    pub fn remove_server(path: &Path, name: &str) -> Result<(), String> {
+        let mut raw = Self::load_raw_from(path)?;
+        if servers_mut(&mut raw)?.shift_remove(name).is_none() {
+            return Err(format!("服务器 {} 不存在", name));
+        }
+        Self::save_raw_to(path, &raw)
+    }
+
+    /// 启用/禁用服务器，只改动 disabled 字段
+    pub fn set_server_disabled(path: &Path, name: &str, disabled: bool) -> Result<(), String> {
+        let mut raw = Self::load_raw_from(path)?;
+        let server = servers_mut(&mut raw)?
+            .get_mut(name)
+            .and_then(|s| s.as_object_mut())
+            .ok_or_else(|| format!("服务器 {} 不存在", name))?;
+        server.insert("disabled".to_string(), Value::Bool(disabled));
+        Self::save_raw_to(path, &raw)
+    }
+
+    /// 添加 Power 的 MCP 配置
+    pub fn add_power_mcp(power_name: &str, mcp_config: Value) -> Result<(), String> {
+        let mut raw = Self::load_raw()?;
+        
+        // 确保 powers.mcpServers 存在
+        if raw.get("powers").is_none() {
+            raw["powers"] = serde_json::json!({"mcpServers": {}});
+        }
+        if raw["powers"].get("mcpServers").is_none() {
+            raw["powers"]["mcpServers"] = serde_json::json!({});
+        }
+        
+        // 读取 power 的 mcp.json 并合并
+        if let Some(servers) = mcp_config.get("mcpServers").and_then(|s| s.as_object()) {
+            for (name, config) in servers {
+                let key = format!("power-{}-{}", power_name, name);
+                raw["powers"]["mcpServers"][key] = config.clone();
+            }
+        }
+        
+        Self::save_raw(&raw)
+    }
+
+    /// 移除 Power 的 MCP 配置
+    pub fn remove_power_mcp(power_name: &str) -> Result<(), String> {
+        let mut raw = Self::load_raw()?;
+        
+        if let Some(powers) = raw.get_mut("powers") {
+            if let Some(servers) = powers.get_mut("mcpServers").and_then(|s| s.as_object_mut()) {
+                let prefix = format!("power-{}-", power_name);
+                servers.retain(|k, _| !k.starts_with(&prefix));
+            }
+        }
+        
+        Self::save_raw(&raw)
+    }
+}
+
+/// 原始文档中的 mcpServers 对象，不存在时创建
+pub(crate) fn servers_mut(raw: &mut Value) -> Result<&mut serde_json::Map<String, Value>, String> {
+    let root = raw.as_object_mut().ok_or("配置文件顶层必须是对象")?;
+    root.entry("mcpServers")
+        .or_insert_with(|| Value::Object(Default::default()))
+        .as_object_mut()
+        .ok_or_else(|| "mcpServers 必须是对象".to_string())
+}
+
+/// 解析失败时用校验器给出具体位置；校验器也找不到问题时退回 serde 的错误信息
+fn parse_error(content: &str, error: serde_json::Error) -> String {
+    let report = validate_mcp_json(content);
+    if report.has_errors() {
+        format!("解析配置文件失败: {}", report.error_summary())
+    } else {
+        format!("解析配置文件失败: {}", error)
+    }
+}
+
+/// 写入前校验，存在 error 级别的问题时拒绝保存（行列号对应即将写入的内容）；
+/// private 为 true 时文件仅本用户可读
+fn write_config(path: &Path, content: &str, private: bool) -> Result<(), String> {
+    let report = validate_mcp_json(content);
+    if report.has_errors() {
+        return Err(format!("配置校验失败: {}", report.error_summary()));
+    }
+
+    // 确保目录存在
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+    
+    // 原子写入：先写临时文件，再覆盖（Kiro 监听该文件，避免读到写了一半的内容）
+    let temp_path = path.with_extension("json.tmp");
+    if private {
+        crate::mcp_secrets::write_private_file(&temp_path, content.as_bytes())?;
+    } else {
+        fs::write(&temp_path, content)
+            .map_err(|e| format!("写入配置文件失败: {}", e))?;
+    }
+    fs::rename(&temp_path, path)
+        .map_err(|e| format!("写入配置文件失败: {}", e))
+}
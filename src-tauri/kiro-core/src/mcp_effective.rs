@@ -0,0 +1,115 @@
+// MCP 生效配置 - 合并 Powers、用户级与工作区级 mcp.json，标注每个服务器的来源与覆盖关系
+// 优先级（低 → 高）：Powers < 用户级 ~/.kiro/settings/mcp.json < 工作区 <workspace>/.kiro/settings/mcp.json
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use crate::mcp::McpConfig;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum McpScope {
+    /// 用户级配置中 powers.mcpServers 下由 Powers 安装的服务器
+    Power,
+    User,
+    Workspace,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpConfigSource {
+    pub scope: McpScope,
+    /// 所在的 mcp.json
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveMcpServer {
+    pub name: String,
+    /// 生效条目的来源
+    pub source: McpConfigSource,
+    /// 生效条目的原始配置
+    pub config: Value,
+    pub disabled: bool,
+    /// 被本条覆盖的低优先级条目（按优先级从低到高）
+    pub overrides: Vec<McpConfigSource>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpSourceError {
+    pub source: McpConfigSource,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveMcpConfig {
+    pub workspace: Option<String>,
+    /// 按名称排序
+    pub servers: Vec<EffectiveMcpServer>,
+    /// 读取失败、未参与合并的配置文件
+    pub errors: Vec<McpSourceError>,
+}
+
+/// 计算生效配置；workspace 为空时只合并 Powers 与用户级配置
+pub fn effective_config(workspace: Option<&str>) -> Result<EffectiveMcpConfig, String> {
+    let user_path = McpConfig::scope_path(None)?;
+    let workspace_path = match workspace.map(str::trim).filter(|w| !w.is_empty()) {
+        Some(w) => Some(McpConfig::scope_path(Some(w))?),
+        None => None,
+    };
+
+    let mut merged: BTreeMap<String, EffectiveMcpServer> = BTreeMap::new();
+    let mut errors = Vec::new();
+
+    let mut layers = vec![
+        (McpScope::Power, user_path.clone(), "/powers/mcpServers"),
+        (McpScope::User, user_path, "/mcpServers"),
+    ];
+    if let Some(path) = workspace_path {
+        layers.push((McpScope::Workspace, path, "/mcpServers"));
+    }
+
+    for (scope, path, pointer) in layers {
+        let source = McpConfigSource { scope, path: path.to_string_lossy().to_string() };
+        let raw = match McpConfig::load_raw_from(&path) {
+            Ok(mut raw) => {
+                crate::mcp_secrets::conceal(&mut raw);
+                raw
+            }
+            Err(message) => {
+                // 用户级文件同时提供 Powers 与用户两层，只报告一次
+                if scope != McpScope::Power {
+                    errors.push(McpSourceError { source, message });
+                }
+                continue;
+            }
+        };
+        let servers = match raw.pointer(pointer).and_then(|s| s.as_object()) {
+            Some(servers) => servers,
+            None => continue,
+        };
+        for (name, config) in servers {
+            let mut overrides = Vec::new();
+            if let Some(previous) = merged.remove(name) {
+                overrides = previous.overrides;
+                overrides.push(previous.source);
+            }
+            merged.insert(name.clone(), EffectiveMcpServer {
+                name: name.clone(),
+                source: source.clone(),
+                config: config.clone(),
+                disabled: config.get("disabled").and_then(|d| d.as_bool()).unwrap_or(false),
+                overrides,
+            });
+        }
+    }
+
+    Ok(EffectiveMcpConfig {
+        workspace: workspace.map(|w| w.to_string()),
+        servers: merged.into_values().collect(),
+        errors,
+    })
+}
@@ -0,0 +1,700 @@
+// MCP 服务器健康检查 - 按 MCP 协议真实握手（initialize → notifications/initialized → tools/list）
+// 命令型服务器通过 stdio 的逐行 JSON-RPC，URL 型服务器通过 Streamable HTTP
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout};
+use crate::mcp::McpServer;
+use crate::mcp_effective::effective_config;
+
+/// 发起握手时声明的协议版本
+const PROTOCOL_VERSION: &str = "2025-03-26";
+
+/// 整个握手的超时（npx / uvx 首次运行需要下载依赖）
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 最多保留的 stderr 字节数
+const STDERR_LIMIT: usize = 64 * 1024;
+
+/// tools/list 最多翻页次数，防止服务器返回循环游标
+const MAX_TOOL_PAGES: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpToolInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub input_schema: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct McpHealthReport {
+    pub name: String,
+    /// stdio 或 http
+    pub transport: String,
+    pub success: bool,
+    pub error: Option<String>,
+    /// 从启动到 tools/list 返回的总耗时（毫秒）
+    pub latency_ms: u64,
+    /// initialize 请求的往返耗时（毫秒）
+    pub initialize_ms: Option<u64>,
+    pub protocol_version: Option<String>,
+    /// 服务器在 initialize 中返回的 serverInfo（name / version）
+    pub server_info: Option<Value>,
+    pub capabilities: Option<Value>,
+    pub instructions: Option<String>,
+    pub tools: Vec<McpToolInfo>,
+    /// 命令型服务器的 stderr 输出（截断到 64KB）
+    pub stderr: String,
+}
+
+/// 按名称检查服务器（包括 Powers 安装的服务器），使用该工作区下生效的配置
+pub async fn test_mcp_server(name: &str, workspace: Option<&str>) -> Result<McpHealthReport, String> {
+    let effective = effective_config(workspace)?;
+    let entry = effective.servers
+        .into_iter()
+        .find(|s| s.name == name)
+        .ok_or_else(|| format!("服务器 {} 不存在", name))?;
+    // 生效配置中的密钥已还原为引用，测试前重新解析
+    let mut config = entry.config;
+    crate::mcp_secrets::resolve(&mut config)?;
+    let server: McpServer = serde_json::from_value(config)
+        .map_err(|e| format!("服务器 {} 的配置无效: {}", name, e))?;
+    let mut report = test_server_config(name, &server).await;
+    // 服务器可能在错误信息或 stderr 中回显密钥
+    report.error = report.error.map(|e| crate::mcp_secrets::conceal_text(&e));
+    report.stderr = crate::mcp_secrets::conceal_text(&report.stderr);
+    Ok(report)
+}
+
+/// 检查给定的服务器配置（不要求已写入 mcp.json，便于在保存前测试）
+pub async fn test_server_config(name: &str, server: &McpServer) -> McpHealthReport {
+    check_server(name, server, HANDSHAKE_TIMEOUT).await
+}
+
+async fn check_server(name: &str, server: &McpServer, timeout: Duration) -> McpHealthReport {
+    let started = Instant::now();
+    let mut report = McpHealthReport {
+        name: name.to_string(),
+        ..Default::default()
+    };
+
+    let result = match server {
+        McpServer::Command(cmd) => {
+            report.transport = "stdio".to_string();
+            match StdioTransport::spawn(&cmd.command, &cmd.args, &cmd.env) {
+                Ok(mut transport) => {
+                    let result = tokio::time::timeout(timeout, handshake(&mut Transport::Stdio(&mut transport), &mut report)).await;
+                    report.stderr = transport.finish().await;
+                    result
+                }
+                Err(e) => Ok(Err(e)),
+            }
+        }
+        McpServer::Url(url) => {
+            report.transport = "http".to_string();
+            let mut transport = HttpTransport::new(&url.url, &url.headers);
+            let result = tokio::time::timeout(timeout, handshake(&mut Transport::Http(&mut transport), &mut report)).await;
+            transport.close().await;
+            result
+        }
+    };
+
+    report.latency_ms = started.elapsed().as_millis() as u64;
+    match result {
+        Ok(Ok(())) => report.success = true,
+        Ok(Err(e)) => report.error = Some(e),
+        Err(_) => report.error = Some(format!("握手超时（{} 秒）", timeout.as_secs())),
+    }
+    println!(
+        "[MCP] Health check {} ({}): {} in {}ms",
+        name,
+        report.transport,
+        if report.success { "ok" } else { "failed" },
+        report.latency_ms
+    );
+    report
+}
+
+/// initialize → notifications/initialized → tools/list
+async fn handshake(transport: &mut Transport<'_>, report: &mut McpHealthReport) -> Result<(), String> {
+    let init_started = Instant::now();
+    let init = transport.request(1, "initialize", json!({
+        "protocolVersion": PROTOCOL_VERSION,
+        "capabilities": {},
+        "clientInfo": {
+            "name": "kiro-account-manager",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+    })).await.map_err(|e| format!("initialize 失败: {}", e))?;
+    report.initialize_ms = Some(init_started.elapsed().as_millis() as u64);
+    report.protocol_version = init.get("protocolVersion").and_then(|v| v.as_str()).map(|s| s.to_string());
+    report.server_info = init.get("serverInfo").cloned();
+    report.capabilities = init.get("capabilities").cloned();
+    report.instructions = init.get("instructions").and_then(|v| v.as_str()).map(|s| s.to_string());
+
+    transport.notify("notifications/initialized").await
+        .map_err(|e| format!("notifications/initialized 失败: {}", e))?;
+
+    // 没有声明 tools 能力的服务器不支持 tools/list
+    if report.capabilities.as_ref().is_some_and(|c| c.get("tools").is_none()) {
+        return Ok(());
+    }
+
+    let mut cursor: Option<String> = None;
+    for page in 0..MAX_TOOL_PAGES {
+        let params = match &cursor {
+            Some(cursor) => json!({ "cursor": cursor }),
+            None => json!({}),
+        };
+        let result = transport.request(2 + page as u64, "tools/list", params).await
+            .map_err(|e| format!("tools/list 失败: {}", e))?;
+        if let Some(tools) = result.get("tools").and_then(|t| t.as_array()) {
+            report.tools.extend(tools.iter().filter_map(|tool| {
+                Some(McpToolInfo {
+                    name: tool.get("name")?.as_str()?.to_string(),
+                    description: tool.get("description").and_then(|d| d.as_str()).map(|s| s.to_string()),
+                    input_schema: tool.get("inputSchema").cloned(),
+                })
+            }));
+        }
+        cursor = result.get("nextCursor").and_then(|c| c.as_str()).map(|s| s.to_string());
+        if cursor.is_none() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn request_message(id: u64, method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+fn notification_message(method: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method })
+}
+
+/// 从 JSON-RPC 响应中取出 result（错误响应转换为错误信息）
+fn response_result(message: &Value) -> Result<Value, String> {
+    if let Some(error) = message.get("error") {
+        let code = error.get("code").and_then(|c| c.as_i64()).unwrap_or_default();
+        let text = error.get("message").and_then(|m| m.as_str()).unwrap_or("未知错误");
+        return Err(format!("服务器返回错误 {}: {}", code, text));
+    }
+    Ok(message.get("result").cloned().unwrap_or(Value::Null))
+}
+
+/// 消息是否是对指定请求的响应（也可能是批量响应中的一项）
+fn find_response(message: Value, id: u64) -> Option<Value> {
+    match message {
+        Value::Array(items) => items.into_iter().find_map(|item| find_response(item, id)),
+        message if message.get("id").and_then(|v| v.as_u64()) == Some(id)
+            && (message.get("result").is_some() || message.get("error").is_some()) => Some(message),
+        _ => None,
+    }
+}
+
+enum Transport<'a> {
+    Stdio(&'a mut StdioTransport),
+    Http(&'a mut HttpTransport),
+}
+
+impl Transport<'_> {
+    async fn request(&mut self, id: u64, method: &str, params: Value) -> Result<Value, String> {
+        let message = request_message(id, method, params);
+        let response = match self {
+            Transport::Stdio(t) => t.request(&message, id).await?,
+            Transport::Http(t) => t.request(&message, id).await?,
+        };
+        response_result(&response)
+    }
+
+    async fn notify(&mut self, method: &str) -> Result<(), String> {
+        let message = notification_message(method);
+        match self {
+            Transport::Stdio(t) => t.send(&message).await,
+            Transport::Http(t) => t.notify(&message).await,
+        }
+    }
+}
+
+// ============================================================
+// stdio
+// ============================================================
+
+struct StdioTransport {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+    stderr: Arc<Mutex<Vec<u8>>>,
+    stderr_task: tokio::task::JoinHandle<()>,
+}
+
+impl StdioTransport {
+    fn spawn(command: &str, args: &[String], env: &IndexMap<String, String>) -> Result<Self, String> {
+        if command.trim().is_empty() {
+            return Err("command 为空".to_string());
+        }
+
+        // Windows 上 npx / uvx 等是 .cmd 脚本，需要经由 cmd 启动
+        #[cfg(target_os = "windows")]
+        let mut process = {
+            let mut process = tokio::process::Command::new("cmd");
+            process.arg("/C").arg(command).creation_flags(0x08000000);
+            process
+        };
+        #[cfg(not(target_os = "windows"))]
+        let mut process = tokio::process::Command::new(command);
+
+        let mut child = process
+            .args(args)
+            .envs(env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("启动 {} 失败: {}", command, e))?;
+
+        let stdin = child.stdin.take().ok_or("无法获取 stdin")?;
+        let stdout = child.stdout.take().ok_or("无法获取 stdout")?;
+        let mut stderr_pipe = child.stderr.take().ok_or("无法获取 stderr")?;
+
+        let stderr = Arc::new(Mutex::new(Vec::new()));
+        let captured = stderr.clone();
+        let stderr_task = tokio::spawn(async move {
+            let mut buf = [0u8; 4096];
+            while let Ok(n) = stderr_pipe.read(&mut buf).await {
+                if n == 0 {
+                    break;
+                }
+                if let Ok(mut captured) = captured.lock() {
+                    let room = STDERR_LIMIT.saturating_sub(captured.len());
+                    captured.extend_from_slice(&buf[..n.min(room)]);
+                }
+            }
+        });
+
+        Ok(Self {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout).lines(),
+            stderr,
+            stderr_task,
+        })
+    }
+
+    async fn send(&mut self, message: &Value) -> Result<(), String> {
+        let mut line = message.to_string();
+        line.push('\n');
+        let written = match self.stdin.write_all(line.as_bytes()).await {
+            Ok(()) => self.stdin.flush().await,
+            Err(e) => Err(e),
+        };
+        match written {
+            Ok(()) => Ok(()),
+            Err(e) => Err(self.exited().unwrap_or_else(|| format!("写入 stdin 失败: {}", e))),
+        }
+    }
+
+    /// 进程已退出时的错误信息
+    fn exited(&mut self) -> Option<String> {
+        let status = self.child.try_wait().ok()??;
+        Some(match status.code() {
+            Some(code) => format!("服务器进程已退出（退出码 {}）", code),
+            None => "服务器进程已退出".to_string(),
+        })
+    }
+
+    /// 发送请求并读取对应的响应，跳过日志通知以及服务器打印到 stdout 的非 JSON 内容
+    async fn request(&mut self, message: &Value, id: u64) -> Result<Value, String> {
+        self.send(message).await?;
+        loop {
+            let line = match self.stdout.next_line().await {
+                Ok(Some(line)) => line,
+                Ok(None) => {
+                    // stdout 关闭后稍等进程退出，以便给出退出码
+                    let _ = tokio::time::timeout(Duration::from_millis(500), self.child.wait()).await;
+                    return Err(self.exited().unwrap_or_else(|| "服务器关闭了 stdout".to_string()));
+                }
+                Err(e) => return Err(format!("读取 stdout 失败: {}", e)),
+            };
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(response) = serde_json::from_str::<Value>(line).ok().and_then(|m| find_response(m, id)) {
+                return Ok(response);
+            }
+        }
+    }
+
+    /// 结束进程并返回捕获的 stderr
+    async fn finish(mut self) -> String {
+        let _ = self.child.start_kill();
+        let _ = tokio::time::timeout(Duration::from_secs(2), self.child.wait()).await;
+        let _ = tokio::time::timeout(Duration::from_millis(500), &mut self.stderr_task).await;
+        self.stderr_task.abort();
+        let captured = self.stderr.lock().map(|b| b.clone()).unwrap_or_default();
+        String::from_utf8_lossy(&captured).to_string()
+    }
+}
+
+// ============================================================
+// Streamable HTTP
+// ============================================================
+
+struct HttpTransport {
+    url: String,
+    /// 配置中的自定义请求头（如鉴权）
+    headers: IndexMap<String, String>,
+    client: reqwest::Client,
+    session_id: Option<String>,
+    protocol_version: Option<String>,
+}
+
+impl HttpTransport {
+    fn new(url: &str, headers: &IndexMap<String, String>) -> Self {
+        // 本机服务器（含测试桩）不走代理
+        let is_local = url::Url::parse(url).ok()
+            .and_then(|u| u.host_str().map(|h| matches!(h, "localhost" | "127.0.0.1" | "[::1]")))
+            .unwrap_or(false);
+        let client = if is_local {
+            reqwest::Client::builder()
+                .no_proxy()
+                .timeout(HANDSHAKE_TIMEOUT)
+                .build()
+                .unwrap_or_default()
+        } else {
+            crate::proxy_utils::create_http_client_with_timeout(HANDSHAKE_TIMEOUT.as_secs())
+        };
+        Self {
+            url: url.trim().to_string(),
+            headers: headers.clone(),
+            client,
+            session_id: None,
+            protocol_version: None,
+        }
+    }
+
+    async fn post(&self, message: &Value) -> Result<reqwest::Response, String> {
+        let mut request = self.client
+            .post(&self.url)
+            .header("Accept", "application/json, text/event-stream")
+            .json(message);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        if let Some(session_id) = &self.session_id {
+            request = request.header("Mcp-Session-Id", session_id);
+        }
+        if let Some(version) = &self.protocol_version {
+            request = request.header("MCP-Protocol-Version", version);
+        }
+        let response = request.send().await
+            .map_err(|e| format!("请求失败: {}", e))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("HTTP {}: {}", status, body.chars().take(500).collect::<String>()));
+        }
+        Ok(response)
+    }
+
+    async fn request(&mut self, message: &Value, id: u64) -> Result<Value, String> {
+        let response = self.post(message).await?;
+        if let Some(session_id) = response.headers().get("mcp-session-id").and_then(|v| v.to_str().ok()) {
+            self.session_id = Some(session_id.to_string());
+        }
+        let is_sse = response.headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| ct.starts_with("text/event-stream"));
+
+        let result = if is_sse {
+            read_sse_response(response, id).await?
+        } else {
+            let body: Value = response.json().await
+                .map_err(|e| format!("解析响应失败: {}", e))?;
+            find_response(body, id).ok_or("响应中没有对应的结果")?
+        };
+
+        // 协商出的协议版本需要在后续请求的头中带上
+        if let Some(version) = result.get("result").and_then(|r| r.get("protocolVersion")).and_then(|v| v.as_str()) {
+            self.protocol_version = Some(version.to_string());
+        }
+        Ok(result)
+    }
+
+    async fn notify(&mut self, message: &Value) -> Result<(), String> {
+        self.post(message).await.map(|_| ())
+    }
+
+    /// 结束会话（服务器可能不支持，忽略错误）
+    async fn close(&mut self) {
+        if let Some(session_id) = self.session_id.take() {
+            let mut request = self.client
+                .delete(&self.url)
+                .header("Mcp-Session-Id", session_id);
+            for (name, value) in &self.headers {
+                request = request.header(name, value);
+            }
+            let _ = request.send().await;
+        }
+    }
+}
+
+/// SSE 事件解析：按原始字节缓冲，只在取到完整一行后才解码，避免多字节字符被 chunk 边界截断
+#[derive(Default)]
+struct SseDecoder {
+    buffer: Vec<u8>,
+    data: String,
+}
+
+impl SseDecoder {
+    /// 追加一段数据，返回其中已结束事件的 data
+    fn feed(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(pos) = self.buffer.iter().position(|b| *b == b'\n') {
+            let raw: Vec<u8> = self.buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&raw[..pos]);
+            let line = line.trim_end_matches('\r');
+            if line.is_empty() {
+                // 空行表示一个事件结束
+                events.push(std::mem::take(&mut self.data));
+            } else if let Some(value) = line.strip_prefix("data:") {
+                if !self.data.is_empty() {
+                    self.data.push('\n');
+                }
+                self.data.push_str(value.strip_prefix(' ').unwrap_or(value));
+            }
+        }
+        events
+    }
+
+    /// 流结束时尚未以空行结束的事件
+    fn finish(mut self) -> String {
+        if !self.buffer.is_empty() {
+            self.feed(b"\n");
+        }
+        self.data
+    }
+}
+
+/// 从 SSE 流中读取指定请求的响应
+async fn read_sse_response(mut response: reqwest::Response, id: u64) -> Result<Value, String> {
+    let mut decoder = SseDecoder::default();
+    while let Some(chunk) = response.chunk().await.map_err(|e| format!("读取响应失败: {}", e))? {
+        for data in decoder.feed(&chunk) {
+            if let Some(message) = serde_json::from_str::<Value>(&data).ok().and_then(|m| find_response(m, id)) {
+                return Ok(message);
+            }
+        }
+    }
+    serde_json::from_str::<Value>(&decoder.finish()).ok()
+        .and_then(|m| find_response(m, id))
+        .ok_or_else(|| "事件流结束前没有收到响应".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(config: Value) -> McpServer {
+        serde_json::from_value(config).unwrap()
+    }
+
+    /// 通过 sh 运行的 stdio 测试桩
+    #[cfg(unix)]
+    fn stdio_stub(script: &str) -> McpServer {
+        server(json!({
+            "command": "sh",
+            "args": ["-c", script],
+            "env": { "STUB_VERSION": "1.2.3" },
+        }))
+    }
+
+    /// 依次应答 initialize 与 tools/list（请求 id 固定为 1、2），并夹带日志通知和非 JSON 输出
+    #[cfg(unix)]
+    const STDIO_SERVER: &str = r#"
+echo "stub starting" >&2
+echo "not json banner"
+read -r line
+echo '{"jsonrpc":"2.0","method":"notifications/message","params":{"level":"info"}}'
+echo '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2025-03-26","capabilities":{"tools":{}},"serverInfo":{"name":"stub","version":"'"$STUB_VERSION"'"}}}'
+read -r line
+read -r line
+echo '{"jsonrpc":"2.0","id":2,"result":{"tools":[{"name":"echo","description":"Echo input","inputSchema":{"type":"object"}}]}}'
+read -r line
+"#;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stdio_handshake_succeeds() {
+        let report = test_server_config("stub", &stdio_stub(STDIO_SERVER)).await;
+        assert!(report.success, "{:?}", report.error);
+        assert_eq!(report.transport, "stdio");
+        assert_eq!(report.protocol_version.as_deref(), Some("2025-03-26"));
+        assert_eq!(report.server_info.unwrap()["version"], "1.2.3");
+        assert_eq!(report.tools.len(), 1);
+        assert_eq!(report.tools[0].name, "echo");
+        assert_eq!(report.tools[0].description.as_deref(), Some("Echo input"));
+        assert!(report.initialize_ms.is_some());
+        assert!(report.stderr.contains("stub starting"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stdio_jsonrpc_error() {
+        let script = r#"read -r line; echo '{"jsonrpc":"2.0","id":1,"error":{"code":-32602,"message":"Unsupported protocol version"}}'; read -r line"#;
+        let report = test_server_config("stub", &stdio_stub(script)).await;
+        assert!(!report.success);
+        let error = report.error.unwrap();
+        assert!(error.starts_with("initialize 失败"), "{}", error);
+        assert!(error.contains("-32602: Unsupported protocol version"), "{}", error);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stdio_early_exit_reports_exit_code() {
+        let report = test_server_config("stub", &stdio_stub("read -r line; echo 'missing API key' >&2; exit 3")).await;
+        assert!(!report.success);
+        let error = report.error.unwrap();
+        assert!(error.contains("退出码 3"), "{}", error);
+        assert!(report.stderr.contains("missing API key"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stdio_handshake_times_out() {
+        let report = check_server("stub", &stdio_stub("exec sleep 30"), Duration::from_secs(1)).await;
+        assert!(!report.success);
+        assert_eq!(report.error.as_deref(), Some("握手超时（1 秒）"));
+        assert!(report.latency_ms < 10_000);
+    }
+
+    fn header(request: &tiny_http::Request, name: &'static str) -> Option<String> {
+        request.headers().iter()
+            .find(|h| h.field.equiv(name))
+            .map(|h| h.value.as_str().to_string())
+    }
+
+    fn respond(request: tiny_http::Request, status: u16, content_type: &str, body: String) {
+        let mut response = tiny_http::Response::from_string(body).with_status_code(status);
+        if !content_type.is_empty() {
+            response = response.with_header(format!("Content-Type: {}", content_type).parse::<tiny_http::Header>().unwrap());
+        }
+        let _ = request.respond(response);
+    }
+
+    /// 本机 Streamable HTTP 测试桩：initialize 返回 JSON，tools/list 返回事件流，
+    /// 缺少鉴权头或会话头时返回 4xx
+    fn http_stub(initialize_error: bool) -> String {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let port = server.server_addr().to_ip().unwrap().port();
+        std::thread::spawn(move || {
+            for mut request in server.incoming_requests() {
+                if header(&request, "Authorization").as_deref() != Some("Bearer test-token") {
+                    respond(request, 401, "", "missing token".to_string());
+                    continue;
+                }
+                if *request.method() == tiny_http::Method::Delete {
+                    respond(request, 200, "", String::new());
+                    continue;
+                }
+                let mut body = String::new();
+                request.as_reader().read_to_string(&mut body).unwrap();
+                let message: Value = serde_json::from_str(&body).unwrap();
+                let method = message["method"].as_str().unwrap_or_default().to_string();
+                if method != "initialize" && header(&request, "Mcp-Session-Id").as_deref() != Some("session-1") {
+                    respond(request, 400, "", "missing session".to_string());
+                    continue;
+                }
+                match method.as_str() {
+                    "initialize" if initialize_error => {
+                        let body = json!({ "jsonrpc": "2.0", "id": message["id"], "error": { "code": -32600, "message": "Invalid request" } });
+                        respond(request, 200, "application/json", body.to_string());
+                    }
+                    "initialize" => {
+                        let body = json!({ "jsonrpc": "2.0", "id": message["id"], "result": {
+                            "protocolVersion": "2025-03-26",
+                            "capabilities": { "tools": {} },
+                            "serverInfo": { "name": "http-stub", "version": "0.1.0" },
+                        } });
+                        let response = tiny_http::Response::from_string(body.to_string())
+                            .with_header("Content-Type: application/json".parse::<tiny_http::Header>().unwrap())
+                            .with_header("Mcp-Session-Id: session-1".parse::<tiny_http::Header>().unwrap());
+                        let _ = request.respond(response);
+                    }
+                    "tools/list" => {
+                        let progress = json!({ "jsonrpc": "2.0", "method": "notifications/progress", "params": {} });
+                        let result = json!({ "jsonrpc": "2.0", "id": message["id"], "result": {
+                            "tools": [{ "name": "search" }, { "name": "fetch", "description": "Fetch a URL" }],
+                        } });
+                        let body = format!("event: message\ndata: {}\n\nevent: message\ndata: {}\n\n", progress, result);
+                        respond(request, 200, "text/event-stream", body);
+                    }
+                    _ => respond(request, 202, "", String::new()),
+                }
+            }
+        });
+        format!("http://127.0.0.1:{}/mcp", port)
+    }
+
+    fn http_server(url: &str) -> McpServer {
+        server(json!({ "url": url, "headers": { "Authorization": "Bearer test-token" } }))
+    }
+
+    #[tokio::test]
+    async fn http_handshake_succeeds() {
+        let url = http_stub(false);
+        let report = test_server_config("stub", &http_server(&url)).await;
+        assert!(report.success, "{:?}", report.error);
+        assert_eq!(report.transport, "http");
+        assert_eq!(report.server_info.unwrap()["name"], "http-stub");
+        let names: Vec<&str> = report.tools.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["search", "fetch"]);
+    }
+
+    #[tokio::test]
+    async fn http_jsonrpc_error() {
+        let url = http_stub(true);
+        let report = test_server_config("stub", &http_server(&url)).await;
+        assert!(!report.success);
+        let error = report.error.unwrap();
+        assert!(error.contains("-32600: Invalid request"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn http_requires_configured_headers() {
+        let url = http_stub(false);
+        let report = test_server_config("stub", &server(json!({ "url": url }))).await;
+        assert!(!report.success);
+        assert!(report.error.unwrap().contains("401"));
+    }
+
+    #[test]
+    fn sse_decoder_keeps_multibyte_chars_split_across_chunks() {
+        let event = "event: message\r\ndata: {\"text\":\"你好\"}\r\n\r\n".as_bytes();
+        // 在“你”的 UTF-8 编码中间切开
+        let split = event.iter().position(|b| *b == 0xE4).unwrap() + 1;
+        let mut decoder = SseDecoder::default();
+        assert!(decoder.feed(&event[..split]).is_empty());
+        assert_eq!(decoder.feed(&event[split..]), vec!["{\"text\":\"你好\"}".to_string()]);
+    }
+
+    #[test]
+    fn sse_decoder_returns_unterminated_event_on_finish() {
+        let mut decoder = SseDecoder::default();
+        assert!(decoder.feed(b"data: {\"a\":\n").is_empty());
+        assert!(decoder.feed(b"data: 1}").is_empty());
+        assert_eq!(decoder.finish(), "{\"a\":\n1}");
+    }
+}
@@ -0,0 +1,468 @@
+// MCP 配置互通 - 在 Kiro mcp.json 与其他客户端的配置格式之间转换服务器定义
+//
+// 顶层布局：
+// - mcpServers：Claude Desktop、Claude Code（.mcp.json）、Cursor、Windsurf 以及 Kiro 自身
+// - servers：VS Code（.vscode/mcp.json，允许注释与尾随逗号）
+//
+// 环境变量引用：Kiro 与 Claude Code 写作 ${VAR}，VS Code、Cursor、Windsurf 写作 ${env:VAR}，Claude Desktop 不展开
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::path::Path;
+use crate::mcp::{servers_mut, McpConfig};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum McpClientFormat {
+    Kiro,
+    ClaudeDesktop,
+    ClaudeCode,
+    Cursor,
+    Windsurf,
+    Vscode,
+}
+
+/// 环境变量引用写法
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EnvStyle {
+    /// ${VAR}
+    Braces,
+    /// ${env:VAR}
+    EnvPrefix,
+    /// 不展开
+    Literal,
+}
+
+impl McpClientFormat {
+    fn root_key(self) -> &'static str {
+        match self {
+            Self::Vscode => "servers",
+            _ => "mcpServers",
+        }
+    }
+
+    fn env_style(self) -> EnvStyle {
+        match self {
+            Self::Kiro | Self::ClaudeCode => EnvStyle::Braces,
+            Self::Cursor | Self::Windsurf | Self::Vscode => EnvStyle::EnvPrefix,
+            Self::ClaudeDesktop => EnvStyle::Literal,
+        }
+    }
+
+    /// 远程服务器地址字段；None 表示不支持远程服务器
+    fn url_key(self) -> Option<&'static str> {
+        match self {
+            Self::ClaudeDesktop => None,
+            Self::Windsurf => Some("serverUrl"),
+            _ => Some("url"),
+        }
+    }
+
+    /// 是否需要 type 字段标明传输方式
+    fn typed(self) -> bool {
+        matches!(self, Self::ClaudeCode | Self::Vscode)
+    }
+}
+
+/// 导入条目与目标配置中同名条目的关系
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum McpImportConflict {
+    /// 同名且内容相同，导入不会产生变化
+    Identical,
+    /// 同名但内容不同，导入会覆盖
+    Different,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpImportEntry {
+    pub name: String,
+    /// 转换后的 Kiro 配置
+    pub config: Value,
+    pub conflict: Option<McpImportConflict>,
+    /// 目标配置中的现有条目（有冲突时）
+    pub existing: Option<Value>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpSkippedServer {
+    pub name: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpImportPreview {
+    /// 实际使用的格式（未指定时为自动识别结果）
+    pub format: McpClientFormat,
+    pub entries: Vec<McpImportEntry>,
+    pub skipped: Vec<McpSkippedServer>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpExport {
+    pub format: McpClientFormat,
+    pub content: String,
+    pub skipped: Vec<McpSkippedServer>,
+    pub warnings: Vec<String>,
+}
+
+// ============================================================
+// 导入
+// ============================================================
+
+/// 解析其他客户端的配置并与 path 指向的 Kiro 配置比对；format 为空时自动识别
+pub fn preview_import(content: &str, format: Option<McpClientFormat>, path: &Path) -> Result<McpImportPreview, String> {
+    let document: Value = serde_json::from_str(&strip_jsonc(content))
+        .map_err(|e| format!("解析配置失败: {}", e))?;
+    let format = match format {
+        Some(format) => format,
+        None => detect_format(&document)?,
+    };
+    let servers = document.get(format.root_key())
+        .and_then(|s| s.as_object())
+        .ok_or_else(|| format!("配置中没有 {} 对象", format.root_key()))?;
+
+    let existing = McpConfig::load_raw_from(path)?;
+    let existing = existing.get("mcpServers").and_then(|s| s.as_object());
+
+    let mut entries = Vec::new();
+    let mut skipped = Vec::new();
+    for (name, server) in servers {
+        match import_server(format, server) {
+            Ok((mut config, warnings)) => {
+                crate::mcp_secrets::conceal(&mut config);
+                let mut current = existing.and_then(|e| e.get(name)).cloned();
+                if let Some(current) = current.as_mut() {
+                    crate::mcp_secrets::conceal(current);
+                }
+                let conflict = current.as_ref().map(|current| {
+                    if normalized(current) == normalized(&config) { McpImportConflict::Identical } else { McpImportConflict::Different }
+                });
+                entries.push(McpImportEntry { name: name.clone(), config, conflict, existing: current, warnings });
+            }
+            Err(reason) => skipped.push(McpSkippedServer { name: name.clone(), reason }),
+        }
+    }
+    Ok(McpImportPreview { format, entries, skipped })
+}
+
+/// 把预览中选中的条目写入 path 指向的 Kiro 配置（同名条目整体替换）
+pub fn import_servers(path: &Path, servers: Map<String, Value>) -> Result<usize, String> {
+    let mut raw = McpConfig::load_raw_from(path)?;
+    let target = servers_mut(&mut raw)?;
+    let count = servers.len();
+    for (name, mut config) in servers {
+        crate::mcp_secrets::resolve(&mut config)?;
+        target.insert(name, config);
+    }
+    McpConfig::save_raw_to(path, &raw)?;
+    println!("[MCP] Imported {} servers into {}", count, path.display());
+    Ok(count)
+}
+
+/// 按顶层键与条目字段识别格式
+pub fn detect_format(document: &Value) -> Result<McpClientFormat, String> {
+    if document.get("servers").is_some_and(|s| s.is_object()) {
+        return Ok(McpClientFormat::Vscode);
+    }
+    let servers = document.get("mcpServers")
+        .and_then(|s| s.as_object())
+        .ok_or("无法识别的格式：缺少 mcpServers 或 servers 对象")?;
+    let entries = || servers.values().filter_map(|s| s.as_object());
+    if entries().any(|s| s.contains_key("serverUrl")) {
+        Ok(McpClientFormat::Windsurf)
+    } else if entries().any(|s| s.contains_key("type")) {
+        Ok(McpClientFormat::ClaudeCode)
+    } else if entries().any(|s| s.contains_key("autoApprove") || s.contains_key("disabledTools")) {
+        Ok(McpClientFormat::Kiro)
+    } else {
+        Ok(McpClientFormat::Cursor)
+    }
+}
+
+/// 单个服务器转换为 Kiro 格式，返回配置与转换说明
+fn import_server(format: McpClientFormat, server: &Value) -> Result<(Value, Vec<String>), String> {
+    let server = server.as_object().ok_or("配置不是对象")?;
+    let mut warnings = Vec::new();
+    let mut config = Map::new();
+    let mut used = vec!["type", "transportType"];
+
+    let convert = |value: &str, warnings: &mut Vec<String>| {
+        convert_env_refs(value, format.env_style(), EnvStyle::Braces, warnings)
+    };
+
+    if let Some(command) = server.get("command").and_then(|c| c.as_str()) {
+        config.insert("command".to_string(), json!(convert(command, &mut warnings)));
+        let args: Vec<Value> = string_array(server.get("args"))
+            .iter()
+            .map(|a| json!(convert(a, &mut warnings)))
+            .collect();
+        config.insert("args".to_string(), Value::Array(args));
+        config.insert("env".to_string(), convert_map(server.get("env"), &mut |v| convert(v, &mut warnings)));
+        used.extend(["command", "args", "env"]);
+    } else if let Some((key, url)) = ["url", "serverUrl", "httpUrl"].iter()
+        .find_map(|key| server.get(*key).and_then(|u| u.as_str()).map(|u| (*key, u)))
+    {
+        config.insert("url".to_string(), json!(convert(url, &mut warnings)));
+        if server.get("headers").is_some_and(|h| h.as_object().is_some_and(|h| !h.is_empty())) {
+            config.insert("headers".to_string(), convert_map(server.get("headers"), &mut |v| convert(v, &mut warnings)));
+        }
+        used.extend([key, "headers"]);
+    } else {
+        return Err("缺少 command 或 url".to_string());
+    }
+
+    if let Some(disabled) = server.get("disabled").and_then(|d| d.as_bool()) {
+        config.insert("disabled".to_string(), json!(disabled));
+    }
+    // Cline / Roo Code 使用 alwaysAllow
+    for (key, target) in [("autoApprove", "autoApprove"), ("alwaysAllow", "autoApprove"), ("disabledTools", "disabledTools")] {
+        let tools = string_array(server.get(key));
+        if !tools.is_empty() {
+            config.insert(target.to_string(), json!(tools));
+        }
+    }
+    if let Some(timeout) = server.get("timeout").and_then(|t| t.as_u64()) {
+        config.insert("timeout".to_string(), json!(timeout));
+    }
+    used.extend(["disabled", "autoApprove", "alwaysAllow", "disabledTools", "timeout"]);
+
+    let ignored: Vec<&str> = server.keys()
+        .map(|k| k.as_str())
+        .filter(|k| !used.contains(k))
+        .collect();
+    if !ignored.is_empty() {
+        warnings.push(format!("忽略了 Kiro 不支持的字段: {}", ignored.join(", ")));
+    }
+    Ok((Value::Object(config), warnings))
+}
+
+// ============================================================
+// 导出
+// ============================================================
+
+/// 把 Kiro 配置中的服务器转换为指定客户端的格式（不含 Powers 管理的服务器）
+pub fn export_servers(format: McpClientFormat, path: &Path) -> Result<McpExport, String> {
+    // 导出内容可能被分享，密钥只保留引用
+    let mut raw = McpConfig::load_raw_from(path)?;
+    crate::mcp_secrets::conceal(&mut raw);
+    let mut servers = Map::new();
+    let mut skipped = Vec::new();
+    let mut warnings = Vec::new();
+
+    if let Some(source) = raw.get("mcpServers").and_then(|s| s.as_object()) {
+        for (name, server) in source {
+            match export_server(format, server, &mut warnings) {
+                Ok(config) => {
+                    servers.insert(name.clone(), config);
+                }
+                Err(reason) => skipped.push(McpSkippedServer { name: name.clone(), reason }),
+            }
+        }
+    }
+    warnings.sort();
+    warnings.dedup();
+
+    let content = serde_json::to_string_pretty(&json!({ format.root_key(): servers }))
+        .map_err(|e| format!("序列化配置失败: {}", e))?;
+    Ok(McpExport { format, content, skipped, warnings })
+}
+
+fn export_server(format: McpClientFormat, server: &Value, warnings: &mut Vec<String>) -> Result<Value, String> {
+    let server = server.as_object().ok_or("配置不是对象")?;
+    if format == McpClientFormat::Kiro {
+        return Ok(Value::Object(server.clone()));
+    }
+    if server.get("disabled").and_then(|d| d.as_bool()).unwrap_or(false) {
+        return Err("已禁用（目标格式没有禁用开关）".to_string());
+    }
+
+    let convert = |value: &str, warnings: &mut Vec<String>| {
+        convert_env_refs(value, EnvStyle::Braces, format.env_style(), warnings)
+    };
+    let mut config = Map::new();
+
+    if let Some(command) = server.get("command").and_then(|c| c.as_str()) {
+        if format.typed() {
+            config.insert("type".to_string(), json!("stdio"));
+        }
+        config.insert("command".to_string(), json!(convert(command, warnings)));
+        let args: Vec<Value> = string_array(server.get("args"))
+            .iter()
+            .map(|a| json!(convert(a, warnings)))
+            .collect();
+        config.insert("args".to_string(), Value::Array(args));
+        let env = convert_map(server.get("env"), &mut |v| convert(v, warnings));
+        if env.as_object().is_some_and(|e| !e.is_empty()) {
+            config.insert("env".to_string(), env);
+        }
+    } else if let Some(url) = server.get("url").and_then(|u| u.as_str()) {
+        let url_key = format.url_key().ok_or("远程服务器（目标格式只支持本地命令）")?;
+        if format.typed() {
+            config.insert("type".to_string(), json!("http"));
+        }
+        config.insert(url_key.to_string(), json!(convert(url, warnings)));
+        let headers = convert_map(server.get("headers"), &mut |v| convert(v, warnings));
+        if headers.as_object().is_some_and(|h| !h.is_empty()) {
+            config.insert("headers".to_string(), headers);
+        }
+    } else {
+        return Err("缺少 command 或 url".to_string());
+    }
+
+    if ["autoApprove", "disabledTools"].iter().any(|k| !string_array(server.get(*k)).is_empty()) {
+        warnings.push("autoApprove / disabledTools 是 Kiro 专有字段，未导出".to_string());
+    }
+    Ok(Value::Object(config))
+}
+
+// ============================================================
+// 工具函数
+// ============================================================
+
+/// 比较用：空的 args / env / headers 与缺省等价，disabled: false 与缺省等价
+fn normalized(server: &Value) -> Value {
+    let mut server = server.clone();
+    if let Some(fields) = server.as_object_mut() {
+        fields.retain(|key, value| match value {
+            Value::Array(items) => !items.is_empty(),
+            Value::Object(map) => !map.is_empty(),
+            Value::Bool(false) => key != "disabled",
+            _ => true,
+        });
+    }
+    server
+}
+
+fn string_array(value: Option<&Value>) -> Vec<String> {
+    value.and_then(|v| v.as_array())
+        .map(|items| items.iter().filter_map(|i| i.as_str().map(|s| s.to_string())).collect())
+        .unwrap_or_default()
+}
+
+/// 转换 env / headers 的值，非字符串值丢弃
+fn convert_map(value: Option<&Value>, convert: &mut dyn FnMut(&str) -> String) -> Value {
+    let map: Map<String, Value> = value.and_then(|v| v.as_object())
+        .map(|m| m.iter()
+            .filter_map(|(k, v)| v.as_str().map(|v| (k.clone(), json!(convert(v)))))
+            .collect())
+        .unwrap_or_default();
+    Value::Object(map)
+}
+
+/// 转换字符串中的环境变量引用写法
+fn convert_env_refs(value: &str, from: EnvStyle, to: EnvStyle, warnings: &mut Vec<String>) -> String {
+    if !value.contains("${") || from == EnvStyle::Literal {
+        return value.to_string();
+    }
+
+    let mut output = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        output.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            output.push_str(&rest[start..]);
+            return output;
+        };
+        let inner = &rest[start + 2..start + end];
+        rest = &rest[start + end + 1..];
+
+        if let Some(secret) = inner.strip_prefix("secret:") {
+            warnings.push(format!("密钥 {} 以引用形式保留，需在目标客户端中手动填入", secret));
+            output.push_str(&format!("${{{}}}", inner));
+            continue;
+        }
+
+        let variable = match from {
+            EnvStyle::EnvPrefix => inner.strip_prefix("env:"),
+            // ${VAR:-default} 的默认值部分各客户端不通用，保留变量名
+            _ => Some(inner.split(":-").next().unwrap_or(inner)),
+        };
+        match (variable, to) {
+            (Some(var), EnvStyle::Braces) => output.push_str(&format!("${{{}}}", var)),
+            (Some(var), EnvStyle::EnvPrefix) => output.push_str(&format!("${{env:{}}}", var)),
+            (Some(var), EnvStyle::Literal) => {
+                warnings.push(format!("目标客户端不展开环境变量引用 ${{{}}}，需手动填入实际值", var));
+                output.push_str(&format!("${{{}}}", var));
+            }
+            (None, _) => {
+                // 如 VS Code 的 ${input:id}：运行时提示输入，Kiro 无对应机制
+                warnings.push(format!("引用 ${{{}}} 无法转换，需手动填入实际值", inner));
+                output.push_str(&format!("${{{}}}", inner));
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// 去掉 JSONC 中的注释与尾随逗号（VS Code 配置允许这两种写法）
+fn strip_jsonc(content: &str) -> String {
+    remove_trailing_commas(&strip_comments(content))
+}
+
+/// 逐字符扫描，跳过字符串内容；handle 返回 false 时该字符不输出
+fn scan_outside_strings(content: &str, mut handle: impl FnMut(&[char], &mut usize, &mut String) -> bool) -> String {
+    let chars: Vec<char> = content.chars().collect();
+    let mut output = String::with_capacity(content.len());
+    let mut i = 0;
+    let mut in_string = false;
+    while i < chars.len() {
+        let c = chars[i];
+        if in_string {
+            output.push(c);
+            if c == '\\' && i + 1 < chars.len() {
+                output.push(chars[i + 1]);
+                i += 1;
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+            output.push(c);
+        } else if handle(&chars, &mut i, &mut output) {
+            output.push(c);
+        }
+        i += 1;
+    }
+    output
+}
+
+fn strip_comments(content: &str) -> String {
+    scan_outside_strings(content, |chars, i, output| {
+        match (chars[*i], chars.get(*i + 1)) {
+            ('/', Some('/')) => {
+                while *i + 1 < chars.len() && chars[*i + 1] != '\n' {
+                    *i += 1;
+                }
+                false
+            }
+            ('/', Some('*')) => {
+                *i += 2;
+                while *i < chars.len() && !(chars[*i] == '*' && chars.get(*i + 1) == Some(&'/')) {
+                    *i += 1;
+                }
+                *i += 1;
+                // 块注释按一个空格处理，避免把前后内容连在一起
+                output.push(' ');
+                false
+            }
+            _ => true,
+        }
+    })
+}
+
+fn remove_trailing_commas(content: &str) -> String {
+    scan_outside_strings(content, |chars, i, _| {
+        if chars[*i] != ',' {
+            return true;
+        }
+        let next = chars[*i + 1..].iter().find(|c| !c.is_whitespace());
+        !matches!(next, Some('}') | Some(']'))
+    })
+}
@@ -0,0 +1,165 @@
+// MCP 配置方案 - 按任务保存多套服务器组合（存到数据目录 mcp-profiles.json），一步切换用户级 mcp.json
+// 应用方案时只替换 mcpServers；Power 管理的服务器存放在 powers.mcpServers 中，与其他顶层字段一起原样保留
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::PathBuf;
+use crate::mcp::McpConfig;
+
+/// 保留的备份数量
+const MAX_BACKUPS: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpProfile {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// 服务器定义（原样保存，含 disabled 开关与未知字段）
+    #[serde(default)]
+    pub servers: Map<String, Value>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+}
+
+fn profiles_path() -> PathBuf {
+    crate::paths::app_data_file("mcp-profiles.json")
+}
+
+fn backups_dir() -> PathBuf {
+    crate::paths::app_data_file("mcp-backups")
+}
+
+pub fn list_profiles() -> Vec<McpProfile> {
+    fs::read_to_string(profiles_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn find_profile(id: &str) -> Result<McpProfile, String> {
+    list_profiles()
+        .into_iter()
+        .find(|p| p.id == id || p.name == id)
+        .ok_or_else(|| format!("未找到 MCP 方案: {}", id))
+}
+
+fn save_profiles(profiles: &[McpProfile]) -> Result<(), String> {
+    let path = profiles_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(profiles)
+        .map_err(|e| format!("序列化方案失败: {}", e))?;
+    fs::write(&path, content)
+        .map_err(|e| format!("保存 MCP 方案失败: {}", e))
+}
+
+/// 新增或更新方案（id 为空时新增），返回保存后的方案
+pub fn save_profile(mut profile: McpProfile) -> Result<McpProfile, String> {
+    profile.name = profile.name.trim().to_string();
+    if profile.name.is_empty() {
+        return Err("方案名称不能为空".to_string());
+    }
+    // 方案文件中只保存密钥引用
+    for config in profile.servers.values_mut() {
+        crate::mcp_secrets::try_conceal(config)?;
+    }
+    profile.updated_at = Some(chrono::Local::now().to_rfc3339());
+
+    let mut profiles = list_profiles();
+    if profiles.iter().any(|p| p.name == profile.name && p.id != profile.id) {
+        return Err(format!("方案 {} 已存在", profile.name));
+    }
+    if profile.id.is_empty() {
+        profile.id = uuid::Uuid::new_v4().to_string();
+        profiles.push(profile.clone());
+    } else {
+        let existing = profiles.iter_mut()
+            .find(|p| p.id == profile.id)
+            .ok_or_else(|| format!("未找到 MCP 方案: {}", profile.id))?;
+        *existing = profile.clone();
+    }
+    save_profiles(&profiles)?;
+    Ok(profile)
+}
+
+/// 以当前用户级 mcp.json 中的服务器新建方案
+pub fn capture_profile(name: &str, description: Option<String>) -> Result<McpProfile, String> {
+    let raw = McpConfig::load_raw()?;
+    let servers = raw.get("mcpServers")
+        .and_then(|s| s.as_object())
+        .cloned()
+        .unwrap_or_default();
+    save_profile(McpProfile {
+        id: String::new(),
+        name: name.to_string(),
+        description,
+        servers,
+        updated_at: None,
+    })
+}
+
+pub fn delete_profile(id: &str) -> Result<(), String> {
+    let mut profiles = list_profiles();
+    let before = profiles.len();
+    profiles.retain(|p| p.id != id);
+    if profiles.len() == before {
+        return Err(format!("未找到 MCP 方案: {}", id));
+    }
+    save_profiles(&profiles)
+}
+
+/// 应用方案：先备份当前 mcp.json，再原子替换其中的服务器列表；返回备份文件路径（原文件不存在时为 None）
+pub fn apply_profile(id: &str) -> Result<Option<PathBuf>, String> {
+    let profile = find_profile(id)?;
+    let path = McpConfig::config_path().ok_or("无法获取用户目录")?;
+
+    // 当前文件无法解析时拒绝覆盖，避免丢失手工修改
+    let mut raw = McpConfig::load_raw_from(&path)?;
+    let root = raw.as_object_mut().ok_or("配置文件顶层必须是对象")?;
+
+    let mut servers = Map::new();
+    for (name, config) in &profile.servers {
+        let mut config = config.clone();
+        crate::mcp_secrets::resolve(&mut config)?;
+        servers.insert(name.clone(), config);
+    }
+    root.insert("mcpServers".to_string(), Value::Object(servers));
+
+    let backup = backup_config(&path)?;
+    McpConfig::save_raw_to(&path, &raw)?;
+    println!("[MCP] Applied profile {} ({} servers)", profile.name, profile.servers.len());
+    Ok(backup)
+}
+
+/// 备份 mcp.json 到数据目录 mcp-backups/，只保留最近 MAX_BACKUPS 份
+fn backup_config(path: &std::path::Path) -> Result<Option<PathBuf>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let dir = backups_dir();
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("创建备份目录失败: {}", e))?;
+    let backup = dir.join(format!("mcp-{}.json", chrono::Local::now().format("%Y%m%d-%H%M%S-%3f")));
+    fs::copy(path, &backup)
+        .map_err(|e| format!("备份 MCP 配置失败: {}", e))?;
+
+    if let Ok(entries) = fs::read_dir(&dir) {
+        let mut backups: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with("mcp-") && n.ends_with(".json")))
+            .collect();
+        // 文件名含时间戳，按名称排序即按时间排序
+        backups.sort();
+        let excess = backups.len().saturating_sub(MAX_BACKUPS);
+        for old in &backups[..excess] {
+            let _ = fs::remove_file(old);
+        }
+    }
+    Ok(Some(backup))
+}
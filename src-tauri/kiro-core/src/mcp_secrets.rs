@@ -0,0 +1,545 @@
+// MCP 密钥库 - env、headers 等处的 API Key 存入加密密钥库，配置中以 ${secret:NAME} 引用
+//
+// - 密钥库：数据目录 mcp-secrets.json，AES-256-GCM 加密；加密密钥存放在系统凭据库（Windows 凭据管理器 /
+//   macOS 钥匙串 / Linux Secret Service）。凭据库不可用（如无桌面会话）或其他平台时退回数据目录的
+//   mcp-secrets.key 并记录日志，此时密钥与密文在同一目录，只能防止明文直接出现，不能算作加密保护
+// - 写入 mcp.json 时把引用解析为实际值（Kiro 只认识明文）；含密钥值的 mcp.json 以仅本用户可读的权限写入。
+//   这是与 Kiro 兼容的折中：密钥库只保证界面、日志、方案与导出中不出现明文，磁盘上的 mcp.json
+//   仍对以本用户身份运行的程序、备份与同步工具可见
+// - 所有读取路径（命令返回、生效配置、方案、导出）把密钥值还原为引用；密钥库无法解密时
+//   遮盖全部 env / headers 值，宁可看不到也不显示明文
+// - 轮换密钥时先改写所有用到旧值的 mcp.json，再保存密钥库，任一步失败都恢复已改写的文件
+
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::mcp::McpConfig;
+
+const REF_PREFIX: &str = "${secret:";
+const REF_SUFFIX: &str = "}";
+
+/// 密钥值的最小长度：所有密钥值都会在返回内容中被还原为引用，过短的值会误伤普通字符串
+const MIN_SECRET_LEN: usize = 6;
+
+/// 密钥库无法解密时替代 env / headers 值的引用；名称不合法，写回时解析必然失败
+const UNAVAILABLE_REF: &str = "${secret:?}";
+
+/// 系统凭据库中的服务名与条目名
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+const KEYRING_SERVICE: &str = "kiro-account-manager";
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+const KEYRING_USER: &str = "mcp-secrets-key";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpSecretInfo {
+    pub name: String,
+    pub updated_at: String,
+    /// 用到该密钥的 mcp.json 路径与方案
+    pub used_by: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SecretVault {
+    #[serde(default)]
+    secrets: BTreeMap<String, SealedSecret>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SealedSecret {
+    nonce: String,
+    ciphertext: String,
+    updated_at: String,
+}
+
+fn vault_path() -> PathBuf {
+    crate::paths::app_data_file("mcp-secrets.json")
+}
+
+fn key_path() -> PathBuf {
+    crate::paths::app_data_file("mcp-secrets.key")
+}
+
+/// 配置中的引用写法
+pub fn secret_ref(name: &str) -> String {
+    format!("{}{}{}", REF_PREFIX, name, REF_SUFFIX)
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')) {
+        return Err("密钥名称只能包含字母、数字、_、- 和 .".to_string());
+    }
+    Ok(())
+}
+
+// ============================================================
+// 密钥库读写
+// ============================================================
+
+/// 读取密钥库；文件不存在时为空，无法读取或已损坏时返回错误（避免保存时覆盖掉其余密钥）
+fn load_vault() -> Result<SecretVault, String> {
+    let content = match fs::read_to_string(vault_path()) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(SecretVault::default()),
+        Err(e) => return Err(format!("读取密钥库失败: {}", e)),
+    };
+    serde_json::from_str(&content)
+        .map_err(|e| format!("密钥库已损坏: {}", e))
+}
+
+fn save_vault(vault: &SecretVault) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(vault)
+        .map_err(|e| format!("序列化密钥库失败: {}", e))?;
+    write_private_file(&vault_path(), content.as_bytes())
+}
+
+/// 写入仅本用户可读的文件（Unix 下权限为 0600，Windows 下去掉继承的 ACL，只授权当前用户）
+pub(crate) fn write_private_file(path: &Path, content: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::io::Write;
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)
+            .map_err(|e| format!("写入 {} 失败: {}", path.display(), e))?;
+        // 文件已存在时 mode 不生效，显式收紧权限
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("设置 {} 权限失败: {}", path.display(), e))?;
+        file.write_all(content)
+            .map_err(|e| format!("写入 {} 失败: {}", path.display(), e))?;
+    }
+
+    #[cfg(windows)]
+    {
+        // 先以空文件收紧 ACL，再写入内容
+        fs::write(path, b"")
+            .map_err(|e| format!("写入 {} 失败: {}", path.display(), e))?;
+        restrict_to_current_user(path)?;
+        fs::write(path, content)
+            .map_err(|e| format!("写入 {} 失败: {}", path.display(), e))?;
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        fs::write(path, content)
+            .map_err(|e| format!("写入 {} 失败: {}", path.display(), e))?;
+    }
+
+    Ok(())
+}
+
+/// 通过 icacls 去掉继承的权限，只保留当前用户的完全控制
+#[cfg(windows)]
+fn restrict_to_current_user(path: &Path) -> Result<(), String> {
+    use std::os::windows::process::CommandExt;
+    let user = std::env::var("USERNAME")
+        .map_err(|_| "无法获取当前用户名".to_string())?;
+    let output = std::process::Command::new("icacls")
+        .arg(path)
+        .args(["/inheritance:r", "/grant:r", &format!("{}:F", user)])
+        .creation_flags(0x08000000)
+        .output()
+        .map_err(|e| format!("执行 icacls 失败: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("设置 {} 权限失败: {}", path.display(), String::from_utf8_lossy(&output.stdout).trim()));
+    }
+    Ok(())
+}
+
+fn read_key_file() -> Result<Option<String>, String> {
+    match fs::read_to_string(key_path()) {
+        Ok(content) => Ok(Some(content.trim().to_string())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("读取密钥库密钥失败: {}", e)),
+    }
+}
+
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+fn keyring_entry() -> keyring::Result<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)
+}
+
+/// 读取十六进制密钥：优先系统凭据库，数据目录中的密钥文件（旧版本或凭据库曾不可用）迁移到凭据库
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+fn read_key() -> Result<Option<String>, String> {
+    match keyring_entry().and_then(|entry| entry.get_password()) {
+        Ok(key) => return Ok(Some(key)),
+        Err(keyring::Error::NoEntry) => {}
+        Err(e) => {
+            println!("[MCP] System keyring unavailable, using key file: {}", e);
+            return read_key_file();
+        }
+    }
+    let Some(key) = read_key_file()? else {
+        return Ok(None);
+    };
+    match keyring_entry().and_then(|entry| entry.set_password(&key)) {
+        Ok(()) => {
+            let _ = fs::remove_file(key_path());
+            println!("[MCP] Moved secret vault key into the system keyring");
+        }
+        Err(e) => println!("[MCP] System keyring unavailable, secret vault key stays in {}: {}", key_path().display(), e),
+    }
+    Ok(Some(key))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn read_key() -> Result<Option<String>, String> {
+    read_key_file()
+}
+
+/// 保存新生成的密钥；凭据库不可用时写入密钥文件
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+fn store_key(key: &str) -> Result<(), String> {
+    match keyring_entry().and_then(|entry| entry.set_password(key)) {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            println!("[MCP] System keyring unavailable, using key file: {}", e);
+            write_private_file(&key_path(), key.as_bytes())
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn store_key(key: &str) -> Result<(), String> {
+    write_private_file(&key_path(), key.as_bytes())
+}
+
+/// 读取加密密钥；create 为 true 时不存在则生成
+fn load_key(create: bool) -> Result<Option<LessSafeKey>, String> {
+    let encoded = match read_key()? {
+        Some(encoded) => encoded,
+        None if create => {
+            let bytes: [u8; 32] = rand::random();
+            let encoded = hex::encode(bytes);
+            store_key(&encoded)?;
+            encoded
+        }
+        None => return Ok(None),
+    };
+    let bytes = hex::decode(encoded.trim())
+        .map_err(|_| "密钥库密钥已损坏".to_string())?;
+    let key = UnboundKey::new(&AES_256_GCM, &bytes)
+        .map_err(|_| "密钥库密钥已损坏".to_string())?;
+    Ok(Some(LessSafeKey::new(key)))
+}
+
+fn seal(key: &LessSafeKey, name: &str, value: &str) -> Result<SealedSecret, String> {
+    let nonce: [u8; NONCE_LEN] = rand::random();
+    let mut data = value.as_bytes().to_vec();
+    // 以密钥名作为附加数据，防止密文在条目之间被调换
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(name.as_bytes()), &mut data)
+        .map_err(|_| "加密密钥失败".to_string())?;
+    Ok(SealedSecret {
+        nonce: hex::encode(nonce),
+        ciphertext: hex::encode(data),
+        updated_at: chrono::Local::now().to_rfc3339(),
+    })
+}
+
+fn open_sealed(key: &LessSafeKey, name: &str, sealed: &SealedSecret) -> Result<String, String> {
+    let corrupted = || format!("密钥 {} 无法解密", name);
+    let nonce: [u8; NONCE_LEN] = hex::decode(&sealed.nonce).ok()
+        .and_then(|n| n.try_into().ok())
+        .ok_or_else(corrupted)?;
+    let mut data = hex::decode(&sealed.ciphertext).map_err(|_| corrupted())?;
+    let plain = key.open_in_place(Nonce::assume_unique_for_key(nonce), Aad::from(name.as_bytes()), &mut data)
+        .map_err(|_| corrupted())?;
+    String::from_utf8(plain.to_vec()).map_err(|_| corrupted())
+}
+
+/// 解密全部密钥（名称 → 值）
+fn load_secrets() -> Result<BTreeMap<String, String>, String> {
+    let vault = load_vault()?;
+    if vault.secrets.is_empty() {
+        return Ok(BTreeMap::new());
+    }
+    let key = load_key(false)?.ok_or("密钥库密钥不存在，无法解密")?;
+    vault.secrets.iter()
+        .map(|(name, sealed)| Ok((name.clone(), open_sealed(&key, name, sealed)?)))
+        .collect()
+}
+
+// ============================================================
+// 引用解析与还原
+// ============================================================
+
+/// 把配置中的 ${secret:NAME} 解析为实际值（写入 mcp.json 前调用）
+pub fn resolve(value: &mut Value) -> Result<(), String> {
+    if !contains_ref(value) {
+        return Ok(());
+    }
+    resolve_with(value, &load_secrets()?)
+}
+
+fn contains_ref(value: &Value) -> bool {
+    match value {
+        Value::String(s) => s.contains(REF_PREFIX),
+        Value::Array(items) => items.iter().any(contains_ref),
+        Value::Object(map) => map.values().any(contains_ref),
+        _ => false,
+    }
+}
+
+fn resolve_with(value: &mut Value, secrets: &BTreeMap<String, String>) -> Result<(), String> {
+    match value {
+        Value::String(s) => {
+            while let Some(start) = s.find(REF_PREFIX) {
+                let end = s[start..].find(REF_SUFFIX)
+                    .ok_or_else(|| format!("密钥引用缺少结尾的 }}: {}", s))?;
+                let name = &s[start + REF_PREFIX.len()..start + end];
+                if s[start..].starts_with(UNAVAILABLE_REF) {
+                    return Err("配置中含有密钥库不可用时被遮盖的值，请重新填写后再保存".to_string());
+                }
+                let secret = secrets.get(name)
+                    .ok_or_else(|| format!("未找到密钥: {}", name))?;
+                s.replace_range(start..start + end + REF_SUFFIX.len(), secret);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                resolve_with(item, secrets)?;
+            }
+        }
+        Value::Object(map) => {
+            for item in map.values_mut() {
+                resolve_with(item, secrets)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// 把配置中出现的密钥值还原为引用（返回给界面前调用）；密钥库无法解密时遮盖全部 env / headers 值
+pub fn conceal(value: &mut Value) {
+    match load_secrets() {
+        Ok(secrets) if !secrets.is_empty() => conceal_with(value, &secrets),
+        Ok(_) => {}
+        Err(e) => {
+            println!("[MCP] Secret vault unavailable, masking env and headers: {}", e);
+            mask_secret_fields(value);
+        }
+    }
+}
+
+/// 同 conceal，但密钥库无法解密时返回错误（结果需要持久化时使用，避免把遮盖值写入文件）
+pub fn try_conceal(value: &mut Value) -> Result<(), String> {
+    let secrets = load_secrets()?;
+    if !secrets.is_empty() {
+        conceal_with(value, &secrets);
+    }
+    Ok(())
+}
+
+/// 还原文本中的密钥值（错误信息、进程输出等）；密钥库无法解密时无法判断哪些是密钥，整段隐藏
+pub fn conceal_text(text: &str) -> String {
+    match load_secrets() {
+        Ok(secrets) if !secrets.is_empty() => {
+            let mut value = Value::String(text.to_string());
+            conceal_with(&mut value, &secrets);
+            match value {
+                Value::String(s) => s,
+                _ => text.to_string(),
+            }
+        }
+        Ok(_) => text.to_string(),
+        Err(e) => {
+            println!("[MCP] Secret vault unavailable, hiding output: {}", e);
+            "（密钥库无法解密，内容已隐藏）".to_string()
+        }
+    }
+}
+
+/// mcp.json 内容中是否含有密钥库中的值（写入时据此决定文件权限）；密钥库无法解密时按含有处理
+pub fn contains_secret_values(content: &str) -> bool {
+    match load_secrets() {
+        Ok(secrets) => secrets.values().any(|secret| content.contains(secret.as_str())),
+        Err(_) => true,
+    }
+}
+
+/// 把所有 env / headers 对象中的值替换为不可解析的引用
+fn mask_secret_fields(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, item) in map.iter_mut() {
+                match item.as_object_mut() {
+                    Some(fields) if key == "env" || key == "headers" => {
+                        for field in fields.values_mut() {
+                            *field = Value::String(UNAVAILABLE_REF.to_string());
+                        }
+                    }
+                    _ => mask_secret_fields(item),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                mask_secret_fields(item);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn conceal_with(value: &mut Value, secrets: &BTreeMap<String, String>) {
+    match value {
+        Value::String(s) => {
+            // 长的先替换，避免一个密钥是另一个的子串时被拆开
+            let mut ordered: Vec<_> = secrets.iter()
+                .filter(|(_, secret)| !secret.is_empty())
+                .collect();
+            ordered.sort_by_key(|(_, secret)| std::cmp::Reverse(secret.len()));
+            for (name, secret) in ordered {
+                if s.contains(secret.as_str()) {
+                    *s = s.replace(secret.as_str(), &secret_ref(name));
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                conceal_with(item, secrets);
+            }
+        }
+        Value::Object(map) => {
+            for item in map.values_mut() {
+                conceal_with(item, secrets);
+            }
+        }
+        _ => {}
+    }
+}
+
+// ============================================================
+// 密钥管理
+// ============================================================
+
+/// 用户级与已登记工作区的 mcp.json
+fn managed_configs() -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = McpConfig::config_path().into_iter().collect();
+    for workspace in crate::workspaces::list_workspaces() {
+        paths.push(crate::paths::workspace_mcp_config_path(std::path::Path::new(&workspace)));
+    }
+    paths.into_iter().filter(|p| p.exists()).collect()
+}
+
+/// 列出密钥（不含值）及其使用位置
+pub fn list_secrets() -> Result<Vec<McpSecretInfo>, String> {
+    let vault = load_vault()?;
+    let secrets = load_secrets()?;
+    let configs: Vec<(PathBuf, Value)> = managed_configs().into_iter()
+        .filter_map(|path| McpConfig::load_raw_from(&path).ok().map(|raw| (path, raw)))
+        .collect();
+
+    let profiles = crate::mcp_profiles::list_profiles();
+
+    Ok(vault.secrets.iter().map(|(name, sealed)| {
+        let reference = secret_ref(name);
+        let used_by = configs.iter()
+            .filter(|(_, raw)| secrets.get(name).is_some_and(|secret| contains_text(raw, secret)))
+            .map(|(path, _)| path.to_string_lossy().to_string())
+            .chain(profiles.iter()
+                .filter(|p| p.servers.values().any(|config| contains_text(config, &reference)))
+                .map(|p| format!("方案: {}", p.name)))
+            .collect();
+        McpSecretInfo { name: name.clone(), updated_at: sealed.updated_at.clone(), used_by }
+    }).collect())
+}
+
+fn contains_text(value: &Value, text: &str) -> bool {
+    match value {
+        Value::String(s) => s.contains(text),
+        Value::Array(items) => items.iter().any(|i| contains_text(i, text)),
+        Value::Object(map) => map.values().any(|v| contains_text(v, text)),
+        _ => false,
+    }
+}
+
+/// 新增或轮换密钥；轮换时改写所有用到旧值的 mcp.json，返回改写的文件
+pub fn set_secret(name: &str, value: &str) -> Result<Vec<String>, String> {
+    let name = name.trim();
+    validate_name(name)?;
+    if value.is_empty() {
+        return Err("密钥值不能为空".to_string());
+    }
+    if value.chars().count() < MIN_SECRET_LEN {
+        return Err(format!("密钥值至少需要 {} 个字符", MIN_SECRET_LEN));
+    }
+
+    let old_secrets = load_secrets()?;
+    let key = load_key(true)?.ok_or("无法创建密钥库密钥")?;
+    let mut vault = load_vault()?;
+    vault.secrets.insert(name.to_string(), seal(&key, name, value)?);
+
+    // 先在内存中完成所有改写，解析失败时不落盘
+    let mut rotations = Vec::new();
+    if let Some(old_value) = old_secrets.get(name).filter(|old| old.as_str() != value) {
+        let old = BTreeMap::from([(name.to_string(), old_value.clone())]);
+        let new = BTreeMap::from([(name.to_string(), value.to_string())]);
+        for path in managed_configs() {
+            let raw = McpConfig::load_raw_from(&path)?;
+            if !contains_text(&raw, old_value) {
+                continue;
+            }
+            let mut rotated = raw.clone();
+            conceal_with(&mut rotated, &old);
+            resolve_with(&mut rotated, &new)?;
+            rotations.push((path, raw, rotated));
+        }
+    }
+
+    // 先改写 mcp.json 再保存密钥库，任一步失败都把已改写的文件恢复为旧值
+    let mut written: Vec<&(PathBuf, Value, Value)> = Vec::new();
+    let result = rotations.iter()
+        .try_for_each(|rotation| {
+            McpConfig::save_private_raw_to(&rotation.0, &rotation.2)?;
+            written.push(rotation);
+            Ok::<(), String>(())
+        })
+        .and_then(|()| save_vault(&vault));
+    if let Err(e) = result {
+        for (path, original, _) in written {
+            if let Err(restore_error) = McpConfig::save_private_raw_to(path, original) {
+                println!("[MCP] Failed to restore {}: {}", path.display(), restore_error);
+            }
+        }
+        return Err(e);
+    }
+
+    let updated: Vec<String> = rotations.iter()
+        .map(|(path, _, _)| path.to_string_lossy().to_string())
+        .collect();
+    if !updated.is_empty() {
+        println!("[MCP] Rotated secret {} ({} configs updated)", name, updated.len());
+    }
+    Ok(updated)
+}
+
+/// 删除密钥；仍被 mcp.json 使用时拒绝（否则明文会重新出现在界面上）
+pub fn delete_secret(name: &str) -> Result<(), String> {
+    let info = list_secrets()?
+        .into_iter()
+        .find(|s| s.name == name)
+        .ok_or_else(|| format!("未找到密钥: {}", name))?;
+    if !info.used_by.is_empty() {
+        return Err(format!("密钥 {} 仍被以下配置使用: {}", name, info.used_by.join(", ")));
+    }
+    let mut vault = load_vault()?;
+    vault.secrets.remove(name);
+    save_vault(&vault)
+}
@@ -0,0 +1,169 @@
+// MCP 工具列表 - 通过 tools/list 握手获取每个服务器的真实工具并缓存（数据目录 mcp-tools-cache.json），
+// 据此设置 autoApprove / disabledTools，并标出服务器上已不存在的条目
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::mcp::{servers_mut, McpConfig};
+use crate::mcp_health::{self, McpToolInfo};
+
+/// 决定工具列表的字段；这些字段变化后缓存视为过期
+const LAUNCH_FIELDS: &[&str] = &["command", "args", "env", "url", "headers"];
+
+/// autoApprove 中表示全部工具的通配符
+const WILDCARD: &str = "*";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedTools {
+    fingerprint: String,
+    tools: Vec<McpToolInfo>,
+    fetched_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServerTools {
+    pub name: String,
+    /// 缓存的工具列表，从未获取过时为空
+    pub tools: Vec<McpToolInfo>,
+    pub fetched_at: Option<String>,
+    /// 获取之后服务器的启动配置发生了变化，建议重新获取
+    pub config_changed: bool,
+    pub auto_approve: Vec<String>,
+    pub disabled_tools: Vec<String>,
+    /// autoApprove / disabledTools 中服务器上已不存在的工具名（未获取过工具列表时为空）
+    pub stale_auto_approve: Vec<String>,
+    pub stale_disabled_tools: Vec<String>,
+}
+
+fn cache_path() -> PathBuf {
+    crate::paths::app_data_file("mcp-tools-cache.json")
+}
+
+fn load_cache() -> BTreeMap<String, CachedTools> {
+    fs::read_to_string(cache_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_cache(cache: &BTreeMap<String, CachedTools>) -> Result<(), String> {
+    let path = cache_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(cache)
+        .map_err(|e| format!("序列化工具缓存失败: {}", e))?;
+    fs::write(&path, content)
+        .map_err(|e| format!("保存工具缓存失败: {}", e))
+}
+
+/// 缓存键：配置文件 + 服务器名（同名服务器在不同工作区可能不同）
+fn cache_key(path: &Path, name: &str) -> String {
+    format!("{}#{}", path.to_string_lossy(), name)
+}
+
+/// 启动配置的摘要
+fn fingerprint(server: &Value) -> String {
+    use sha2::{Digest, Sha256};
+    let launch: serde_json::Map<String, Value> = LAUNCH_FIELDS.iter()
+        .filter_map(|key| server.get(*key).map(|v| (key.to_string(), v.clone())))
+        .collect();
+    hex::encode(Sha256::digest(Value::Object(launch).to_string().as_bytes()))
+}
+
+/// 配置文件中服务器的原始条目
+fn server_entry(path: &Path, name: &str) -> Result<Value, String> {
+    McpConfig::load_raw_from(path)?
+        .get("mcpServers")
+        .and_then(|s| s.get(name))
+        .cloned()
+        .ok_or_else(|| format!("服务器 {} 不存在", name))
+}
+
+fn string_list(server: &Value, key: &str) -> Vec<String> {
+    server.get(key)
+        .and_then(|v| v.as_array())
+        .map(|items| items.iter().filter_map(|i| i.as_str().map(|s| s.to_string())).collect())
+        .unwrap_or_default()
+}
+
+/// 读取缓存的工具列表并与当前的 autoApprove / disabledTools 比对
+pub fn server_tools(name: &str, workspace: Option<&str>) -> Result<McpServerTools, String> {
+    let path = McpConfig::scope_path(workspace)?;
+    let server = server_entry(&path, name)?;
+    let cached = load_cache().remove(&cache_key(&path, name));
+
+    let auto_approve = string_list(&server, "autoApprove");
+    let disabled_tools = string_list(&server, "disabledTools");
+    let stale = |list: &[String]| -> Vec<String> {
+        match &cached {
+            Some(cached) => list.iter()
+                .filter(|t| t.as_str() != WILDCARD && !cached.tools.iter().any(|tool| &tool.name == *t))
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        }
+    };
+
+    Ok(McpServerTools {
+        name: name.to_string(),
+        stale_auto_approve: stale(&auto_approve),
+        stale_disabled_tools: stale(&disabled_tools),
+        auto_approve,
+        disabled_tools,
+        config_changed: cached.as_ref().is_some_and(|c| c.fingerprint != fingerprint(&server)),
+        fetched_at: cached.as_ref().map(|c| c.fetched_at.clone()),
+        tools: cached.map(|c| c.tools).unwrap_or_default(),
+    })
+}
+
+/// 执行 tools/list 握手并更新缓存
+pub async fn refresh_server_tools(name: &str, workspace: Option<&str>) -> Result<McpServerTools, String> {
+    let path = McpConfig::scope_path(workspace)?;
+    let server = server_entry(&path, name)?;
+    let report = mcp_health::test_mcp_server(name, workspace).await?;
+    if !report.success {
+        return Err(format!("获取工具列表失败: {}", report.error.unwrap_or_default()));
+    }
+
+    let mut cache = load_cache();
+    cache.insert(cache_key(&path, name), CachedTools {
+        fingerprint: fingerprint(&server),
+        tools: report.tools,
+        fetched_at: chrono::Local::now().to_rfc3339(),
+    });
+    save_cache(&cache)?;
+    server_tools(name, workspace)
+}
+
+/// 保存 autoApprove / disabledTools（空列表时移除字段），其余字段与键顺序不变
+pub fn save_tool_settings(
+    name: &str,
+    auto_approve: Vec<String>,
+    disabled_tools: Vec<String>,
+    workspace: Option<&str>,
+) -> Result<McpServerTools, String> {
+    let path = McpConfig::scope_path(workspace)?;
+    let mut raw = McpConfig::load_raw_from(&path)?;
+    let server = servers_mut(&mut raw)?
+        .get_mut(name)
+        .and_then(|s| s.as_object_mut())
+        .ok_or_else(|| format!("服务器 {} 不存在", name))?;
+
+    for (key, mut list) in [("autoApprove", auto_approve), ("disabledTools", disabled_tools)] {
+        let mut seen = std::collections::HashSet::new();
+        list.retain(|tool| seen.insert(tool.clone()));
+        if list.is_empty() {
+            server.shift_remove(key);
+        } else {
+            server.insert(key.to_string(), json!(list));
+        }
+    }
+    McpConfig::save_raw_to(&path, &raw)?;
+    server_tools(name, workspace)
+}
@@ -0,0 +1,474 @@
+// MCP 配置校验 - 检查 mcp.json 的语法与逻辑问题，每条诊断附带 JSON 路径与行列号
+// 保存前与用户主动检查时都会调用
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+
+/// Power 安装的 MCP 服务器名前缀（power-{power}-{server}）
+const POWER_PREFIX: &str = "power-";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum McpDiagnosticSeverity {
+    /// Kiro 无法正确加载，保存时会被拒绝
+    Error,
+    /// 可以加载，但结果可能与预期不同
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpDiagnostic {
+    pub severity: McpDiagnosticSeverity,
+    /// 机器可读的问题类型，如 syntax、command_and_url、duplicate_name
+    pub code: String,
+    pub message: String,
+    /// JSON Pointer（RFC 6901），如 /mcpServers/github/command，根节点为空字符串
+    pub path: String,
+    /// 1 起始的行列号，无法定位时为空
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct McpValidationReport {
+    /// 没有 error 级别的问题
+    pub valid: bool,
+    pub diagnostics: Vec<McpDiagnostic>,
+}
+
+impl McpValidationReport {
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(|d| d.severity == McpDiagnosticSeverity::Error)
+    }
+
+    /// 把 error 级别的诊断拼成一条错误信息
+    pub fn error_summary(&self) -> String {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == McpDiagnosticSeverity::Error)
+            .map(|d| d.to_string())
+            .collect::<Vec<_>>()
+            .join("；")
+    }
+}
+
+impl std::fmt::Display for McpDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = if self.path.is_empty() { "/" } else { &self.path };
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "{} (第 {} 行第 {} 列): {}", path, line, column, self.message),
+            _ => write!(f, "{}: {}", path, self.message),
+        }
+    }
+}
+
+/// 校验 mcp.json 文本
+pub fn validate_mcp_json(content: &str) -> McpValidationReport {
+    let mut validator = Validator::default();
+
+    match serde_json::from_str::<Value>(content) {
+        Ok(root) => {
+            validator.locator = Locator::scan(content);
+            validator.check_duplicate_keys();
+            validator.check_root(&root);
+        }
+        Err(e) => {
+            let line = e.line();
+            let column = e.column();
+            validator.diagnostics.push(McpDiagnostic {
+                severity: McpDiagnosticSeverity::Error,
+                code: "syntax".to_string(),
+                message: format!("JSON 语法错误: {}", syntax_message(&e)),
+                path: String::new(),
+                line: (line > 0).then_some(line),
+                column: (line > 0).then_some(column.max(1)),
+            });
+        }
+    }
+
+    let valid = !validator.diagnostics.iter().any(|d| d.severity == McpDiagnosticSeverity::Error);
+    McpValidationReport { valid, diagnostics: validator.diagnostics }
+}
+
+/// serde_json 的错误信息末尾自带 "at line x column y"，位置已单独给出，这里去掉
+fn syntax_message(e: &serde_json::Error) -> String {
+    let message = e.to_string();
+    match message.rfind(" at line ") {
+        Some(idx) => message[..idx].to_string(),
+        None => message,
+    }
+}
+
+/// 转义 JSON Pointer 中的一段
+fn pointer_token(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[derive(Default)]
+struct Validator {
+    locator: Locator,
+    diagnostics: Vec<McpDiagnostic>,
+}
+
+impl Validator {
+    fn push(&mut self, severity: McpDiagnosticSeverity, code: &str, path: &str, message: String) {
+        let position = self.locator.positions.get(path).copied();
+        self.diagnostics.push(McpDiagnostic {
+            severity,
+            code: code.to_string(),
+            message,
+            path: path.to_string(),
+            line: position.map(|p| p.0),
+            column: position.map(|p| p.1),
+        });
+    }
+
+    fn error(&mut self, code: &str, path: &str, message: String) {
+        self.push(McpDiagnosticSeverity::Error, code, path, message);
+    }
+
+    fn warning(&mut self, code: &str, path: &str, message: String) {
+        self.push(McpDiagnosticSeverity::Warning, code, path, message);
+    }
+
+    fn check_duplicate_keys(&mut self) {
+        for (path, line, column) in std::mem::take(&mut self.locator.duplicates) {
+            let key = path.rsplit('/').next().unwrap_or_default().replace("~1", "/").replace("~0", "~");
+            self.diagnostics.push(McpDiagnostic {
+                severity: McpDiagnosticSeverity::Warning,
+                code: "duplicate_key".to_string(),
+                message: format!("键 \"{}\" 重复出现，只有最后一个生效", key),
+                path,
+                line: Some(line),
+                column: Some(column),
+            });
+        }
+    }
+
+    fn check_root(&mut self, root: &Value) {
+        let root = match root.as_object() {
+            Some(obj) => obj,
+            None => {
+                self.error("invalid_type", "", "配置文件顶层必须是对象".to_string());
+                return;
+            }
+        };
+
+        let user_servers = match root.get("mcpServers") {
+            None => None,
+            Some(Value::Object(servers)) => Some(servers),
+            Some(_) => {
+                self.error("invalid_type", "/mcpServers", "mcpServers 必须是对象".to_string());
+                None
+            }
+        };
+
+        let power_servers = match root.get("powers") {
+            None => None,
+            Some(Value::Object(powers)) => match powers.get("mcpServers") {
+                None => None,
+                Some(Value::Object(servers)) => Some(servers),
+                Some(_) => {
+                    self.error("invalid_type", "/powers/mcpServers", "powers.mcpServers 必须是对象".to_string());
+                    None
+                }
+            },
+            Some(_) => {
+                self.error("invalid_type", "/powers", "powers 必须是对象".to_string());
+                None
+            }
+        };
+
+        if let Some(servers) = user_servers {
+            for (name, server) in servers {
+                self.check_server(&format!("/mcpServers/{}", pointer_token(name)), name, server, false);
+            }
+            self.check_case_duplicates("/mcpServers", servers);
+        }
+        if let Some(servers) = power_servers {
+            for (name, server) in servers {
+                self.check_server(&format!("/powers/mcpServers/{}", pointer_token(name)), name, server, true);
+            }
+            self.check_case_duplicates("/powers/mcpServers", servers);
+        }
+        if let Some(servers) = user_servers {
+            self.check_power_collisions(servers, power_servers);
+        }
+    }
+
+    fn check_server(&mut self, path: &str, name: &str, server: &Value, is_power: bool) {
+        if name.trim().is_empty() {
+            self.error("empty_name", path, "服务器名称不能为空".to_string());
+        }
+        let server = match server.as_object() {
+            Some(obj) => obj,
+            None => {
+                self.error("invalid_type", path, format!("服务器 \"{}\" 的配置必须是对象", name));
+                return;
+            }
+        };
+
+        let has_command = server.contains_key("command");
+        let has_url = server.contains_key("url");
+        if is_power && !has_url {
+            // Powers 的服务器只支持远程 url
+            self.error("missing_url", path, format!("Power 服务器 \"{}\" 缺少 url", name));
+        } else if has_command && has_url {
+            self.error("command_and_url", path, format!("服务器 \"{}\" 同时设置了 command 和 url，只能二选一", name));
+        } else if !has_command && !has_url {
+            self.error("missing_command_or_url", path, format!("服务器 \"{}\" 需要设置 command 或 url", name));
+        }
+
+        if has_command {
+            let field = format!("{}/command", path);
+            match server.get("command") {
+                Some(Value::String(command)) if command.trim().is_empty() => {
+                    self.error("empty_command", &field, format!("服务器 \"{}\" 的 command 为空", name));
+                }
+                Some(Value::String(_)) => {}
+                _ => self.error("invalid_type", &field, "command 必须是字符串".to_string()),
+            }
+        }
+        if has_url {
+            let field = format!("{}/url", path);
+            match server.get("url") {
+                Some(Value::String(url)) if url.trim().is_empty() => {
+                    self.error("empty_url", &field, format!("服务器 \"{}\" 的 url 为空", name));
+                }
+                Some(Value::String(url)) => match url::Url::parse(url.trim()) {
+                    Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+                    Ok(parsed) => self.error("invalid_url", &field, format!("url 只支持 http / https，当前为 {}", parsed.scheme())),
+                    Err(e) => self.error("invalid_url", &field, format!("url 无效: {}", e)),
+                },
+                _ => self.error("invalid_type", &field, "url 必须是字符串".to_string()),
+            }
+        }
+
+        self.check_string_array(path, server, "args");
+        self.check_string_array(path, server, "autoApprove");
+        self.check_string_array(path, server, "disabledTools");
+        if let Some(value) = server.get("disabled") {
+            if !value.is_boolean() {
+                self.error("invalid_type", &format!("{}/disabled", path), "disabled 必须是 true 或 false".to_string());
+            }
+        }
+        if let Some(value) = server.get("timeout") {
+            if !value.is_u64() {
+                self.error("invalid_type", &format!("{}/timeout", path), "timeout 必须是非负整数（毫秒）".to_string());
+            }
+        }
+        self.check_string_map(path, server, "env", "环境变量");
+        self.check_string_map(path, server, "headers", "请求头");
+    }
+
+    fn check_string_map(&mut self, path: &str, server: &Map<String, Value>, key: &str, label: &str) {
+        let field = format!("{}/{}", path, key);
+        match server.get(key) {
+            None => {}
+            Some(Value::Object(entries)) => {
+                for (name, value) in entries {
+                    if !value.is_string() {
+                        self.error("invalid_type", &format!("{}/{}", field, pointer_token(name)), format!("{} {} 的值必须是字符串", label, name));
+                    }
+                }
+            }
+            Some(_) => self.error("invalid_type", &field, format!("{} 必须是对象", key)),
+        }
+    }
+
+    fn check_string_array(&mut self, path: &str, server: &Map<String, Value>, key: &str) {
+        let field = format!("{}/{}", path, key);
+        match server.get(key) {
+            None => {}
+            Some(Value::Array(items)) => {
+                for (i, item) in items.iter().enumerate() {
+                    if !item.is_string() {
+                        self.error("invalid_type", &format!("{}/{}", field, i), format!("{} 的元素必须是字符串", key));
+                    }
+                }
+            }
+            Some(_) => self.error("invalid_type", &field, format!("{} 必须是字符串数组", key)),
+        }
+    }
+
+    /// 仅大小写不同的服务器名：在大小写不敏感的场景（工具前缀、文件系统）中会互相覆盖
+    fn check_case_duplicates(&mut self, base: &str, servers: &Map<String, Value>) {
+        let mut first_by_lower: HashMap<String, &str> = HashMap::new();
+        for name in servers.keys() {
+            match first_by_lower.get(&name.to_lowercase()) {
+                Some(first) => {
+                    let message = format!("服务器 \"{}\" 与 \"{}\" 仅大小写不同", name, first);
+                    self.warning("duplicate_name", &format!("{}/{}", base, pointer_token(name)), message);
+                }
+                None => {
+                    first_by_lower.insert(name.to_lowercase(), name);
+                }
+            }
+        }
+    }
+
+    /// 用户服务器与 Powers 安装的服务器使用相同名称，或占用了 power- 前缀
+    fn check_power_collisions(&mut self, servers: &Map<String, Value>, power_servers: Option<&Map<String, Value>>) {
+        let power_names: HashSet<String> = power_servers
+            .map(|s| s.keys().map(|k| k.to_lowercase()).collect())
+            .unwrap_or_default();
+        for name in servers.keys() {
+            let path = format!("/mcpServers/{}", pointer_token(name));
+            if power_names.contains(&name.to_lowercase()) {
+                self.error("power_conflict", &path, format!("服务器 \"{}\" 与 Power 安装的同名服务器冲突", name));
+            } else if name.to_lowercase().starts_with(POWER_PREFIX) {
+                self.warning("reserved_prefix", &path, format!("\"{}\" 使用了 Powers 保留的 power- 前缀，安装或卸载 Power 时可能冲突", name));
+            }
+        }
+    }
+}
+
+/// 记录 JSON 文本中每个节点的位置（对象成员取键的位置），以及重复的键
+/// 只在 serde_json 解析成功后调用，因此不处理语法错误
+#[derive(Default)]
+struct Locator {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    column: usize,
+    positions: HashMap<String, (usize, usize)>,
+    duplicates: Vec<(String, usize, usize)>,
+}
+
+impl Locator {
+    fn scan(content: &str) -> Self {
+        let mut locator = Locator {
+            chars: content.chars().collect(),
+            line: 1,
+            column: 1,
+            ..Default::default()
+        };
+        locator.value("");
+        locator.chars = Vec::new();
+        locator
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn value(&mut self, path: &str) {
+        self.skip_whitespace();
+        self.positions.entry(path.to_string()).or_insert((self.line, self.column));
+        match self.peek() {
+            Some('{') => self.object(path),
+            Some('[') => self.array(path),
+            Some('"') => {
+                self.string();
+            }
+            _ => {
+                while self.peek().is_some_and(|c| !matches!(c, ',' | '}' | ']') && !c.is_whitespace()) {
+                    self.bump();
+                }
+            }
+        }
+    }
+
+    fn object(&mut self, path: &str) {
+        self.bump();
+        let mut seen = HashSet::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some('}') => {
+                    self.bump();
+                    return;
+                }
+                Some(',') => {
+                    self.bump();
+                }
+                Some('"') => {
+                    let position = (self.line, self.column);
+                    let key = self.string();
+                    let child = format!("{}/{}", path, pointer_token(&key));
+                    // 重复的键以最后一个为准，与 serde_json 一致
+                    if !seen.insert(key) {
+                        self.duplicates.push((child.clone(), position.0, position.1));
+                    }
+                    self.positions.insert(child.clone(), position);
+                    self.skip_whitespace();
+                    if self.peek() == Some(':') {
+                        self.bump();
+                    }
+                    self.value(&child);
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn array(&mut self, path: &str) {
+        self.bump();
+        let mut index = 0;
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(']') => {
+                    self.bump();
+                    return;
+                }
+                Some(',') => {
+                    self.bump();
+                }
+                Some(_) => {
+                    self.value(&format!("{}/{}", path, index));
+                    index += 1;
+                }
+                None => return,
+            }
+        }
+    }
+
+    /// 读取字符串并解码转义
+    fn string(&mut self) -> String {
+        self.bump();
+        let mut out = String::new();
+        while let Some(c) = self.bump() {
+            match c {
+                '"' => break,
+                '\\' => match self.bump() {
+                    Some('n') => out.push('\n'),
+                    Some('t') => out.push('\t'),
+                    Some('r') => out.push('\r'),
+                    Some('b') => out.push('\u{8}'),
+                    Some('f') => out.push('\u{c}'),
+                    Some('u') => {
+                        let hex: String = (0..4).filter_map(|_| self.bump()).collect();
+                        let code = u32::from_str_radix(&hex, 16).unwrap_or(0xFFFD);
+                        out.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+                    }
+                    Some(other) => out.push(other),
+                    None => break,
+                },
+                c => out.push(c),
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,258 @@
+// 路径解析 - 所有 Kiro IDE、AWS SSO 与本应用数据目录的唯一来源
+//
+// 覆盖优先级：环境变量 > 应用设置 > 平台默认位置
+// - KIRO_AM_DATA_DIR      本应用数据目录（账号、设置等），也可在程序旁放置 .kiro-account-manager 目录实现便携安装
+// - KIRO_AM_HOME          用户目录（~/.aws、~/.kiro 以及 IDE 默认目录都以此为基准），便于测试指向临时目录
+// - KIRO_AM_IDE_DATA_DIR  Kiro IDE 数据目录（包含 User/settings.json 的目录）
+//
+// 应用设置存放在数据目录中，因此数据目录只能通过环境变量或便携目录覆盖
+
+use std::path::{Path, PathBuf};
+use crate::installations::KiroInstallation;
+use crate::settings::get_app_settings_inner;
+
+pub const ENV_DATA_DIR: &str = "KIRO_AM_DATA_DIR";
+pub const ENV_HOME: &str = "KIRO_AM_HOME";
+pub const ENV_IDE_DATA_DIR: &str = "KIRO_AM_IDE_DATA_DIR";
+
+/// 本应用数据目录名
+const APP_DIR_NAME: &str = ".kiro-account-manager";
+
+fn env_path(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+fn setting_path(value: Option<String>) -> Option<PathBuf> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+/// 通过环境变量或设置覆盖的用户目录
+fn home_override() -> Option<PathBuf> {
+    env_path(ENV_HOME).or_else(|| {
+        setting_path(get_app_settings_inner().ok()?.kiro_home_dir)
+    })
+}
+
+/// 系统用户目录
+fn system_home_dir() -> Option<PathBuf> {
+    dirs::home_dir().or_else(|| {
+        std::env::var_os("USERPROFILE")
+            .or_else(|| std::env::var_os("HOME"))
+            .map(PathBuf::from)
+    })
+}
+
+/// 以给定用户目录为基准的平台数据目录（与 dirs::data_dir 的布局一致）
+fn data_dir_under(home: &Path) -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        home.join("AppData").join("Roaming")
+    }
+    #[cfg(target_os = "macos")]
+    {
+        home.join("Library").join("Application Support")
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        home.join(".local").join("share")
+    }
+}
+
+/// 以给定用户目录为基准的平台配置目录（与 dirs::config_dir 的布局一致）
+fn config_dir_under(home: &Path) -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        home.join("AppData").join("Roaming")
+    }
+    #[cfg(target_os = "macos")]
+    {
+        home.join("Library").join("Application Support")
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        home.join(".config")
+    }
+}
+
+/// 用户目录（~/.aws、~/.kiro 所在位置）
+pub fn home_dir() -> Option<PathBuf> {
+    home_override().or_else(system_home_dir)
+}
+
+/// 便携安装：程序所在目录下存在 .kiro-account-manager 时直接使用
+fn portable_data_dir() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let dir = exe.parent()?.join(APP_DIR_NAME);
+    dir.is_dir().then_some(dir)
+}
+
+/// 本应用数据目录（accounts.json、app-settings.json 等）
+pub fn app_data_dir() -> PathBuf {
+    if let Some(dir) = env_path(ENV_DATA_DIR).or_else(portable_data_dir) {
+        return dir;
+    }
+    let base = match env_path(ENV_HOME) {
+        Some(home) => data_dir_under(&home),
+        None => dirs::data_dir()
+            .or_else(system_home_dir)
+            .unwrap_or_else(|| PathBuf::from(".")),
+    };
+    base.join(APP_DIR_NAME)
+}
+
+/// 本应用数据目录下的文件
+pub fn app_data_file(name: &str) -> PathBuf {
+    app_data_dir().join(name)
+}
+
+/// 默认 Kiro IDE 数据目录（Windows: %APPDATA%\Kiro，macOS: ~/Library/Application Support/Kiro，Linux: ~/.config/Kiro）
+pub fn kiro_ide_data_dir() -> Option<PathBuf> {
+    if let Some(dir) = env_path(ENV_IDE_DATA_DIR) {
+        return Some(dir);
+    }
+    let settings = get_app_settings_inner().unwrap_or_default();
+    if let Some(dir) = setting_path(settings.kiro_ide_data_dir) {
+        return Some(dir);
+    }
+    let config_dir = match env_path(ENV_HOME).or_else(|| setting_path(settings.kiro_home_dir)) {
+        Some(home) => config_dir_under(&home),
+        // Linux 上遵循 $XDG_CONFIG_HOME
+        None => dirs::config_dir()?,
+    };
+    Some(config_dir.join("Kiro"))
+}
+
+/// 某个 Kiro 安装使用的 IDE 与 AWS 路径
+#[derive(Debug, Clone)]
+pub struct KiroPaths {
+    /// 用户目录（.aws/sso/cache 所在位置）
+    pub home: Option<PathBuf>,
+    /// IDE 数据目录（包含 User/）
+    pub ide_data_dir: Option<PathBuf>,
+}
+
+impl KiroPaths {
+    /// 默认安装：遵循环境变量与设置中的全局覆盖
+    pub fn default_installation() -> Self {
+        Self {
+            home: home_dir(),
+            ide_data_dir: kiro_ide_data_dir(),
+        }
+    }
+
+    /// 指定安装：未设置的目录回退到默认安装（只设置了用户目录时，IDE 目录以该用户目录为基准）
+    pub fn for_installation(installation: Option<&KiroInstallation>) -> Self {
+        let installation = match installation {
+            Some(i) => i,
+            None => return Self::default_installation(),
+        };
+        let home = installation.home();
+        let ide_data_dir = setting_path(installation.data_dir.clone())
+            .or_else(|| home.as_deref().map(|h| config_dir_under(h).join("Kiro")))
+            .or_else(kiro_ide_data_dir);
+        Self {
+            home: home.or_else(home_dir),
+            ide_data_dir,
+        }
+    }
+
+    /// User/settings.json
+    pub fn settings_path(&self) -> Option<PathBuf> {
+        self.ide_data_dir.as_ref().map(|d| d.join("User").join("settings.json"))
+    }
+
+    /// User/globalStorage/storage.json
+    pub fn storage_json_path(&self) -> Option<PathBuf> {
+        self.ide_data_dir.as_ref().map(|d| d.join("User").join("globalStorage").join("storage.json"))
+    }
+
+    /// User/globalStorage/state.vscdb
+    pub fn state_db_path(&self) -> Option<PathBuf> {
+        self.ide_data_dir.as_ref().map(|d| d.join("User").join("globalStorage").join("state.vscdb"))
+    }
+
+    /// AWS SSO 缓存目录 ~/.aws/sso/cache
+    pub fn aws_sso_cache_dir(&self) -> Option<PathBuf> {
+        self.home.as_ref().map(|h| h.join(".aws").join("sso").join("cache"))
+    }
+
+    /// Kiro IDE 登录 Token 文件
+    pub fn auth_token_path(&self) -> Option<PathBuf> {
+        self.aws_sso_cache_dir().map(|d| d.join("kiro-auth-token.json"))
+    }
+
+    /// IdC 客户端注册文件（以 clientIdHash 命名）
+    pub fn client_registration_path(&self, client_id_hash: &str) -> Option<PathBuf> {
+        self.aws_sso_cache_dir().map(|d| d.join(format!("{}.json", client_id_hash)))
+    }
+}
+
+/// Kiro 用户目录 ~/.kiro
+pub fn kiro_user_dir() -> Option<PathBuf> {
+    home_dir().map(|h| h.join(".kiro"))
+}
+
+/// ~/.kiro/steering
+pub fn steering_dir() -> Option<PathBuf> {
+    kiro_user_dir().map(|d| d.join("steering"))
+}
+
+/// ~/.kiro/powers
+pub fn powers_dir() -> Option<PathBuf> {
+    kiro_user_dir().map(|d| d.join("powers"))
+}
+
+/// ~/.kiro/settings
+pub fn kiro_user_settings_dir() -> Option<PathBuf> {
+    kiro_user_dir().map(|d| d.join("settings"))
+}
+
+/// 用户级 MCP 配置 ~/.kiro/settings/mcp.json
+pub fn mcp_config_path() -> Option<PathBuf> {
+    kiro_user_settings_dir().map(|d| d.join("mcp.json"))
+}
+
+/// 工作区 MCP 配置 <workspace>/.kiro/settings/mcp.json
+pub fn workspace_mcp_config_path(workspace: &Path) -> PathBuf {
+    workspace.join(".kiro").join("settings").join("mcp.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 环境变量为进程级状态，所有覆盖放在同一个测试中设置，避免并行测试互相干扰
+    #[test]
+    fn env_overrides_redirect_all_paths() {
+        let root = std::env::temp_dir().join(format!("kiro-am-paths-{}", std::process::id()));
+        let data_dir = root.join("data");
+        let home = root.join("home");
+        let ide_data_dir = root.join("ide");
+        std::env::set_var(ENV_DATA_DIR, &data_dir);
+        std::env::set_var(ENV_HOME, &home);
+        std::env::set_var(ENV_IDE_DATA_DIR, &ide_data_dir);
+
+        assert_eq!(app_data_dir(), data_dir);
+        assert_eq!(app_data_file("accounts.json"), data_dir.join("accounts.json"));
+        assert_eq!(home_dir(), Some(home.clone()));
+        assert_eq!(mcp_config_path(), Some(home.join(".kiro").join("settings").join("mcp.json")));
+
+        let paths = KiroPaths::default_installation();
+        assert_eq!(paths.settings_path(), Some(ide_data_dir.join("User").join("settings.json")));
+        assert_eq!(paths.aws_sso_cache_dir(), Some(home.join(".aws").join("sso").join("cache")));
+        assert_eq!(paths.auth_token_path(), Some(home.join(".aws").join("sso").join("cache").join("kiro-auth-token.json")));
+
+        // 只设置用户目录时，IDE 与应用数据目录都以该用户目录为基准
+        std::env::remove_var(ENV_DATA_DIR);
+        std::env::remove_var(ENV_IDE_DATA_DIR);
+        assert_eq!(app_data_dir(), data_dir_under(&home).join(APP_DIR_NAME));
+        assert_eq!(kiro_ide_data_dir(), Some(config_dir_under(&home).join("Kiro")));
+
+        std::env::remove_var(ENV_HOME);
+    }
+}
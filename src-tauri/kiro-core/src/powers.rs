@@ -4,7 +4,7 @@ use crate::mcp::McpConfig;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -96,7 +96,7 @@ pub struct PowerSource {
 impl PowersRegistry {
     /// 获取 Powers 目录路径
     pub fn powers_dir() -> Option<PathBuf> {
-        dirs::home_dir().map(|h| h.join(".kiro").join("powers"))
+        crate::paths::powers_dir()
     }
 
     /// 获取 Powers 注册表文件路径
@@ -279,7 +279,7 @@ impl PowersRegistry {
 }
 
 /// 复制目录内容
-fn copy_dir_contents(src: &PathBuf, dst: &PathBuf) -> Result<(), String> {
+fn copy_dir_contents(src: &Path, dst: &Path) -> Result<(), String> {
     for entry in fs::read_dir(src).map_err(|e| format!("读取目录失败: {}", e))? {
         let entry = entry.map_err(|e| format!("读取条目失败: {}", e))?;
         let path = entry.path();
@@ -0,0 +1,603 @@
+// 进程管理相关功能
+// 各函数接受可选的 Kiro 安装（None 为默认安装），以便区分并存的多个安装
+
+use std::process::Command;
+use std::time::{Duration, Instant};
+use crate::events::{publish, DomainEvent, IdeShutdownStage};
+use crate::installations::KiroInstallation;
+use crate::settings::get_app_settings_inner;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// 默认等待 IDE 正常退出的时间（秒），留出处理保存提示的余地
+const DEFAULT_CLOSE_TIMEOUT_SECS: u32 = 10;
+
+/// 强制结束后等待进程消失的时间
+const FORCE_KILL_WAIT: Duration = Duration::from_secs(3);
+
+/// Linux 上 IDE 可执行文件名（官方 tar.gz / deb / rpm / AppImage 均为 kiro）
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const KIRO_EXECUTABLE: &str = "kiro";
+
+/// 安装对应的进程名（Get-Process 使用的不带 .exe 的名称，默认 Kiro）
+#[cfg(target_os = "windows")]
+fn process_name(installation: Option<&KiroInstallation>) -> String {
+    installation
+        .and_then(|i| i.executable())
+        .and_then(|p| p.file_stem().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "Kiro".to_string())
+}
+
+/// PowerShell 单引号字符串
+#[cfg(target_os = "windows")]
+fn ps_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// 检查 Kiro IDE 是否正在运行（内部函数，同步）；与关闭时使用同一套进程匹配规则
+pub fn check_kiro_running(installation: Option<&KiroInstallation>) -> bool {
+    !find_kiro_pids(installation).is_empty()
+}
+
+/// 启动路径实际对应的可执行文件（.desktop 取 Exec 中的程序，解析符号链接）
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn resolve_executable(path: &std::path::Path) -> Option<std::path::PathBuf> {
+    let program = if path.extension().is_some_and(|ext| ext == "desktop") {
+        std::path::PathBuf::from(parse_desktop_exec(path).ok()?.0)
+    } else {
+        path.to_path_buf()
+    };
+    // Exec 中可能只写了命令名
+    let program = if program.is_absolute() {
+        program
+    } else {
+        std::env::split_paths(&std::env::var_os("PATH")?)
+            .map(|dir| dir.join(&program))
+            .find(|p| p.is_file())?
+    };
+    std::fs::canonicalize(program).ok()
+}
+
+/// 进程是否属于某个安装：启动入口常是 bin/kiro 这样的脚本，
+/// 真正运行的是安装目录下的程序，因此同目录或 bin 的上一级目录也算
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn exe_matches(exe: &std::path::Path, target: &std::path::Path) -> bool {
+    if exe == target {
+        return true;
+    }
+    let (exe_dir, target_dir) = match (exe.parent(), target.parent()) {
+        (Some(e), Some(t)) => (e, t),
+        _ => return false,
+    };
+    exe_dir == target_dir
+        || (target_dir.file_name().is_some_and(|n| n == "bin") && target_dir.parent() == Some(exe_dir))
+}
+
+/// 扫描 /proc 查找 Kiro IDE 进程（主进程及其 Electron 子进程）
+/// 登记的安装按其可执行文件匹配；默认安装按文件名匹配，并排除其他已登记安装的进程
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn find_kiro_pids(installation: Option<&KiroInstallation>) -> Vec<u32> {
+    let own_pid = std::process::id();
+    let target = installation
+        .and_then(|i| i.executable())
+        .and_then(|p| resolve_executable(&p));
+    // 用户在设置中指定的可执行文件也算作 Kiro（例如改过名的安装）
+    let configured = configured_launch_path().and_then(|p| resolve_executable(&p));
+    let others: Vec<std::path::PathBuf> = match installation {
+        Some(_) => Vec::new(),
+        None => crate::installations::list_installations()
+            .iter()
+            .filter_map(|i| resolve_executable(&i.executable()?))
+            .collect(),
+    };
+
+    let entries = match std::fs::read_dir("/proc") {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut pids: Vec<u32> = entries
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<u32>().ok())
+        .filter(|pid| *pid != own_pid)
+        .filter(|pid| {
+            // 其他用户的进程读不到 exe，本来也无权结束，直接跳过
+            let exe = match std::fs::read_link(format!("/proc/{}/exe", pid)) {
+                Ok(exe) => exe,
+                Err(_) => return false,
+            };
+            // 升级后旧进程的 exe 会带 " (deleted)" 后缀
+            let exe_str = exe.to_string_lossy();
+            let exe = std::path::PathBuf::from(exe_str.trim_end_matches(" (deleted)"));
+            let is_kiro = exe.file_name().and_then(|n| n.to_str()) == Some(KIRO_EXECUTABLE);
+            match (&target, installation) {
+                (Some(target), _) => exe_matches(&exe, target),
+                // 登记的安装没有填写可执行文件时无法区分，按文件名匹配
+                (None, Some(_)) => is_kiro,
+                (None, None) => {
+                    (is_kiro || configured.as_deref().is_some_and(|c| exe_matches(&exe, c)))
+                        && !others.iter().any(|o| exe_matches(&exe, o))
+                }
+            }
+        })
+        .collect();
+    pids.sort_unstable();
+    pids
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn send_signal(pids: &[u32], signal: libc::c_int) {
+    for pid in pids {
+        // SAFETY: kill 只向指定进程发送信号，不涉及内存
+        unsafe {
+            libc::kill(*pid as libc::pid_t, signal);
+        }
+    }
+}
+
+/// 设置中指定的 Kiro IDE 启动路径（可执行文件或 .desktop 文件）
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn configured_launch_path() -> Option<std::path::PathBuf> {
+    get_app_settings_inner()
+        .ok()?
+        .kiro_launch_path
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .map(std::path::PathBuf::from)
+}
+
+/// 向该安装的 Kiro IDE 进程发送 SIGTERM，IDE 会保存状态后退出
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn request_close(installation: Option<&KiroInstallation>) -> Result<(), String> {
+    send_signal(&find_kiro_pids(installation), libc::SIGTERM);
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn force_kill(installation: Option<&KiroInstallation>) -> Result<(), String> {
+    send_signal(&find_kiro_pids(installation), libc::SIGKILL);
+    Ok(())
+}
+
+/// 通过 PowerShell 按可执行文件完整路径查找 Kiro 进程：
+/// 登记了可执行文件的安装只匹配该路径，默认安装匹配同名进程中不属于已登记安装的那些
+#[cfg(target_os = "windows")]
+pub fn find_kiro_pids(installation: Option<&KiroInstallation>) -> Vec<u32> {
+    let filter = match installation.and_then(|i| i.executable()) {
+        Some(path) => format!("$_.Path -eq {}", ps_quote(&path.to_string_lossy())),
+        None => {
+            let registered: Vec<String> = crate::installations::list_installations()
+                .iter()
+                .filter_map(|i| i.executable())
+                .map(|p| ps_quote(&p.to_string_lossy()))
+                .collect();
+            format!("@({}) -notcontains $_.Path", registered.join(","))
+        }
+    };
+    let script = format!(
+        "Get-Process -Name {} -ErrorAction SilentlyContinue | Where-Object {{ {} }} | ForEach-Object {{ $_.Id }}",
+        ps_quote(&process_name(installation)),
+        filter,
+    );
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output();
+
+    match output {
+        // 每行一个 PID
+        Ok(out) => String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .filter_map(|line| line.trim().parse::<u32>().ok())
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// 对安装的进程逐个 PID 执行 taskkill，避免误关其他安装的同名进程
+#[cfg(target_os = "windows")]
+fn taskkill(installation: Option<&KiroInstallation>, extra: &[&str]) -> Result<(), String> {
+    let pids = find_kiro_pids(installation);
+    if pids.is_empty() {
+        return Ok(());
+    }
+    let pid_strings: Vec<String> = pids.iter().map(|pid| pid.to_string()).collect();
+    let mut args = Vec::new();
+    for pid in &pid_strings {
+        args.push("/PID");
+        args.push(pid.as_str());
+    }
+    args.extend_from_slice(extra);
+
+    let output = Command::new("taskkill")
+        .args(&args)
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| format!("Failed to execute taskkill: {}", e))?;
+    
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !stderr.contains("not found") && !stderr.contains("没有找到") {
+            return Err(format!("Failed to close Kiro IDE: {}", stderr));
+        }
+    }
+    Ok(())
+}
+
+/// 不带 /F 时 taskkill 向窗口发送关闭消息，IDE 会提示保存未保存的内容
+#[cfg(target_os = "windows")]
+fn request_close(installation: Option<&KiroInstallation>) -> Result<(), String> {
+    taskkill(installation, &[])
+}
+
+#[cfg(target_os = "windows")]
+fn force_kill(installation: Option<&KiroInstallation>) -> Result<(), String> {
+    taskkill(installation, &["/F", "/T"])
+}
+
+/// pgrep / pkill 的匹配参数：登记的安装按 .app 路径匹配命令行，默认安装按进程名匹配
+#[cfg(target_os = "macos")]
+fn process_pattern(installation: Option<&KiroInstallation>) -> Vec<String> {
+    match installation.and_then(|i| i.executable()) {
+        Some(path) => vec!["-f".to_string(), path.to_string_lossy().to_string()],
+        None => vec!["-x".to_string(), "Kiro".to_string()],
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn find_kiro_pids(installation: Option<&KiroInstallation>) -> Vec<u32> {
+    match Command::new("pgrep").args(process_pattern(installation)).output() {
+        Ok(out) => String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .filter_map(|line| line.trim().parse::<u32>().ok())
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// 通过 AppleScript 请求退出（等同 Cmd+Q），不等待应用响应，避免被保存对话框阻塞
+#[cfg(target_os = "macos")]
+fn request_close(installation: Option<&KiroInstallation>) -> Result<(), String> {
+    // 按 .app 路径指定应用，稳定版与预览版同名时也不会关错
+    let app = app_path(installation).to_string_lossy().replace('"', "\\\"");
+    Command::new("osascript")
+        .args([
+            "-e", "ignoring application responses",
+            "-e", &format!("tell application \"{}\" to quit", app),
+            "-e", "end ignoring",
+        ])
+        .output()
+        .map_err(|e| format!("Failed to execute osascript: {}", e))?;
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn force_kill(installation: Option<&KiroInstallation>) -> Result<(), String> {
+    let output = Command::new("pkill")
+        .arg("-9")
+        .args(process_pattern(installation))
+        .output()
+        .map_err(|e| format!("Failed to execute pkill: {}", e))?;
+    
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !stderr.contains("No matching") {
+            return Err(format!("Failed to close Kiro IDE: {}", stderr));
+        }
+    }
+    Ok(())
+}
+
+/// IDE 关闭结果
+#[derive(Debug, Clone)]
+pub enum ShutdownOutcome {
+    /// 已退出（或本来就没有运行），附带被关闭的进程
+    Closed(Vec<u32>),
+    /// 超时仍未退出（通常是在提示保存未保存的内容），需用户确认后强制关闭
+    AwaitingConfirmation(Vec<u32>),
+}
+
+/// 等待 IDE 正常退出的时间（秒），可在设置中修改
+fn close_timeout() -> Duration {
+    let secs = get_app_settings_inner().ok()
+        .and_then(|s| s.ide_close_timeout)
+        .filter(|s| *s > 0)
+        .unwrap_or(DEFAULT_CLOSE_TIMEOUT_SECS);
+    Duration::from_secs(secs as u64)
+}
+
+/// 进程是否仍然存在
+#[cfg(unix)]
+fn pid_alive(pid: u32) -> bool {
+    let pid = pid as libc::pid_t;
+    unsafe {
+        // 由本程序启动的 IDE 退出后会成为僵尸进程，先尝试回收（不是子进程时无影响）
+        libc::waitpid(pid, std::ptr::null_mut(), libc::WNOHANG);
+        libc::kill(pid, 0) == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+    }
+}
+
+/// 在超时前给定的 IDE 进程是否已全部退出（按 PID 轮询，不重复查找进程）
+#[cfg(unix)]
+fn wait_for_exit(_installation: Option<&KiroInstallation>, pids: &[u32], timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if !pids.iter().any(|pid| pid_alive(*pid)) {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(250));
+    }
+}
+
+/// 在超时前给定的 IDE 进程是否已全部退出：逐个打开进程句柄等待其结束
+#[cfg(target_os = "windows")]
+fn wait_for_exit(installation: Option<&KiroInstallation>, pids: &[u32], timeout: Duration) -> bool {
+    use windows_sys::Win32::Foundation::{CloseHandle, WAIT_OBJECT_0};
+    use windows_sys::Win32::System::Threading::{OpenProcess, WaitForSingleObject, PROCESS_SYNCHRONIZE};
+
+    let deadline = Instant::now() + timeout;
+    let mut unopened = Vec::new();
+    for &pid in pids {
+        let handle = unsafe { OpenProcess(PROCESS_SYNCHRONIZE, 0, pid) };
+        if handle.is_null() {
+            // 进程已退出，或权限不足（如以管理员身份运行的 IDE）
+            unopened.push(pid);
+            continue;
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now()).as_millis().min(u32::MAX as u128) as u32;
+        let exited = unsafe {
+            let exited = WaitForSingleObject(handle, remaining) == WAIT_OBJECT_0;
+            CloseHandle(handle);
+            exited
+        };
+        if !exited {
+            return false;
+        }
+    }
+    if unopened.is_empty() {
+        return true;
+    }
+    // 无法打开句柄的进程只能通过进程列表确认，间隔放长以免频繁启动 PowerShell
+    loop {
+        let running = find_kiro_pids(installation);
+        if !unopened.iter().any(|pid| running.contains(pid)) {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(Duration::from_secs(2));
+    }
+}
+
+/// 分阶段关闭 Kiro IDE：先请求正常退出并等待，超时后交由用户确认；
+/// force 为 true 表示用户已确认，直接强制结束。每个阶段都会发布 IdeShutdown 事件
+pub fn shutdown_kiro(installation: Option<&KiroInstallation>, force: bool) -> Result<ShutdownOutcome, String> {
+    let pids = find_kiro_pids(installation);
+    if pids.is_empty() {
+        return Ok(ShutdownOutcome::Closed(pids));
+    }
+    let installation_id = installation.map(|i| i.id.clone());
+    let event = |stage: IdeShutdownStage| DomainEvent::IdeShutdown {
+        stage,
+        pids: pids.clone(),
+        installation_id: installation_id.clone(),
+    };
+
+    if force {
+        println!("[Process] Force killing Kiro IDE {:?}", pids);
+        force_kill(installation)?;
+        if !wait_for_exit(installation, &pids, FORCE_KILL_WAIT) {
+            return Err(format!("Failed to close Kiro IDE (pid {:?})", find_kiro_pids(installation)));
+        }
+        publish(event(IdeShutdownStage::ForceKilled));
+        return Ok(ShutdownOutcome::Closed(pids));
+    }
+
+    let timeout = close_timeout();
+    println!("[Process] Requesting Kiro IDE {:?} to close (timeout {}s)", pids, timeout.as_secs());
+    publish(event(IdeShutdownStage::CloseRequested));
+    request_close(installation)?;
+
+    if wait_for_exit(installation, &pids, timeout) {
+        publish(event(IdeShutdownStage::Closed));
+        return Ok(ShutdownOutcome::Closed(pids));
+    }
+
+    println!("[Process] Kiro IDE did not exit within {}s, waiting for confirmation", timeout.as_secs());
+    publish(event(IdeShutdownStage::TimedOut));
+    Ok(ShutdownOutcome::AwaitingConfirmation(pids))
+}
+
+/// 启动 Kiro IDE（内部函数），返回新进程 PID（macOS 经由 open 启动，无法获取）
+#[cfg(target_os = "windows")]
+pub fn launch_kiro(installation: Option<&KiroInstallation>) -> Result<Option<u32>, String> {
+    let kiro_path = match installation.and_then(|i| i.executable()) {
+        Some(path) => path,
+        None => {
+            let localappdata = std::env::var("LOCALAPPDATA")
+                .map_err(|_| "Cannot find LOCALAPPDATA")?;
+            std::path::Path::new(&localappdata)
+                .join("Programs")
+                .join("Kiro")
+                .join("Kiro.exe")
+        }
+    };
+    
+    if !kiro_path.exists() {
+        return Err(format!("Kiro IDE not found at: {}", kiro_path.display()));
+    }
+    
+    let mut command = Command::new(&kiro_path);
+    if let Some(home) = installation.and_then(|i| i.home()) {
+        command.env("USERPROFILE", home);
+    }
+    let child = command
+        .spawn()
+        .map_err(|e| format!("Failed to start Kiro IDE: {}", e))?;
+    
+    Ok(Some(child.id()))
+}
+
+/// 安装对应的 .app 包（默认 /Applications/Kiro.app）
+#[cfg(target_os = "macos")]
+fn app_path(installation: Option<&KiroInstallation>) -> std::path::PathBuf {
+    installation
+        .and_then(|i| i.executable())
+        .unwrap_or_else(|| std::path::PathBuf::from("/Applications/Kiro.app"))
+}
+
+#[cfg(target_os = "macos")]
+pub fn launch_kiro(installation: Option<&KiroInstallation>) -> Result<Option<u32>, String> {
+    let kiro_path = app_path(installation);
+    
+    if !kiro_path.exists() {
+        return Err(format!("Kiro IDE not found at: {}", kiro_path.display()));
+    }
+    
+    let mut command = Command::new("open");
+    command.arg("-a").arg(&kiro_path);
+    // open 启动的应用不继承本进程的环境变量，需要通过 --env 传入
+    if let Some(home) = installation.and_then(|i| i.home()) {
+        command.arg("--env").arg(format!("HOME={}", home.display()));
+    }
+    command
+        .spawn()
+        .map_err(|e| format!("Failed to start Kiro IDE: {}", e))?;
+    
+    Ok(None)
+}
+
+/// 优先使用安装或设置中的路径，否则在常见安装位置查找可执行文件或 .desktop 文件
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn launch_kiro(installation: Option<&KiroInstallation>) -> Result<Option<u32>, String> {
+    let target = match installation.and_then(|i| i.executable()).or_else(configured_launch_path) {
+        Some(path) => path,
+        None => find_kiro_launcher()
+            .ok_or("Kiro IDE not found, please set its binary or .desktop file path in settings")?,
+    };
+    if !target.exists() {
+        return Err(format!("Kiro IDE not found at: {}", target.display()));
+    }
+
+    let (program, args) = if target.extension().is_some_and(|ext| ext == "desktop") {
+        parse_desktop_exec(&target)?
+    } else {
+        (target.to_string_lossy().to_string(), Vec::new())
+    };
+
+    let home = installation.and_then(|i| i.home());
+    spawn_detached(&program, &args, home.as_deref()).map(Some)
+}
+
+/// 在 PATH 与常见安装目录中查找 Kiro IDE
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn find_kiro_launcher() -> Option<std::path::PathBuf> {
+    use std::path::PathBuf;
+
+    let mut paths: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).map(|dir| dir.join(KIRO_EXECUTABLE)).collect())
+        .unwrap_or_default();
+    paths.extend([
+        PathBuf::from("/usr/share/kiro/kiro"),
+        PathBuf::from("/opt/Kiro/kiro"),
+        PathBuf::from("/opt/kiro/kiro"),
+    ]);
+    if let Some(data_dir) = dirs::data_dir() {
+        paths.push(data_dir.join("applications").join("kiro.desktop"));
+    }
+    paths.extend([
+        PathBuf::from("/usr/local/share/applications/kiro.desktop"),
+        PathBuf::from("/usr/share/applications/kiro.desktop"),
+    ]);
+
+    paths.into_iter().find(|p| p.is_file())
+}
+
+/// 从 .desktop 文件的 Exec= 行解析启动命令（去掉 %u/%F 等占位符）
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn parse_desktop_exec(path: &std::path::Path) -> Result<(String, Vec<String>), String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    let mut in_entry = false;
+    let mut exec = None;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+        } else if in_entry {
+            if let Some(value) = line.strip_prefix("Exec=") {
+                exec = Some(value.to_string());
+                break;
+            }
+        }
+    }
+    let exec = exec.ok_or_else(|| format!("No Exec= entry in {}", path.display()))?;
+
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '\\' if in_quotes => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    parts.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    // 字段代码（%f %u %F %U %i %c %k 等）在没有文件参数时直接去掉，%% 还原为 %
+    let mut parts = parts.into_iter()
+        .filter(|p| !(p.len() == 2 && p.starts_with('%') && p != "%%"))
+        .map(|p| p.replace("%%", "%"));
+
+    let program = parts.next().ok_or_else(|| format!("Empty Exec= entry in {}", path.display()))?;
+    Ok((program, parts.collect()))
+}
+
+/// 以独立进程组启动，关闭本程序时不会连带结束 IDE
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn spawn_detached(program: &str, args: &[String], home: Option<&std::path::Path>) -> Result<u32, String> {
+    use std::os::unix::process::CommandExt;
+    use std::process::Stdio;
+
+    let mut command = Command::new(program);
+    command
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .process_group(0);
+    // 登记的安装可指定独立的用户目录，IDE 会在其中读写 .aws 与配置
+    if let Some(home) = home {
+        command.env("HOME", home);
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to start Kiro IDE: {}", e))?;
+
+    let pid = child.id();
+    println!("[Process] Started Kiro IDE (pid {})", pid);
+    // 回收子进程，避免 IDE 退出后留下僵尸进程
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(pid)
+}
@@ -239,6 +239,12 @@ pub struct KiroWebPortalClient {
     endpoint: String,
 }
 
+impl Default for KiroWebPortalClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl KiroWebPortalClient {
     pub fn new() -> Self {
         Self {
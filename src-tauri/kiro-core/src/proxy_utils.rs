@@ -2,13 +2,11 @@
 
 use reqwest::{Client, Proxy};
 use std::time::Duration;
+use crate::paths::{app_data_file, KiroPaths};
 
 /// 从应用自身设置中获取代理配置
 fn get_app_proxy() -> Option<String> {
-    let data_dir = dirs::data_dir()?;
-    let path = data_dir
-        .join(".kiro-account-manager")
-        .join("app-settings.json");
+    let path = app_data_file("app-settings.json");
 
     if path.exists() {
         if let Ok(content) = std::fs::read_to_string(&path) {
@@ -33,47 +31,13 @@ pub fn get_configured_proxy() -> Option<String> {
     }
 
     // 2. 从 Kiro IDE 设置读取
-    #[cfg(target_os = "macos")]
-    {
-        let home = std::env::var("HOME").ok()?;
-        let path = std::path::PathBuf::from(home)
-            .join("Library")
-            .join("Application Support")
-            .join("Kiro")
-            .join("User")
-            .join("settings.json");
-
-        if path.exists() {
-            if let Ok(content) = std::fs::read_to_string(&path) {
-                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
-                    if let Some(proxy) = json.get("http.proxy").and_then(|v| v.as_str()) {
-                        if !proxy.is_empty() {
-                            println!("[Proxy] Using Kiro IDE proxy: {}", proxy);
-                            return Some(proxy.to_string());
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    #[cfg(target_os = "windows")]
-    {
-        if let Ok(appdata) = std::env::var("APPDATA") {
-            let path = std::path::PathBuf::from(appdata)
-                .join("Kiro")
-                .join("User")
-                .join("settings.json");
-
-            if path.exists() {
-                if let Ok(content) = std::fs::read_to_string(&path) {
-                    if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
-                        if let Some(proxy) = json.get("http.proxy").and_then(|v| v.as_str()) {
-                            if !proxy.is_empty() {
-                                println!("[Proxy] Using Kiro IDE proxy: {}", proxy);
-                                return Some(proxy.to_string());
-                            }
-                        }
+    if let Some(path) = KiroPaths::default_installation().settings_path().filter(|p| p.exists()) {
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+                if let Some(proxy) = json.get("http.proxy").and_then(|v| v.as_str()) {
+                    if !proxy.is_empty() {
+                        println!("[Proxy] Using Kiro IDE proxy: {}", proxy);
+                        return Some(proxy.to_string());
                     }
                 }
             }
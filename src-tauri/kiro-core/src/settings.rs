@@ -0,0 +1,164 @@
+// 应用自身设置 (存到 ~/.kiro-account-manager/app-settings.json)
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use crate::installations::KiroInstallation;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSettings {
+    pub theme: Option<String>,
+    pub lock_model: Option<bool>,
+    pub locked_model: Option<String>,
+    pub auto_refresh: Option<bool>,
+    pub auto_refresh_interval: Option<i32>,
+    pub auto_change_machine_id: Option<bool>,
+    pub browser_path: Option<String>,
+    pub kiro_launch_path: Option<String>,              // Kiro IDE 启动路径：可执行文件或 .desktop 文件（仅 Linux）
+    pub ide_close_timeout: Option<u32>,                // 关闭 IDE 时等待其正常退出的秒数（默认 10）
+    // 路径覆盖（环境变量优先，见 paths 模块）
+    pub kiro_home_dir: Option<String>,                 // 替代用户目录（~/.aws、~/.kiro）
+    pub kiro_ide_data_dir: Option<String>,             // Kiro IDE 数据目录（包含 User/settings.json）
+    pub kiro_installations: Option<Vec<KiroInstallation>>,  // 额外登记的 Kiro 安装（稳定版 / 预览版 / 便携版）
+    pub workspaces: Option<Vec<String>>,               // 登记的项目文件夹（管理其中的 .kiro 配置）
+    // 代理设置
+    pub proxy: Option<String>,  // HTTP 代理地址，如 http://127.0.0.1:7890
+    // 账户机器码绑定功能
+    pub bind_machine_id_to_account: Option<bool>,  // 是否启用账户绑定机器码
+    pub use_bound_machine_id: Option<bool>,        // 切换时使用绑定的机器码（否则随机生成）
+    pub account_machine_ids: Option<std::collections::HashMap<String, String>>,  // 账户ID -> 机器码映射
+    // 日历订阅
+    pub calendar_feed_enabled: Option<bool>,           // 是否通过本地 HTTP 服务提供 /calendar.ics
+    pub calendar_account_ids: Option<Vec<String>>,     // 日历包含的账号（为空表示全部）
+    // 本地 HTTP API
+    pub http_server_enabled: Option<bool>,             // 是否启动本地 HTTP API（默认开启）
+    pub http_allowed_origins: Option<Vec<String>>,     // 允许跨域访问的 Origin 白名单
+    pub http_bind_address: Option<String>,             // 监听地址（默认 127.0.0.1）
+    pub http_port: Option<u16>,                        // 监听端口（默认 23847，被占用时自动换用其他端口）
+    pub http_unix_socket: Option<bool>,                // 额外监听 Unix 套接字（仅 Linux/macOS）
+    pub http_unix_socket_path: Option<String>,         // 自定义套接字路径（默认 $XDG_RUNTIME_DIR）
+}
+
+fn get_app_settings_path() -> PathBuf {
+    crate::paths::app_data_file("app-settings.json")
+}
+
+pub fn get_app_settings_inner() -> Result<AppSettings, String> {
+    let path = get_app_settings_path();
+    if !path.exists() {
+        return Ok(AppSettings::default());
+    }
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("读取设置失败: {}", e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("解析设置失败: {}", e))
+}
+
+pub fn save_app_settings_inner(updates: AppSettings) -> Result<(), String> {
+    let path = get_app_settings_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).ok();
+    }
+    
+    // 读取现有设置，合并更新
+    let mut current = get_app_settings_inner().unwrap_or_default();
+    
+    // 只更新传入的非 None 字段
+    if updates.theme.is_some() { current.theme = updates.theme; }
+    if updates.lock_model.is_some() { current.lock_model = updates.lock_model; }
+    if updates.locked_model.is_some() { current.locked_model = updates.locked_model; }
+    if updates.auto_refresh.is_some() { current.auto_refresh = updates.auto_refresh; }
+    if updates.auto_refresh_interval.is_some() { current.auto_refresh_interval = updates.auto_refresh_interval; }
+    if updates.auto_change_machine_id.is_some() { current.auto_change_machine_id = updates.auto_change_machine_id; }
+    if updates.browser_path.is_some() { current.browser_path = updates.browser_path; }
+    if updates.kiro_launch_path.is_some() { current.kiro_launch_path = updates.kiro_launch_path; }
+    if updates.ide_close_timeout.is_some() { current.ide_close_timeout = updates.ide_close_timeout; }
+    if updates.kiro_home_dir.is_some() { current.kiro_home_dir = updates.kiro_home_dir; }
+    if updates.kiro_ide_data_dir.is_some() { current.kiro_ide_data_dir = updates.kiro_ide_data_dir; }
+    if updates.kiro_installations.is_some() { current.kiro_installations = updates.kiro_installations; }
+    if updates.workspaces.is_some() { current.workspaces = updates.workspaces; }
+    if updates.proxy.is_some() { current.proxy = updates.proxy; }
+    if updates.bind_machine_id_to_account.is_some() { current.bind_machine_id_to_account = updates.bind_machine_id_to_account; }
+    if updates.use_bound_machine_id.is_some() { current.use_bound_machine_id = updates.use_bound_machine_id; }
+    if updates.account_machine_ids.is_some() { current.account_machine_ids = updates.account_machine_ids; }
+    if updates.calendar_feed_enabled.is_some() { current.calendar_feed_enabled = updates.calendar_feed_enabled; }
+    if updates.calendar_account_ids.is_some() { current.calendar_account_ids = updates.calendar_account_ids; }
+    if updates.http_server_enabled.is_some() { current.http_server_enabled = updates.http_server_enabled; }
+    if updates.http_allowed_origins.is_some() { current.http_allowed_origins = updates.http_allowed_origins; }
+    if updates.http_bind_address.is_some() { current.http_bind_address = updates.http_bind_address; }
+    if updates.http_port.is_some() { current.http_port = updates.http_port; }
+    if updates.http_unix_socket.is_some() { current.http_unix_socket = updates.http_unix_socket; }
+    if updates.http_unix_socket_path.is_some() { current.http_unix_socket_path = updates.http_unix_socket_path; }
+    
+    let content = serde_json::to_string_pretty(&current)
+        .map_err(|e| format!("序列化失败: {}", e))?;
+    std::fs::write(&path, content)
+        .map_err(|e| format!("写入失败: {}", e))?;
+    Ok(())
+}
+
+/// 获取自定义浏览器路径（供打开浏览器时使用）
+pub fn get_browser_path() -> Option<String> {
+    let path = get_app_settings_path();
+    println!("[Settings] App settings path: {:?}", path);
+    
+    let result = get_app_settings_inner();
+    println!("[Settings] get_app_settings_inner result: {:?}", result);
+    
+    let browser_path = result.ok().and_then(|s| s.browser_path).filter(|p| !p.is_empty());
+    println!("[Settings] browser_path: {:?}", browser_path);
+    
+    browser_path
+}
+
+// ============================================================
+// 账号绑定机器码功能
+// ============================================================
+
+/// 绑定机器码到账号
+pub fn bind_machine_id_inner(account_id: String, machine_id: String) -> Result<(), String> {
+    let path = get_app_settings_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).ok();
+    }
+    
+    let mut current = get_app_settings_inner().unwrap_or_default();
+    let mut map = current.account_machine_ids.unwrap_or_default();
+    map.insert(account_id, machine_id);
+    current.account_machine_ids = Some(map);
+    
+    let content = serde_json::to_string_pretty(&current)
+        .map_err(|e| format!("序列化失败: {}", e))?;
+    std::fs::write(&path, content)
+        .map_err(|e| format!("写入失败: {}", e))?;
+    Ok(())
+}
+
+/// 解绑账号的机器码
+pub fn unbind_machine_id_inner(account_id: String) -> Result<(), String> {
+    let path = get_app_settings_path();
+    let mut current = get_app_settings_inner().unwrap_or_default();
+    
+    if let Some(ref mut map) = current.account_machine_ids {
+        map.remove(&account_id);
+    }
+    
+    let content = serde_json::to_string_pretty(&current)
+        .map_err(|e| format!("序列化失败: {}", e))?;
+    std::fs::write(&path, content)
+        .map_err(|e| format!("写入失败: {}", e))?;
+    Ok(())
+}
+
+/// 获取账号绑定的机器码
+pub fn get_bound_machine_id_inner(account_id: String) -> Result<Option<String>, String> {
+    let current = get_app_settings_inner().unwrap_or_default();
+    Ok(current.account_machine_ids
+        .and_then(|map| map.get(&account_id).cloned()))
+}
+
+/// 获取所有账号绑定的机器码
+pub fn get_all_bound_machine_ids_inner() -> Result<std::collections::HashMap<String, String>, String> {
+    let current = get_app_settings_inner().unwrap_or_default();
+    Ok(current.account_machine_ids.unwrap_or_default())
+}
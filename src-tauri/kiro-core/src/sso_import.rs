@@ -0,0 +1,299 @@
+// SSO Token 导入 - 用 x-amz-sso_authn Cookie 中的 Bearer Token 代替浏览器完成 BuilderId 设备授权
+// 只负责与 AWS SSO Portal / OIDC 交互取得令牌，账号的保存见 account_service::import_from_sso_token
+
+use serde::{Deserialize, Serialize};
+
+const PORTAL_BASE: &str = "https://portal.sso.us-east-1.amazonaws.com";
+const START_URL: &str = "https://view.awsapps.com/start";
+
+/// 设备授权完成后得到的客户端注册信息与令牌
+#[derive(Debug, Clone)]
+pub struct SsoTokens {
+    pub client_id: String,
+    pub client_secret: String,
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RegisterClientResponse {
+    client_id: String,
+    client_secret: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DeviceAuthResponse {
+    device_code: String,
+    user_code: String,
+    #[serde(default)]
+    interval: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceSessionResponse {
+    token: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AcceptUserCodeResponse {
+    device_context: Option<DeviceContext>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DeviceContext {
+    device_context_id: Option<String>,
+    client_id: Option<String>,
+    client_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenErrorResponse {
+    error: Option<String>,
+}
+
+/// 注册 OIDC 客户端并以 Bearer Token 自动批准设备授权，返回获取到的令牌
+pub async fn exchange_sso_token(bearer_token: &str, region: &str) -> Result<SsoTokens, String> {
+    let oidc_base = format!("https://oidc.{}.amazonaws.com", region);
+    
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?;
+
+    // Step 1: 注册 OIDC 客户端
+    println!("[SSO Import] Step 1: 注册 OIDC 客户端...");
+    let scopes = vec![
+        "codewhisperer:analysis",
+        "codewhisperer:completions", 
+        "codewhisperer:conversations",
+        "codewhisperer:taskassist",
+        "codewhisperer:transformations"
+    ];
+    
+    let reg_body = serde_json::json!({
+        "clientName": "Kiro Account Manager",
+        "clientType": "public",
+        "scopes": scopes,
+        "grantTypes": ["urn:ietf:params:oauth:grant-type:device_code", "refresh_token"],
+        "issuerUrl": START_URL
+    });
+    
+    let reg_res = client
+        .post(format!("{}/client/register", oidc_base))
+        .header("Content-Type", "application/json")
+        .json(&reg_body)
+        .send()
+        .await
+        .map_err(|e| format!("注册客户端请求失败: {}", e))?;
+    
+    if !reg_res.status().is_success() {
+        let text = reg_res.text().await.unwrap_or_default();
+        return Err(format!("注册客户端失败: {}", text));
+    }
+    
+    let reg_data: RegisterClientResponse = reg_res.json().await
+        .map_err(|e| format!("解析注册响应失败: {}", e))?;
+    
+    let client_id = reg_data.client_id;
+    let client_secret = reg_data.client_secret;
+    println!("[SSO Import] 客户端已注册: {}...", &client_id[..20.min(client_id.len())]);
+
+    // Step 2: 发起设备授权
+    println!("[SSO Import] Step 2: 发起设备授权...");
+    let dev_body = serde_json::json!({
+        "clientId": client_id,
+        "clientSecret": client_secret,
+        "startUrl": START_URL
+    });
+    
+    let dev_res = client
+        .post(format!("{}/device_authorization", oidc_base))
+        .header("Content-Type", "application/json")
+        .json(&dev_body)
+        .send()
+        .await
+        .map_err(|e| format!("设备授权请求失败: {}", e))?;
+    
+    if !dev_res.status().is_success() {
+        let text = dev_res.text().await.unwrap_or_default();
+        return Err(format!("设备授权失败: {}", text));
+    }
+    
+    let dev_data: DeviceAuthResponse = dev_res.json().await
+        .map_err(|e| format!("解析设备授权响应失败: {}", e))?;
+    
+    let device_code = dev_data.device_code;
+    let user_code = dev_data.user_code;
+    let interval = dev_data.interval.unwrap_or(1);
+    println!("[SSO Import] 设备码已获取, user_code: {}", user_code);
+
+    // Step 3: 验证 Bearer Token
+    println!("[SSO Import] Step 3: 验证 Bearer Token...");
+    let who_res = client
+        .get(format!("{}/token/whoAmI", PORTAL_BASE))
+        .header("Authorization", format!("Bearer {}", bearer_token))
+        .header("Accept", "application/json")
+        .send()
+        .await
+        .map_err(|e| format!("验证 Token 请求失败: {}", e))?;
+    
+    if !who_res.status().is_success() {
+        let status = who_res.status();
+        let text = who_res.text().await.unwrap_or_default();
+        return Err(format!("Token 验证失败 ({}): {}", status, text));
+    }
+    println!("[SSO Import] Bearer Token 验证通过");
+
+    // Step 4: 获取设备会话令牌
+    println!("[SSO Import] Step 4: 获取设备会话令牌...");
+    let sess_res = client
+        .post(format!("{}/session/device", PORTAL_BASE))
+        .header("Authorization", format!("Bearer {}", bearer_token))
+        .header("Content-Type", "application/json")
+        .json(&serde_json::json!({}))
+        .send()
+        .await
+        .map_err(|e| format!("获取设备会话请求失败: {}", e))?;
+    
+    if !sess_res.status().is_success() {
+        let text = sess_res.text().await.unwrap_or_default();
+        return Err(format!("获取设备会话失败: {}", text));
+    }
+    
+    let sess_data: DeviceSessionResponse = sess_res.json().await
+        .map_err(|e| format!("解析设备会话响应失败: {}", e))?;
+    
+    let device_session_token = sess_data.token;
+    println!("[SSO Import] 设备会话令牌已获取");
+
+    // Step 5: 接受用户代码
+    println!("[SSO Import] Step 5: 接受用户代码...");
+    let accept_body = serde_json::json!({
+        "userCode": user_code,
+        "userSessionId": device_session_token
+    });
+    
+    let accept_res = client
+        .post(format!("{}/device_authorization/accept_user_code", oidc_base))
+        .header("Content-Type", "application/json")
+        .header("Referer", "https://view.awsapps.com/")
+        .json(&accept_body)
+        .send()
+        .await
+        .map_err(|e| format!("接受用户代码请求失败: {}", e))?;
+    
+    if !accept_res.status().is_success() {
+        let text = accept_res.text().await.unwrap_or_default();
+        return Err(format!("接受用户代码失败: {}", text));
+    }
+    
+    let accept_data: AcceptUserCodeResponse = accept_res.json().await
+        .map_err(|e| format!("解析接受用户代码响应失败: {}", e))?;
+    
+    let device_context = accept_data.device_context;
+    println!("[SSO Import] 用户代码已接受");
+
+    // Step 6: 批准授权
+    if let Some(ref ctx) = device_context {
+        if let Some(ref ctx_id) = ctx.device_context_id {
+            println!("[SSO Import] Step 6: 批准授权...");
+            let approve_body = serde_json::json!({
+                "deviceContext": {
+                    "deviceContextId": ctx_id,
+                    "clientId": ctx.client_id.as_ref().unwrap_or(&client_id),
+                    "clientType": ctx.client_type.as_ref().unwrap_or(&"public".to_string())
+                },
+                "userSessionId": device_session_token
+            });
+            
+            let approve_res = client
+                .post(format!("{}/device_authorization/associate_token", oidc_base))
+                .header("Content-Type", "application/json")
+                .header("Referer", "https://view.awsapps.com/")
+                .json(&approve_body)
+                .send()
+                .await
+                .map_err(|e| format!("批准授权请求失败: {}", e))?;
+            
+            if !approve_res.status().is_success() {
+                let text = approve_res.text().await.unwrap_or_default();
+                return Err(format!("批准授权失败: {}", text));
+            }
+            println!("[SSO Import] 授权已批准");
+        }
+    }
+
+    // Step 7: 轮询获取 Token
+    println!("[SSO Import] Step 7: 轮询获取 Token...");
+    let start_time = std::time::Instant::now();
+    let timeout = std::time::Duration::from_secs(120);
+    let mut current_interval = interval;
+    
+    let token_data = loop {
+        if start_time.elapsed() > timeout {
+            return Err("授权超时，请重试".to_string());
+        }
+        
+        tokio::time::sleep(std::time::Duration::from_secs(current_interval)).await;
+        
+        let token_body = serde_json::json!({
+            "clientId": client_id,
+            "clientSecret": client_secret,
+            "grantType": "urn:ietf:params:oauth:grant-type:device_code",
+            "deviceCode": device_code
+        });
+        
+        let token_res = client
+            .post(format!("{}/token", oidc_base))
+            .header("Content-Type", "application/json")
+            .json(&token_body)
+            .send()
+            .await
+            .map_err(|e| format!("获取 Token 请求失败: {}", e))?;
+        
+        let status = token_res.status();
+        let text = token_res.text().await.unwrap_or_default();
+        
+        if status.is_success() {
+            let data: TokenResponse = serde_json::from_str(&text)
+                .map_err(|e| format!("解析 Token 响应失败: {}", e))?;
+            break data;
+        }
+        
+        if status.as_u16() == 400 {
+            if let Ok(err_data) = serde_json::from_str::<TokenErrorResponse>(&text) {
+                match err_data.error.as_deref() {
+                    Some("authorization_pending") => continue,
+                    Some("slow_down") => {
+                        current_interval += 5;
+                        continue;
+                    }
+                    Some(e) => return Err(format!("Token 获取失败: {}", e)),
+                    None => return Err(format!("Token 获取失败: {}", text)),
+                }
+            }
+        }
+        
+        return Err(format!("Token 获取失败 ({}): {}", status, text));
+    };
+    
+    println!("[SSO Import] Token 获取成功!");
+
+    Ok(SsoTokens {
+        client_id,
+        client_secret,
+        access_token: token_data.access_token,
+        refresh_token: token_data.refresh_token,
+    })
+}
@@ -18,7 +18,7 @@ pub struct SteeringManager;
 impl SteeringManager {
     /// 获取 steering 目录路径
     pub fn steering_dir() -> Option<PathBuf> {
-        dirs::home_dir().map(|h| h.join(".kiro").join("steering"))
+        crate::paths::steering_dir()
     }
 
     /// 读取所有 steering 文件列表
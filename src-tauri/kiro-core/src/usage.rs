@@ -0,0 +1,147 @@
+// Usage 数据解析 - 将账号中的原始 usage_data 转为统一结构
+
+use chrono::{TimeZone, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use crate::account::Account;
+use crate::codewhisperer_client::CodeWhispererUsageResponse;
+
+/// 从账号的原始 usage_data 解析出限额响应
+///
+/// Social 账号存的是 DesktopUsageResponse，IdC 账号存的是 CodeWhispererUsageResponse，
+/// 两者字段一致（后者是超集），统一按后者解析。
+pub fn parse_usage_data(account: &Account) -> Option<CodeWhispererUsageResponse> {
+    let value = account.usage_data.as_ref()?;
+    if value.is_null() {
+        return None;
+    }
+    serde_json::from_value(value.clone()).ok()
+}
+
+/// 秒级时间戳转 RFC 3339
+fn timestamp_to_rfc3339(ts: f64) -> Option<String> {
+    Utc.timestamp_opt(ts as i64, 0).single().map(|dt| dt.to_rfc3339())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NormalizedFreeTrial {
+    pub usage_limit: f64,
+    pub current_usage: f64,
+    pub expires_at: Option<String>,
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NormalizedBonus {
+    pub code: Option<String>,
+    pub name: Option<String>,
+    pub usage_limit: f64,
+    pub current_usage: f64,
+    pub expires_at: Option<String>,
+    pub status: Option<String>,
+}
+
+/// 统一的用量视图（基础额度 + 试用 + 奖励）
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NormalizedUsage {
+    pub account_id: String,
+    pub email: String,
+    pub provider: Option<String>,
+    pub subscription_title: Option<String>,
+    pub subscription_type: Option<String>,
+    pub resource_type: Option<String>,
+    pub unit: Option<String>,
+    pub usage_limit: f64,
+    pub current_usage: f64,
+    pub free_trial: Option<NormalizedFreeTrial>,
+    pub bonuses: Vec<NormalizedBonus>,
+    /// 可用总额度（基础 + 活跃试用 + 活跃奖励）
+    pub total_limit: f64,
+    pub total_usage: f64,
+    pub remaining: f64,
+    pub usage_percent: f64,
+    pub overage_capability: Option<String>,
+    pub overage_rate: Option<f64>,
+    pub overage_cap: Option<f64>,
+    pub currency: Option<String>,
+    pub next_reset: Option<String>,
+    pub days_until_reset: Option<i32>,
+}
+
+fn is_active(status: Option<&str>) -> bool {
+    status.map(|s| s.eq_ignore_ascii_case("ACTIVE")).unwrap_or(false)
+}
+
+/// 解析账号的用量（没有 usage 数据时返回 None）
+pub fn normalize_usage(account: &Account) -> Option<NormalizedUsage> {
+    let usage = parse_usage_data(account)?;
+    let breakdown = usage.usage_breakdown_list.as_ref().and_then(|l| l.first());
+
+    let usage_limit = breakdown
+        .and_then(|b| b.usage_limit_with_precision.or(b.usage_limit.map(|v| v as f64)))
+        .unwrap_or(0.0);
+    let current_usage = breakdown
+        .and_then(|b| b.current_usage_with_precision.or(b.current_usage.map(|v| v as f64)))
+        .unwrap_or(0.0);
+
+    let free_trial = breakdown.and_then(|b| b.free_trial_info.as_ref()).map(|t| NormalizedFreeTrial {
+        usage_limit: t.usage_limit_with_precision.or(t.usage_limit.map(|v| v as f64)).unwrap_or(0.0),
+        current_usage: t.current_usage_with_precision.or(t.current_usage.map(|v| v as f64)).unwrap_or(0.0),
+        expires_at: t.free_trial_expiry.and_then(timestamp_to_rfc3339),
+        status: t.free_trial_status.clone(),
+    });
+
+    let bonuses: Vec<NormalizedBonus> = breakdown
+        .and_then(|b| b.bonuses.as_ref())
+        .map(|list| list.iter().map(|b| NormalizedBonus {
+            code: b.bonus_code.clone(),
+            name: b.display_name.clone(),
+            usage_limit: b.usage_limit.unwrap_or(0.0),
+            current_usage: b.current_usage.unwrap_or(0.0),
+            expires_at: b.expires_at.and_then(timestamp_to_rfc3339),
+            status: b.status.clone(),
+        }).collect())
+        .unwrap_or_default();
+
+    let mut total_limit = usage_limit;
+    let mut total_usage = current_usage;
+    if let Some(t) = free_trial.as_ref().filter(|t| is_active(t.status.as_deref())) {
+        total_limit += t.usage_limit;
+        total_usage += t.current_usage;
+    }
+    for b in bonuses.iter().filter(|b| is_active(b.status.as_deref())) {
+        total_limit += b.usage_limit;
+        total_usage += b.current_usage;
+    }
+
+    let usage_percent = if total_limit > 0.0 { total_usage / total_limit * 100.0 } else { 0.0 };
+
+    Some(NormalizedUsage {
+        account_id: account.id.clone(),
+        email: account.email.clone(),
+        provider: account.provider.clone(),
+        subscription_title: usage.subscription_info.as_ref().and_then(|s| s.subscription_title.clone()),
+        subscription_type: usage.subscription_info.as_ref().and_then(|s| s.subscription_type.clone()),
+        resource_type: breakdown.and_then(|b| b.resource_type.clone()),
+        unit: breakdown.and_then(|b| b.unit.clone()),
+        usage_limit,
+        current_usage,
+        free_trial,
+        bonuses,
+        total_limit,
+        total_usage,
+        remaining: (total_limit - total_usage).max(0.0),
+        usage_percent,
+        overage_capability: usage.subscription_info.as_ref().and_then(|s| s.overage_capability.clone()),
+        overage_rate: breakdown.and_then(|b| b.overage_rate),
+        overage_cap: breakdown.and_then(|b| b.overage_cap_with_precision.or(b.overage_cap.map(|v| v as f64))),
+        currency: breakdown.and_then(|b| b.currency.clone()),
+        next_reset: breakdown.and_then(|b| b.next_date_reset)
+            .or(usage.next_date_reset)
+            .and_then(timestamp_to_rfc3339),
+        days_until_reset: usage.days_until_reset,
+    })
+}
@@ -0,0 +1,70 @@
+// 项目文件夹登记 - 用于管理工作区级的 .kiro 配置（存到 app-settings.json）
+
+use std::path::PathBuf;
+use crate::settings::{get_app_settings_inner, save_app_settings_inner, AppSettings};
+
+/// 已登记的项目文件夹
+pub fn list_workspaces() -> Vec<String> {
+    get_app_settings_inner()
+        .ok()
+        .and_then(|s| s.workspaces)
+        .unwrap_or_default()
+}
+
+/// 按路径查找已登记的项目文件夹（只允许操作登记过的目录）
+pub fn find_workspace(path: &str) -> Result<PathBuf, String> {
+    let normalized = normalize(path);
+    list_workspaces()
+        .into_iter()
+        .find(|w| *w == normalized)
+        .map(PathBuf::from)
+        .ok_or_else(|| format!("项目文件夹未登记: {}", path))
+}
+
+/// 统一路径写法（绝对路径、去掉末尾分隔符）
+/// 不用 canonicalize：Windows 上会得到 \\?\ 前缀的路径
+fn normalize(path: &str) -> String {
+    let trimmed = path.trim().trim_end_matches(['/', '\\']);
+    // 根目录本身不去掉分隔符
+    let path = if trimmed.is_empty() { path.trim() } else { trimmed };
+    std::path::absolute(path)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+fn save_workspaces(workspaces: Vec<String>) -> Result<(), String> {
+    save_app_settings_inner(AppSettings {
+        workspaces: Some(workspaces),
+        ..Default::default()
+    })
+}
+
+/// 登记项目文件夹，返回规范化后的路径
+pub fn add_workspace(path: &str) -> Result<String, String> {
+    if path.trim().is_empty() {
+        return Err("项目文件夹路径不能为空".to_string());
+    }
+    if !std::path::Path::new(path.trim()).is_dir() {
+        return Err(format!("文件夹不存在: {}", path));
+    }
+
+    let normalized = normalize(path);
+    let mut workspaces = list_workspaces();
+    if !workspaces.contains(&normalized) {
+        workspaces.push(normalized.clone());
+        save_workspaces(workspaces)?;
+    }
+    Ok(normalized)
+}
+
+/// 取消登记（不会删除文件夹中的配置）
+pub fn remove_workspace(path: &str) -> Result<(), String> {
+    let normalized = normalize(path);
+    let mut workspaces = list_workspaces();
+    let before = workspaces.len();
+    workspaces.retain(|w| *w != normalized && w != path);
+    if workspaces.len() == before {
+        return Err(format!("项目文件夹未登记: {}", path));
+    }
+    save_workspaces(workspaces)
+}
@@ -5,6 +5,7 @@ use std::path::PathBuf;
 use std::sync::Mutex;
 use chrono::{DateTime, Local};
 use uuid::Uuid;
+use kiro_core::paths::app_data_dir;
 
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
@@ -301,13 +302,7 @@ impl AutoRegisterConfigStore {
     }
 
     fn get_storage_paths() -> (PathBuf, PathBuf) {
-        let data_dir = dirs::data_dir().unwrap_or_else(|| {
-            let home = std::env::var("USERPROFILE")
-                .or_else(|_| std::env::var("HOME"))
-                .unwrap_or_else(|_| ".".to_string());
-            PathBuf::from(home)
-        });
-        let base_dir = data_dir.join(".kiro-account-manager");
+        let base_dir = app_data_dir();
         (
             base_dir.join("auto_register_config.json"),
             base_dir.join("auto_register_history.json"),
@@ -777,16 +772,10 @@ pub fn get_scripts_dir() -> PathBuf {
         }
         
         // 最后回退到用户数据目录
-        dirs::data_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join(".kiro-account-manager")
-            .join("scripts")
+        app_data_dir().join("scripts")
     } else {
         // 生产模式下使用应用资源目录
-        dirs::data_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join(".kiro-account-manager")
-            .join("scripts")
+        app_data_dir().join("scripts")
     }
 }
 
@@ -866,5 +855,9 @@ pub fn build_script_env(config: &AutoRegisterConfig) -> Vec<(String, String)> {
         ("EMAIL_DOMAIN".to_string(), config.register.email_domain.clone()),
         ("EMAIL_TIMEOUT".to_string(), config.email.timeout.to_string()),
         ("EMAIL_POLL_INTERVAL".to_string(), config.email.poll_interval.to_string()),
+        // 本地 HTTP API 令牌，脚本调用接口时需要
+        ("KIRO_MANAGER_API_TOKEN".to_string(), crate::http_auth::load_or_create_api_token().unwrap_or_default()),
+        // 实际监听地址（端口可能因占用而变化）
+        ("KIRO_MANAGER_API_URL".to_string(), crate::http_server::get_http_server_status().base_url.unwrap_or_default()),
     ]
 }
@@ -0,0 +1,589 @@
+// kiro-accounts - 命令行账号管理工具，与 GUI 共用账号文件与切换逻辑
+
+use std::io::{IsTerminal, Read, Write};
+use std::process::ExitCode;
+use std::sync::Mutex;
+use std::time::Duration;
+use serde::Serialize;
+use kiro_core::account::{Account, AccountStore};
+use kiro_core::account_service::{poll_device_auth, start_device_auth, sync_account, DeviceAuthPoll};
+use kiro_core::kiro::{switch_kiro_account_inner, SwitchAccountParams};
+use kiro_core::usage::{normalize_usage, NormalizedUsage};
+use kiro_account_manager_lib::http_client::LocalApiClient;
+use kiro_account_manager_lib::http_types::{SyncAccountResult, SyncAccountsResponse};
+
+const USAGE: &str = "\
+用法: kiro-accounts [--json] [--verbose] <命令> [参数]
+
+命令:
+  list [--include-secrets]                      列出所有账号
+  show <账号> [--include-secrets]               显示账号详情
+  add --device-flow [--region <区域>] [--no-browser]
+                                                通过 AWS Builder ID 设备授权添加账号
+  import <文件|->                               从 JSON 文件（或标准输入）导入账号
+  export [文件] [--ids <id,id,...>]             导出账号 JSON（包含 token，请妥善保管）
+  sync <账号>... | --all                        刷新 token 并更新用量
+  switch <账号> [--reset-machine-id] [--restart] [--force-close] [--installation <id>]
+                                                将 Kiro IDE（默认或已登记的安装）切换到指定账号
+  delete <账号>... [--yes]                      删除账号
+  usage [<账号>...]                             显示用量
+
+<账号> 可以是账号 ID、ID 前缀或邮箱。
+
+全局选项:
+  --json       以 JSON 输出结果，便于脚本处理
+  --verbose    输出内部日志（写到标准错误）
+  -h, --help   显示帮助
+  -V, --version
+
+环境变量:
+  KIRO_AM_DATA_DIR       本程序数据目录（默认为系统数据目录下的 .kiro-account-manager）
+  KIRO_AM_HOME           替代用户目录（~/.aws、~/.kiro 与 IDE 数据目录都以此为基准）
+  KIRO_AM_IDE_DATA_DIR   Kiro IDE 数据目录";
+
+/// 通知运行中的实例重新加载账号的超时时间
+const NOTIFY_TIMEOUT: Duration = Duration::from_secs(2);
+
+struct Cli {
+    json: bool,
+    /// 结果输出（标准输出）；库函数的日志不会混入
+    out: Box<dyn Write>,
+    /// 是否已经输出过结果
+    printed: bool,
+    rt: tokio::runtime::Runtime,
+}
+
+/// 从参数中取出一个开关
+fn take_flag(args: &mut Vec<String>, names: &[&str]) -> bool {
+    let before = args.len();
+    args.retain(|a| !names.contains(&a.as_str()));
+    args.len() != before
+}
+
+/// 从参数中取出一个带值的选项
+fn take_option(args: &mut Vec<String>, name: &str) -> Result<Option<String>, String> {
+    match args.iter().position(|a| a == name) {
+        Some(i) if i + 1 < args.len() => {
+            let value = args.remove(i + 1);
+            args.remove(i);
+            Ok(Some(value))
+        }
+        Some(_) => Err(format!("{} 缺少参数值", name)),
+        None => Ok(None),
+    }
+}
+
+/// 剩余参数中不应再有选项
+fn positional(args: Vec<String>) -> Result<Vec<String>, String> {
+    match args.iter().find(|a| a.starts_with('-') && a.as_str() != "-") {
+        Some(unknown) => Err(format!("未知选项: {}", unknown)),
+        None => Ok(args),
+    }
+}
+
+/// 库函数用 println! 输出日志；将其转到标准错误（--verbose）或丢弃，
+/// 结果写到原来的标准输出，保证 --json 的输出可以直接被脚本解析
+#[cfg(unix)]
+fn redirect_library_logs(verbose: bool) -> Box<dyn Write> {
+    use std::os::unix::io::{AsRawFd, FromRawFd};
+
+    let sink = if verbose {
+        None
+    } else {
+        std::fs::OpenOptions::new().write(true).open("/dev/null").ok()
+    };
+    let target = sink.as_ref().map(|f| f.as_raw_fd()).unwrap_or(libc::STDERR_FILENO);
+
+    let _ = std::io::stdout().flush();
+    // SAFETY: 只复制标准输出描述符；dup 得到的新描述符交给 File 独占
+    unsafe {
+        let saved = libc::dup(libc::STDOUT_FILENO);
+        if saved >= 0 && libc::dup2(target, libc::STDOUT_FILENO) != -1 {
+            return Box::new(std::fs::File::from_raw_fd(saved));
+        }
+    }
+    Box::new(std::io::stdout())
+}
+
+#[cfg(not(unix))]
+fn redirect_library_logs(_verbose: bool) -> Box<dyn Write> {
+    Box::new(std::io::stdout())
+}
+
+/// 按 ID、邮箱或唯一的 ID 前缀查找账号
+fn resolve<'a>(accounts: &'a [Account], key: &str) -> Result<&'a Account, String> {
+    if let Some(account) = accounts.iter().find(|a| a.id == key) {
+        return Ok(account);
+    }
+    let by_email: Vec<&Account> = accounts.iter().filter(|a| a.email.eq_ignore_ascii_case(key)).collect();
+    let matches = if by_email.is_empty() {
+        accounts.iter().filter(|a| a.id.starts_with(key)).collect()
+    } else {
+        by_email
+    };
+    match matches.as_slice() {
+        [account] => Ok(account),
+        [] => Err(format!("未找到账号: {}", key)),
+        many => Err(format!(
+            "{} 匹配到多个账号: {}",
+            key,
+            many.iter().map(|a| format!("{} ({})", a.id, a.email)).collect::<Vec<_>>().join(", ")
+        )),
+    }
+}
+
+/// 终端显示宽度（中日韩字符按两列计算）
+fn display_width(s: &str) -> usize {
+    s.chars().map(|c| if c as u32 >= 0x2E80 { 2 } else { 1 }).sum()
+}
+
+fn format_amount(v: f64) -> String {
+    if v.fract() == 0.0 {
+        format!("{}", v as i64)
+    } else {
+        format!("{:.2}", v)
+    }
+}
+
+fn usage_summary(usage: Option<&NormalizedUsage>) -> String {
+    match usage {
+        Some(u) => format!("{}/{}", format_amount(u.total_usage), format_amount(u.total_limit)),
+        None => "-".to_string(),
+    }
+}
+
+/// AWS Builder ID 设备授权：打印验证地址并轮询，成功后保存账号
+async fn add_by_device_flow(region: &str, no_browser: bool) -> Result<Account, String> {
+    let info = start_device_auth(region).await?;
+
+    let url = info.url();
+    // 提示写到标准错误，不影响 --json 输出
+    eprintln!("请在浏览器中打开以下地址完成授权:");
+    eprintln!("  {}", url);
+    eprintln!("验证码: {}", info.user_code);
+    if !no_browser && open::that(&url).is_err() {
+        eprintln!("无法自动打开浏览器，请手动打开上面的地址");
+    }
+
+    let store = Mutex::new(AccountStore::new());
+    let mut interval = info.interval.max(1) as u64;
+    let deadline = std::time::Instant::now() + Duration::from_secs(info.expires_in.max(0) as u64);
+    loop {
+        if std::time::Instant::now() > deadline {
+            return Err("授权已过期".to_string());
+        }
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+        match poll_device_auth(&store, &info.device_code, &info.client_id, &info.client_secret, region).await? {
+            DeviceAuthPoll::Success(account) => return Ok(*account),
+            DeviceAuthPoll::Pending => continue,
+            DeviceAuthPoll::SlowDown => interval += 5,
+            DeviceAuthPoll::Expired => return Err("授权已过期".to_string()),
+            DeviceAuthPoll::Denied => return Err("授权被拒绝".to_string()),
+        }
+    }
+}
+
+impl Cli {
+    fn print_json<T: Serialize>(&mut self, value: &T) -> Result<(), String> {
+        let text = serde_json::to_string_pretty(value)
+            .map_err(|e| format!("序列化失败: {}", e))?;
+        self.line(&text);
+        Ok(())
+    }
+
+    fn line(&mut self, text: &str) {
+        self.printed = true;
+        let _ = writeln!(self.out, "{}", text);
+    }
+
+    fn table(&mut self, headers: &[&str], rows: Vec<Vec<String>>) {
+        let mut widths: Vec<usize> = headers.iter().map(|h| display_width(h)).collect();
+        for row in &rows {
+            for (w, cell) in widths.iter_mut().zip(row) {
+                *w = (*w).max(display_width(cell));
+            }
+        }
+        let render = |cells: Vec<String>| {
+            let last = cells.len().saturating_sub(1);
+            cells.iter().enumerate()
+                .map(|(i, c)| {
+                    if i == last {
+                        c.clone()
+                    } else {
+                        format!("{}{}", c, " ".repeat(widths[i] - display_width(c)))
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("  ")
+        };
+        let header = render(headers.iter().map(|h| h.to_string()).collect());
+        self.line(&header);
+        for row in rows {
+            let text = render(row);
+            self.line(&text);
+        }
+    }
+
+    /// 通知正在运行的 GUI / 无界面实例重新加载账号（尽力而为，失败不影响命令结果）
+    fn notify_running_instance(&self) {
+        let client = match LocalApiClient::discover() {
+            Ok(c) => c,
+            Err(_) => return,
+        };
+        let _ = self.rt.block_on(tokio::time::timeout(NOTIFY_TIMEOUT, client.reload_accounts()));
+    }
+
+    fn list(&mut self, mut args: Vec<String>) -> Result<(), String> {
+        let include_secrets = take_flag(&mut args, &["--include-secrets"]);
+        positional(args)?;
+
+        let accounts = AccountStore::new().get_all();
+        if self.json {
+            let accounts: Vec<Account> = if include_secrets {
+                accounts
+            } else {
+                accounts.iter().map(Account::redacted).collect()
+            };
+            return self.print_json(&accounts);
+        }
+
+        if accounts.is_empty() {
+            self.line("没有账号");
+            return Ok(());
+        }
+        let rows = accounts.iter()
+            .map(|a| vec![
+                a.id.clone(),
+                a.email.clone(),
+                a.provider.clone().unwrap_or_else(|| "-".to_string()),
+                a.status.clone(),
+                usage_summary(normalize_usage(a).as_ref()),
+                a.expires_at.clone().unwrap_or_else(|| "-".to_string()),
+            ])
+            .collect();
+        self.table(&["ID", "EMAIL", "PROVIDER", "STATUS", "USAGE", "EXPIRES"], rows);
+        Ok(())
+    }
+
+    fn show(&mut self, mut args: Vec<String>) -> Result<(), String> {
+        let include_secrets = take_flag(&mut args, &["--include-secrets"]);
+        let args = positional(args)?;
+        let key = match args.as_slice() {
+            [key] => key,
+            _ => return Err("用法: kiro-accounts show <账号>".to_string()),
+        };
+
+        let accounts = AccountStore::new().get_all();
+        let account = resolve(&accounts, key)?;
+        if self.json {
+            let account = if include_secrets { account.clone() } else { account.redacted() };
+            return self.print_json(&account);
+        }
+
+        let usage = normalize_usage(account);
+        let or_dash = |v: &Option<String>| v.clone().unwrap_or_else(|| "-".to_string());
+        let fields = [
+            ("ID", account.id.clone()),
+            ("邮箱", account.email.clone()),
+            ("备注", account.label.clone()),
+            ("状态", account.status.clone()),
+            ("登录方式", or_dash(&account.provider)),
+            ("添加时间", account.added_at.clone()),
+            ("Token 过期", or_dash(&account.expires_at)),
+            ("区域", or_dash(&account.region)),
+            ("订阅", or_dash(&usage.as_ref().and_then(|u| u.subscription_title.clone()))),
+            ("用量", usage_summary(usage.as_ref())),
+            ("下次重置", or_dash(&usage.as_ref().and_then(|u| u.next_reset.clone()))),
+        ];
+        let width = fields.iter().map(|(k, _)| display_width(k)).max().unwrap_or(0);
+        for (key, value) in fields {
+            let text = format!("{}{}  {}", key, " ".repeat(width - display_width(key)), value);
+            self.line(&text);
+        }
+        Ok(())
+    }
+
+    fn add(&mut self, mut args: Vec<String>) -> Result<(), String> {
+        let device_flow = take_flag(&mut args, &["--device-flow"]);
+        let no_browser = take_flag(&mut args, &["--no-browser"]);
+        let region = take_option(&mut args, "--region")?.unwrap_or_else(|| "us-east-1".to_string());
+        positional(args)?;
+        if !device_flow {
+            return Err("目前仅支持 --device-flow（AWS Builder ID 设备授权）".to_string());
+        }
+
+        let account = self.rt.block_on(add_by_device_flow(&region, no_browser))?;
+
+        self.notify_running_instance();
+        if self.json {
+            return self.print_json(&account.redacted());
+        }
+        let text = format!("已添加账号 {} ({})", account.email, account.id);
+        self.line(&text);
+        Ok(())
+    }
+
+    fn import(&mut self, args: Vec<String>) -> Result<(), String> {
+        let args = positional(args)?;
+        let source = match args.as_slice() {
+            [source] => source.as_str(),
+            _ => return Err("用法: kiro-accounts import <文件|->".to_string()),
+        };
+
+        let json = if source == "-" {
+            let mut buf = String::new();
+            std::io::stdin().read_to_string(&mut buf)
+                .map_err(|e| format!("读取标准输入失败: {}", e))?;
+            buf
+        } else {
+            std::fs::read_to_string(source)
+                .map_err(|e| format!("读取 {} 失败: {}", source, e))?
+        };
+
+        let mut store = AccountStore::new();
+        let before = store.accounts.len();
+        let total = store.import_from_json(&json)?;
+        let added = store.accounts.len() - before;
+        self.notify_running_instance();
+
+        if self.json {
+            return self.print_json(&serde_json::json!({ "total": total, "added": added, "skipped": total - added }));
+        }
+        let text = format!("已导入 {} 个账号（{} 个已存在，已跳过）", added, total - added);
+        self.line(&text);
+        Ok(())
+    }
+
+    fn export(&mut self, mut args: Vec<String>) -> Result<(), String> {
+        let ids: Option<Vec<String>> = take_option(&mut args, "--ids")?
+            .map(|s| s.split(',').map(|id| id.trim().to_string()).filter(|id| !id.is_empty()).collect());
+        let args = positional(args)?;
+
+        let accounts = AccountStore::new().get_all();
+        let selected: Vec<&Account> = match &ids {
+            Some(keys) => keys.iter().map(|k| resolve(&accounts, k)).collect::<Result<_, _>>()?,
+            None => accounts.iter().collect(),
+        };
+        let content = serde_json::to_string_pretty(&selected)
+            .map_err(|e| format!("序列化失败: {}", e))?;
+
+        match args.as_slice() {
+            [] => {
+                self.line(&content);
+                Ok(())
+            }
+            [path] => {
+                std::fs::write(path, content).map_err(|e| format!("写入 {} 失败: {}", path, e))?;
+                if self.json {
+                    return self.print_json(&serde_json::json!({ "exported": selected.len(), "path": path }));
+                }
+                let text = format!("已导出 {} 个账号到 {}", selected.len(), path);
+                self.line(&text);
+                Ok(())
+            }
+            _ => Err("用法: kiro-accounts export [文件] [--ids <id,id,...>]".to_string()),
+        }
+    }
+
+    fn sync(&mut self, mut args: Vec<String>) -> Result<(), String> {
+        let all = take_flag(&mut args, &["--all"]);
+        let keys = positional(args)?;
+
+        let store = Mutex::new(AccountStore::new());
+        let ids: Vec<String> = {
+            let store = store.lock().unwrap();
+            if all {
+                store.accounts.iter().map(|a| a.id.clone()).collect()
+            } else if keys.is_empty() {
+                return Err("用法: kiro-accounts sync <账号>... | --all".to_string());
+            } else {
+                keys.iter().map(|k| resolve(&store.accounts, k).map(|a| a.id.clone())).collect::<Result<_, _>>()?
+            }
+        };
+
+        let mut results = Vec::with_capacity(ids.len());
+        for id in ids {
+            let result = self.rt.block_on(sync_account(&store, &id));
+            if !self.json {
+                let text = match &result {
+                    Ok(a) => format!("✓ {}  {}", a.email, usage_summary(normalize_usage(a).as_ref())),
+                    Err(e) => format!("✗ {}  {}", id, e),
+                };
+                self.line(&text);
+            }
+            results.push(match result {
+                Ok(account) => SyncAccountResult { id, success: true, account: Some(account.redacted()), error: None },
+                Err(e) => SyncAccountResult { id, success: false, account: None, error: Some(e) },
+            });
+        }
+        self.notify_running_instance();
+
+        let failed = results.iter().filter(|r| !r.success).count();
+        if self.json {
+            self.print_json(&SyncAccountsResponse { succeeded: results.len() - failed, failed, results })?;
+        }
+        if failed > 0 {
+            return Err(format!("{} 个账号同步失败", failed));
+        }
+        Ok(())
+    }
+
+    fn switch(&mut self, mut args: Vec<String>) -> Result<(), String> {
+        let reset_machine_id = take_flag(&mut args, &["--reset-machine-id"]);
+        let auto_restart = take_flag(&mut args, &["--restart"]);
+        let force_close = take_flag(&mut args, &["--force-close"]);
+        let installation_id = take_option(&mut args, "--installation")?;
+        let args = positional(args)?;
+        let key = match args.as_slice() {
+            [key] => key,
+            _ => return Err("用法: kiro-accounts switch <账号> [--reset-machine-id] [--restart] [--force-close] [--installation <id>]".to_string()),
+        };
+
+        let accounts = AccountStore::new().get_all();
+        let account = resolve(&accounts, key)?;
+        let mut params = SwitchAccountParams::from_account(account, reset_machine_id, auto_restart)?;
+        params.force_close = Some(force_close);
+        params.installation_id = installation_id;
+        let result = switch_kiro_account_inner(params)?;
+
+        if self.json {
+            self.print_json(&result)?;
+            if result.awaiting_force_close {
+                return Err("Kiro IDE 未能正常退出".to_string());
+            }
+            return Ok(());
+        }
+        if result.awaiting_force_close {
+            return Err(format!(
+                "{}\n请先在 IDE 中保存或放弃修改；确认要强制关闭时加上 --force-close",
+                result.message
+            ));
+        }
+        let text = format!("已切换到 {}：{}", account.email, result.message);
+        self.line(&text);
+        Ok(())
+    }
+
+    fn delete(&mut self, mut args: Vec<String>) -> Result<(), String> {
+        let yes = take_flag(&mut args, &["--yes", "-y"]);
+        let keys = positional(args)?;
+        if keys.is_empty() {
+            return Err("用法: kiro-accounts delete <账号>... [--yes]".to_string());
+        }
+
+        let mut store = AccountStore::new();
+        let targets: Vec<Account> = keys.iter()
+            .map(|k| resolve(&store.accounts, k).cloned())
+            .collect::<Result<_, _>>()?;
+
+        // 交互式终端中需要确认；脚本调用时用 --yes 跳过
+        if !yes && std::io::stdin().is_terminal() {
+            for account in &targets {
+                eprintln!("  {} ({})", account.email, account.id);
+            }
+            eprint!("确认删除以上 {} 个账号? [y/N] ", targets.len());
+            let mut answer = String::new();
+            let _ = std::io::stdin().read_line(&mut answer);
+            if !matches!(answer.trim(), "y" | "Y" | "yes") {
+                return Err("已取消".to_string());
+            }
+        }
+
+        let ids: Vec<String> = targets.iter().map(|a| a.id.clone()).collect();
+        let deleted = store.delete_many(&ids);
+        self.notify_running_instance();
+
+        if self.json {
+            return self.print_json(&serde_json::json!({ "deleted": deleted, "ids": ids }));
+        }
+        let text = format!("已删除 {} 个账号", deleted);
+        self.line(&text);
+        Ok(())
+    }
+
+    fn usage(&mut self, args: Vec<String>) -> Result<(), String> {
+        let keys = positional(args)?;
+        let accounts = AccountStore::new().get_all();
+        let selected: Vec<&Account> = if keys.is_empty() {
+            accounts.iter().collect()
+        } else {
+            keys.iter().map(|k| resolve(&accounts, k)).collect::<Result<_, _>>()?
+        };
+
+        let usages: Vec<NormalizedUsage> = selected.iter().filter_map(|a| normalize_usage(a)).collect();
+        if self.json {
+            return self.print_json(&usages);
+        }
+
+        if usages.is_empty() {
+            self.line("没有用量数据，请先执行 kiro-accounts sync");
+            return Ok(());
+        }
+        let rows = usages.iter()
+            .map(|u| vec![
+                u.email.clone(),
+                u.subscription_title.clone().unwrap_or_else(|| "-".to_string()),
+                format!("{}/{}", format_amount(u.total_usage), format_amount(u.total_limit)),
+                format_amount(u.remaining),
+                format!("{:.1}%", u.usage_percent),
+                u.next_reset.clone().unwrap_or_else(|| "-".to_string()),
+            ])
+            .collect();
+        self.table(&["EMAIL", "PLAN", "USED", "REMAINING", "PERCENT", "RESET"], rows);
+        Ok(())
+    }
+}
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let json = take_flag(&mut args, &["--json"]);
+    let verbose = take_flag(&mut args, &["--verbose"]);
+
+    if take_flag(&mut args, &["-V", "--version"]) {
+        println!("kiro-accounts {}", env!("CARGO_PKG_VERSION"));
+        return ExitCode::SUCCESS;
+    }
+    if args.is_empty() || take_flag(&mut args, &["-h", "--help"]) {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    let rt = match tokio::runtime::Runtime::new() {
+        Ok(rt) => rt,
+        Err(e) => {
+            eprintln!("错误: 创建 tokio runtime 失败: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut cli = Cli { json, out: redirect_library_logs(verbose), printed: false, rt };
+
+    let command = args.remove(0);
+    let result = match command.as_str() {
+        "list" | "ls" => cli.list(args),
+        "show" => cli.show(args),
+        "add" => cli.add(args),
+        "import" => cli.import(args),
+        "export" => cli.export(args),
+        "sync" => cli.sync(args),
+        "switch" => cli.switch(args),
+        "delete" | "rm" => cli.delete(args),
+        "usage" => cli.usage(args),
+        other => {
+            eprintln!("未知命令: {}\n\n{}", other, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    let _ = cli.out.flush();
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            // 命令已输出部分结果（如批量同步）时不再追加错误对象
+            if json && !cli.printed {
+                let _ = writeln!(cli.out, "{}", serde_json::json!({ "success": false, "error": e }));
+            }
+            eprintln!("错误: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
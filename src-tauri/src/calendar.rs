@@ -0,0 +1,209 @@
+// iCalendar 导出 - 奖励/试用到期与配额重置日期
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use kiro_core::account::Account;
+use kiro_core::usage::parse_usage_data;
+
+const PRODID: &str = "-//Kiro Account Manager//Usage Calendar//EN";
+
+/// 日历事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalendarEventKind {
+    BonusExpiry,
+    FreeTrialExpiry,
+    UsageReset,
+}
+
+impl CalendarEventKind {
+    fn uid_tag(&self) -> &'static str {
+        match self {
+            Self::BonusExpiry => "bonus",
+            Self::FreeTrialExpiry => "trial",
+            Self::UsageReset => "reset",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CalendarEvent {
+    pub kind: CalendarEventKind,
+    pub account_id: String,
+    pub summary: String,
+    pub description: String,
+    pub at: DateTime<Utc>,
+    /// 区分同一账号下的多个奖励
+    pub discriminator: String,
+}
+
+fn timestamp_to_utc(ts: f64) -> Option<DateTime<Utc>> {
+    Utc.timestamp_opt(ts as i64, 0).single()
+}
+
+/// 收集单个账号的到期/重置事件
+pub fn collect_account_events(account: &Account) -> Vec<CalendarEvent> {
+    let mut events = Vec::new();
+    let usage = match parse_usage_data(account) {
+        Some(u) => u,
+        None => return events,
+    };
+
+    let breakdown = usage.usage_breakdown_list.as_ref().and_then(|l| l.first());
+
+    // 配额重置
+    let reset_ts = breakdown.and_then(|b| b.next_date_reset).or(usage.next_date_reset);
+    if let Some(at) = reset_ts.and_then(timestamp_to_utc) {
+        events.push(CalendarEvent {
+            kind: CalendarEventKind::UsageReset,
+            account_id: account.id.clone(),
+            summary: format!("Kiro 配额重置 - {}", account.email),
+            description: format!("账号 {} ({}) 的使用配额将在此时重置", account.email, account.label),
+            at,
+            discriminator: String::new(),
+        });
+    }
+
+    let breakdown = match breakdown {
+        Some(b) => b,
+        None => return events,
+    };
+
+    // 试用到期
+    if let Some(trial) = &breakdown.free_trial_info {
+        if let Some(at) = trial.free_trial_expiry.and_then(timestamp_to_utc) {
+            let limit = trial.usage_limit_with_precision
+                .or(trial.usage_limit.map(|v| v as f64))
+                .unwrap_or(0.0);
+            let used = trial.current_usage_with_precision
+                .or(trial.current_usage.map(|v| v as f64))
+                .unwrap_or(0.0);
+            events.push(CalendarEvent {
+                kind: CalendarEventKind::FreeTrialExpiry,
+                account_id: account.id.clone(),
+                summary: format!("Kiro 免费试用到期 - {}", account.email),
+                description: format!(
+                    "账号 {} 的免费试用将到期（已用 {} / {}，状态: {}）",
+                    account.email,
+                    used,
+                    limit,
+                    trial.free_trial_status.as_deref().unwrap_or("UNKNOWN")
+                ),
+                at,
+                discriminator: String::new(),
+            });
+        }
+    }
+
+    // 奖励额度到期
+    for (idx, bonus) in breakdown.bonuses.iter().flatten().enumerate() {
+        if let Some(at) = bonus.expires_at.and_then(timestamp_to_utc) {
+            let name = bonus.display_name.clone()
+                .or_else(|| bonus.bonus_code.clone())
+                .unwrap_or_else(|| "Bonus".to_string());
+            events.push(CalendarEvent {
+                kind: CalendarEventKind::BonusExpiry,
+                account_id: account.id.clone(),
+                summary: format!("Kiro 奖励额度到期: {} - {}", name, account.email),
+                description: format!(
+                    "账号 {} 的奖励 {} 将到期（已用 {} / {}）",
+                    account.email,
+                    name,
+                    bonus.current_usage.unwrap_or(0.0),
+                    bonus.usage_limit.unwrap_or(0.0)
+                ),
+                at,
+                discriminator: bonus.bonus_code.clone().unwrap_or_else(|| idx.to_string()),
+            });
+        }
+    }
+
+    events
+}
+
+/// 转义 TEXT 类型的属性值（RFC 5545 3.3.11）
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// 按 75 字节折行（RFC 5545 3.1），不拆分 UTF-8 字符
+fn fold_line(line: &str, out: &mut String) {
+    let mut width = 0;
+    for ch in line.chars() {
+        let len = ch.len_utf8();
+        if width + len > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(ch);
+        width += len;
+    }
+    out.push_str("\r\n");
+}
+
+fn format_utc(dt: &DateTime<Utc>) -> String {
+    dt.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// 生成 ICS 文本，每个到期/重置时间一个 VEVENT
+pub fn generate_ics(accounts: &[Account]) -> (String, usize) {
+    let now = format_utc(&Utc::now());
+    let mut lines: Vec<String> = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", PRODID),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "X-WR-CALNAME:Kiro Accounts".to_string(),
+    ];
+
+    let mut count = 0;
+    for account in accounts {
+        for event in collect_account_events(account) {
+            let uid = if event.discriminator.is_empty() {
+                format!("{}-{}@kiro-account-manager", event.account_id, event.kind.uid_tag())
+            } else {
+                format!("{}-{}-{}@kiro-account-manager", event.account_id, event.kind.uid_tag(), event.discriminator)
+            };
+            lines.push("BEGIN:VEVENT".to_string());
+            lines.push(format!("UID:{}", escape_text(&uid)));
+            lines.push(format!("DTSTAMP:{}", now));
+            lines.push(format!("DTSTART:{}", format_utc(&event.at)));
+            lines.push(format!("DTEND:{}", format_utc(&(event.at + Duration::minutes(30)))));
+            lines.push(format!("SUMMARY:{}", escape_text(&event.summary)));
+            lines.push(format!("DESCRIPTION:{}", escape_text(&event.description)));
+            lines.push("TRANSP:TRANSPARENT".to_string());
+            // 到期事件提前一天提醒
+            if event.kind != CalendarEventKind::UsageReset {
+                lines.push("BEGIN:VALARM".to_string());
+                lines.push("ACTION:DISPLAY".to_string());
+                lines.push(format!("DESCRIPTION:{}", escape_text(&event.summary)));
+                lines.push("TRIGGER:-P1D".to_string());
+                lines.push("END:VALARM".to_string());
+            }
+            lines.push("END:VEVENT".to_string());
+            count += 1;
+        }
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out = String::new();
+    for line in &lines {
+        fold_line(line, &mut out);
+    }
+    (out, count)
+}
+
+/// 按 ID 过滤账号（未指定或为空时返回全部）
+pub fn select_accounts(accounts: &[Account], ids: Option<&[String]>) -> Vec<Account> {
+    match ids {
+        Some(list) if !list.is_empty() => accounts.iter()
+            .filter(|a| list.contains(&a.id))
+            .cloned()
+            .collect(),
+        _ => accounts.to_vec(),
+    }
+}
@@ -0,0 +1,8 @@
+// 浏览器检测命令
+
+use kiro_core::browser::{detect_browsers, DetectedBrowser};
+
+#[tauri::command]
+pub async fn detect_installed_browsers() -> Vec<DetectedBrowser> {
+    detect_browsers()
+}
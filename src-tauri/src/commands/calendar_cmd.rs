@@ -0,0 +1,40 @@
+// 日历导出命令
+
+use tauri::State;
+use crate::state::AppState;
+use crate::calendar::{generate_ics, select_accounts};
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarExportResult {
+    pub path: String,
+    pub event_count: usize,
+}
+
+/// 将选中账号的奖励/试用到期与配额重置日期导出为 .ics 文件
+#[tauri::command]
+pub fn export_calendar_ics(
+    state: State<AppState>,
+    ids: Option<Vec<String>>,
+    path: String,
+) -> Result<CalendarExportResult, String> {
+    let accounts = {
+        let store = state.store.lock().unwrap();
+        select_accounts(&store.accounts, ids.as_deref())
+    };
+
+    let (ics, event_count) = generate_ics(&accounts);
+
+    let file_path = std::path::PathBuf::from(&path);
+    if let Some(parent) = file_path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("创建目录失败: {}", e))?;
+        }
+    }
+    std::fs::write(&file_path, ics)
+        .map_err(|e| format!("写入日历文件失败: {}", e))?;
+
+    println!("[Calendar] Exported {} events to {}", event_count, path);
+    Ok(CalendarExportResult { path, event_count })
+}
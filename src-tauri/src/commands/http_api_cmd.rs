@@ -0,0 +1,58 @@
+// 本地 HTTP API 管理命令
+
+use tauri::State;
+use crate::state::AppState;
+use kiro_core::settings::{save_app_settings_inner, AppSettings};
+use crate::http_auth::{load_or_create_api_token, regenerate_api_token};
+use crate::http_server::{get_http_server_status as get_status, start_http_server, stop_http_server, HttpServerStatus};
+
+/// 获取本地 HTTP API 令牌（供外部脚本配置 Authorization 头）
+#[tauri::command]
+pub fn get_http_api_token() -> Result<String, String> {
+    load_or_create_api_token()
+}
+
+/// 重新生成本地 HTTP API 令牌，旧令牌立即失效
+#[tauri::command]
+pub fn regenerate_http_api_token() -> Result<String, String> {
+    regenerate_api_token()
+}
+
+/// 开启/关闭本地 HTTP API，立即生效并写入设置
+#[tauri::command]
+pub async fn set_http_server_enabled(state: State<'_, AppState>, enabled: bool) -> Result<(), String> {
+    let store = state.store.clone();
+    tokio::task::spawn_blocking(move || {
+        save_app_settings_inner(AppSettings {
+            http_server_enabled: Some(enabled),
+            ..Default::default()
+        })?;
+        if enabled {
+            start_http_server(store);
+        } else {
+            stop_http_server();
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// 获取本地 HTTP API 状态（已监听 / 失败 / 已禁用）及实际地址
+#[tauri::command]
+pub fn get_http_server_status() -> HttpServerStatus {
+    get_status()
+}
+
+/// 重启本地 HTTP API，使新的监听地址、端口等设置生效
+#[tauri::command]
+pub async fn restart_http_server(state: State<'_, AppState>) -> Result<HttpServerStatus, String> {
+    let store = state.store.clone();
+    tokio::task::spawn_blocking(move || {
+        stop_http_server();
+        start_http_server(store);
+        get_status()
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))
+}
@@ -0,0 +1,19 @@
+// Kiro IDE 安装登记命令
+
+use kiro_core::installations::{self, KiroInstallation};
+
+#[tauri::command]
+pub fn list_kiro_installations() -> Vec<KiroInstallation> {
+    installations::list_installations()
+}
+
+/// 新增或更新安装（id 为空时新增），返回保存后的安装
+#[tauri::command]
+pub fn save_kiro_installation(installation: KiroInstallation) -> Result<KiroInstallation, String> {
+    installations::save_installation(installation)
+}
+
+#[tauri::command]
+pub fn remove_kiro_installation(id: String) -> Result<(), String> {
+    installations::remove_installation(&id)
+}
@@ -0,0 +1,35 @@
+// Kiro IDE 命令（本地 Token、遥测信息、切换账号、重置机器 ID）
+// installation_id 为空时操作默认安装
+
+use kiro_core::kiro::{
+    self, KiroLocalToken, KiroTelemetryInfo, SwitchAccountParams, SwitchAccountResult,
+};
+
+#[tauri::command]
+pub fn get_kiro_local_token(installation_id: Option<String>) -> Option<KiroLocalToken> {
+    kiro::get_kiro_local_token(installation_id.as_deref())
+}
+
+#[tauri::command]
+pub async fn get_kiro_telemetry_info(installation_id: Option<String>) -> Option<KiroTelemetryInfo> {
+    tokio::task::spawn_blocking(move || kiro::get_kiro_telemetry_info_inner(installation_id.as_deref()))
+        .await
+        .ok()
+        .flatten()
+}
+
+/// 切换 Kiro 账号（直接写入 Token 文件，仅重置机器ID时才关闭IDE）
+#[tauri::command]
+pub async fn switch_kiro_account(params: SwitchAccountParams) -> Result<SwitchAccountResult, String> {
+    // 使用 spawn_blocking 避免阻塞异步运行时
+    tokio::task::spawn_blocking(move || kiro::switch_kiro_account_inner(params))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn reset_kiro_machine_id(installation_id: Option<String>) -> Result<KiroTelemetryInfo, String> {
+    tokio::task::spawn_blocking(move || kiro::reset_kiro_machine_id_inner(installation_id.as_deref()))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use chrono::Local;
 use uuid::Uuid;
+use kiro_core::paths::app_data_file;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -27,21 +28,13 @@ pub struct MachineGuidBackup {
 }
 
 fn get_machine_guid_backup_path() -> PathBuf {
-    let data_dir = dirs::data_dir()
-        .unwrap_or_else(|| PathBuf::from("."));
-    data_dir
-        .join(".kiro-account-manager")
-        .join("machine-guid-backup.json")
+    app_data_file("machine-guid-backup.json")
 }
 
 // macOS 覆盖文件路径（硬件 UUID 无法修改，用覆盖文件变通）
 #[allow(dead_code)]
 fn get_macos_override_path() -> PathBuf {
-    let data_dir = dirs::data_dir()
-        .unwrap_or_else(|| PathBuf::from("."));
-    data_dir
-        .join(".kiro-account-manager")
-        .join("machine-id-override")
+    app_data_file("machine-id-override")
 }
 
 /// 获取操作系统类型
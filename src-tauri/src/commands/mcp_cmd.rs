@@ -1,40 +1,106 @@
-// MCP 服务器管理命令
+// MCP 服务器管理命令（workspace 为空时操作用户级配置，否则为已登记项目文件夹的配置）
 
-use crate::mcp::{McpConfig, McpServer};
+use kiro_core::mcp::{McpConfig, McpServer};
+use kiro_core::mcp_effective::{self, EffectiveMcpConfig};
+use kiro_core::mcp_health::{self, McpHealthReport};
+use kiro_core::mcp_interop::{self, McpClientFormat, McpExport, McpImportPreview};
+use kiro_core::mcp_tools::{self, McpServerTools};
+use kiro_core::mcp_validation::{validate_mcp_json, McpValidationReport};
+use serde_json::{Map, Value};
 
 /// 获取 MCP 配置
 #[tauri::command]
-pub fn get_mcp_config() -> Result<McpConfig, String> {
-    McpConfig::load()
+pub fn get_mcp_config(workspace: Option<String>) -> Result<McpConfig, String> {
+    McpConfig::load_from(&McpConfig::scope_path(workspace.as_deref())?)
 }
 
-/// 保存/更新服务器配置
+/// 保存/更新服务器配置（保留未知字段与键顺序）
 #[tauri::command]
-pub fn save_mcp_server(name: String, config: McpServer) -> Result<(), String> {
-    let mut mcp_config = McpConfig::load()?;
-    mcp_config.mcp_servers.insert(name, config);
-    mcp_config.save()
+pub fn save_mcp_server(name: String, config: McpServer, workspace: Option<String>) -> Result<(), String> {
+    McpConfig::upsert_server(&McpConfig::scope_path(workspace.as_deref())?, &name, &config)
 }
 
 /// 删除服务器
 #[tauri::command]
-pub fn delete_mcp_server(name: String) -> Result<(), String> {
-    let mut mcp_config = McpConfig::load()?;
-    mcp_config.mcp_servers.remove(&name);
-    mcp_config.save()
+pub fn delete_mcp_server(name: String, workspace: Option<String>) -> Result<(), String> {
+    McpConfig::remove_server(&McpConfig::scope_path(workspace.as_deref())?, &name)
 }
 
 /// 启用/禁用服务器
 #[tauri::command]
-pub fn toggle_mcp_server(name: String, disabled: bool) -> Result<(), String> {
-    let mut mcp_config = McpConfig::load()?;
-    if let Some(server) = mcp_config.mcp_servers.get_mut(&name) {
-        match server {
-            McpServer::Command(cmd) => cmd.disabled = disabled,
-            McpServer::Url(url) => url.disabled = disabled,
-        }
-        mcp_config.save()
-    } else {
-        Err(format!("服务器 {} 不存在", name))
+pub fn toggle_mcp_server(name: String, disabled: bool, workspace: Option<String>) -> Result<(), String> {
+    McpConfig::set_server_disabled(&McpConfig::scope_path(workspace.as_deref())?, &name, disabled)
+}
+
+/// 校验 MCP 配置：传入 content 时校验该文本（编辑器中尚未保存的内容），否则校验配置文件
+#[tauri::command]
+pub fn validate_mcp_config(content: Option<String>, workspace: Option<String>) -> Result<McpValidationReport, String> {
+    match content {
+        Some(content) => Ok(validate_mcp_json(&content)),
+        None => McpConfig::validate_file(&McpConfig::scope_path(workspace.as_deref())?),
     }
 }
+
+/// 测试服务器：真实执行 MCP 握手并列出工具
+#[tauri::command]
+pub async fn test_mcp_server(name: String, workspace: Option<String>) -> Result<McpHealthReport, String> {
+    mcp_health::test_mcp_server(&name, workspace.as_deref()).await
+}
+
+/// 合并后的生效配置，标注每个服务器的来源与覆盖关系
+#[tauri::command]
+pub fn get_effective_mcp_config(workspace: Option<String>) -> Result<EffectiveMcpConfig, String> {
+    mcp_effective::effective_config(workspace.as_deref())
+}
+
+/// 预览从其他客户端配置导入：content 为粘贴的文本，file 为选择的文件；format 为空时自动识别
+#[tauri::command]
+pub fn preview_mcp_import(
+    content: Option<String>,
+    file: Option<String>,
+    format: Option<McpClientFormat>,
+    workspace: Option<String>,
+) -> Result<McpImportPreview, String> {
+    let content = match (content, file) {
+        (Some(content), _) => content,
+        (None, Some(file)) => std::fs::read_to_string(&file)
+            .map_err(|e| format!("读取文件失败: {}", e))?,
+        (None, None) => return Err("没有可导入的内容".to_string()),
+    };
+    mcp_interop::preview_import(&content, format, &McpConfig::scope_path(workspace.as_deref())?)
+}
+
+/// 导入预览中选中的服务器（已转换为 Kiro 格式），返回导入数量
+#[tauri::command]
+pub fn import_mcp_servers(servers: Map<String, Value>, workspace: Option<String>) -> Result<usize, String> {
+    mcp_interop::import_servers(&McpConfig::scope_path(workspace.as_deref())?, servers)
+}
+
+/// 导出为其他客户端的配置格式
+#[tauri::command]
+pub fn export_mcp_servers(format: McpClientFormat, workspace: Option<String>) -> Result<McpExport, String> {
+    mcp_interop::export_servers(format, &McpConfig::scope_path(workspace.as_deref())?)
+}
+
+/// 缓存的工具列表与 autoApprove / disabledTools 的比对结果
+#[tauri::command]
+pub fn get_mcp_server_tools(name: String, workspace: Option<String>) -> Result<McpServerTools, String> {
+    mcp_tools::server_tools(&name, workspace.as_deref())
+}
+
+/// 通过 tools/list 重新获取工具列表
+#[tauri::command]
+pub async fn refresh_mcp_server_tools(name: String, workspace: Option<String>) -> Result<McpServerTools, String> {
+    mcp_tools::refresh_server_tools(&name, workspace.as_deref()).await
+}
+
+/// 保存逐个工具的自动批准与禁用设置
+#[tauri::command]
+pub fn save_mcp_tool_settings(
+    name: String,
+    auto_approve: Vec<String>,
+    disabled_tools: Vec<String>,
+    workspace: Option<String>,
+) -> Result<McpServerTools, String> {
+    mcp_tools::save_tool_settings(&name, auto_approve, disabled_tools, workspace.as_deref())
+}
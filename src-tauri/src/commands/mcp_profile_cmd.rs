@@ -0,0 +1,31 @@
+// MCP 配置方案命令
+
+use kiro_core::mcp_profiles::{self, McpProfile};
+
+#[tauri::command]
+pub fn list_mcp_profiles() -> Vec<McpProfile> {
+    mcp_profiles::list_profiles()
+}
+
+/// 新增或更新方案（id 为空时新增），返回保存后的方案
+#[tauri::command]
+pub fn save_mcp_profile(profile: McpProfile) -> Result<McpProfile, String> {
+    mcp_profiles::save_profile(profile)
+}
+
+/// 以当前用户级 mcp.json 新建方案
+#[tauri::command]
+pub fn capture_mcp_profile(name: String, description: Option<String>) -> Result<McpProfile, String> {
+    mcp_profiles::capture_profile(&name, description)
+}
+
+#[tauri::command]
+pub fn delete_mcp_profile(id: String) -> Result<(), String> {
+    mcp_profiles::delete_profile(&id)
+}
+
+/// 应用方案，返回备份文件路径
+#[tauri::command]
+pub fn apply_mcp_profile(id: String) -> Result<Option<String>, String> {
+    mcp_profiles::apply_profile(&id).map(|backup| backup.map(|p| p.to_string_lossy().to_string()))
+}
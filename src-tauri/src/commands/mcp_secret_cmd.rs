@@ -0,0 +1,19 @@
+// MCP 密钥命令（只返回名称与使用位置，不返回密钥值）
+
+use kiro_core::mcp_secrets::{self, McpSecretInfo};
+
+#[tauri::command]
+pub fn list_mcp_secrets() -> Result<Vec<McpSecretInfo>, String> {
+    mcp_secrets::list_secrets()
+}
+
+/// 新增或轮换密钥，返回因轮换而改写的 mcp.json
+#[tauri::command]
+pub fn set_mcp_secret(name: String, value: String) -> Result<Vec<String>, String> {
+    mcp_secrets::set_secret(&name, &value)
+}
+
+#[tauri::command]
+pub fn delete_mcp_secret(name: String) -> Result<(), String> {
+    mcp_secrets::delete_secret(&name)
+}
@@ -4,13 +4,23 @@ pub mod account_cmd;
 pub mod app_settings_cmd;
 pub mod auth_cmd;
 pub mod auto_register_cmd;
+pub mod browser_cmd;
+pub mod calendar_cmd;
+pub mod http_api_cmd;
+pub mod installation_cmd;
 
+pub mod kiro_cmd;
 pub mod kiro_settings_cmd;
 pub mod machine_guid_cmd;
 pub mod mcp_cmd;
+pub mod mcp_profile_cmd;
+pub mod mcp_secret_cmd;
+pub mod overage_cmd;
 pub mod powers_cmd;
+pub mod process_cmd;
 pub mod proxy_cmd;
 pub mod sso_import_cmd;
 pub mod steering_cmd;
 pub mod update_cmd;
 pub mod web_oauth_cmd;
+pub mod workspace_cmd;
@@ -0,0 +1,26 @@
+// 超额费用估算命令
+
+use tauri::State;
+use crate::state::AppState;
+use crate::overage::{estimate_accounts, OverageCostReport, DEFAULT_WARN_RATIO};
+
+/// 估算选中账号（默认全部）的当前与预计超额费用
+#[tauri::command]
+pub fn estimate_overage_costs(
+    state: State<AppState>,
+    ids: Option<Vec<String>>,
+    warn_ratio: Option<f64>,
+) -> OverageCostReport {
+    let store = state.store.lock().unwrap();
+    let accounts: Vec<_> = match ids {
+        Some(id_list) if !id_list.is_empty() => store.accounts.iter()
+            .filter(|a| id_list.contains(&a.id))
+            .cloned()
+            .collect(),
+        _ => store.get_all(),
+    };
+    drop(store);
+
+    let ratio = warn_ratio.filter(|r| *r > 0.0 && *r <= 1.0).unwrap_or(DEFAULT_WARN_RATIO);
+    estimate_accounts(&accounts, ratio)
+}
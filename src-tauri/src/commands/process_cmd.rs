@@ -0,0 +1,43 @@
+// Kiro IDE 进程管理命令（异步，避免阻塞主线程）
+// installation_id 为空时操作默认安装
+
+use kiro_core::installations::find_installation;
+use kiro_core::process::{check_kiro_running, launch_kiro, shutdown_kiro, ShutdownOutcome};
+
+/// 检查 Kiro IDE 是否正在运行
+#[tauri::command]
+pub async fn is_kiro_ide_running(installation_id: Option<String>) -> bool {
+    tokio::task::spawn_blocking(move || {
+        find_installation(installation_id.as_deref())
+            .map(|installation| check_kiro_running(installation.as_ref()))
+            .unwrap_or(false)
+    })
+    .await
+    .unwrap_or(false)
+}
+
+/// 关闭 Kiro IDE 进程（先请求正常退出；force 表示用户已确认强制关闭）
+/// 返回 false 表示超时仍未退出，需要用户确认后以 force 重新调用
+#[tauri::command]
+pub async fn close_kiro_ide(force: Option<bool>, installation_id: Option<String>) -> Result<bool, String> {
+    tokio::task::spawn_blocking(move || {
+        let installation = find_installation(installation_id.as_deref())?;
+        match shutdown_kiro(installation.as_ref(), force.unwrap_or(false))? {
+            ShutdownOutcome::Closed(_) => Ok(true),
+            ShutdownOutcome::AwaitingConfirmation(_) => Ok(false),
+        }
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// 启动 Kiro IDE
+#[tauri::command]
+pub async fn start_kiro_ide(installation_id: Option<String>) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let installation = find_installation(installation_id.as_deref())?;
+        launch_kiro(installation.as_ref()).map(|_| ())
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
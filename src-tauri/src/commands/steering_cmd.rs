@@ -1,6 +1,6 @@
 // Steering 管理命令
 
-use crate::steering::{SteeringFile, SteeringManager};
+use kiro_core::steering::{SteeringFile, SteeringManager};
 use tauri::command;
 
 #[command]
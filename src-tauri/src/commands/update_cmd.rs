@@ -2,6 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 use reqwest::Proxy;
+use kiro_core::paths::KiroPaths;
 
 const UPDATE_URL: &str = "https://github.com/hj01857655/kiro-account-manager/releases/latest/download/latest.json";
 
@@ -24,24 +25,8 @@ pub struct UpdateCheckResult {
 
 /// 获取 Kiro IDE 设置中的代理
 fn get_proxy_from_kiro_settings() -> Option<String> {
-    #[cfg(target_os = "windows")]
-    let path = std::env::var("APPDATA").ok().map(|appdata| {
-        std::path::PathBuf::from(appdata).join("Kiro").join("User").join("settings.json")
-    });
-    
-    #[cfg(target_os = "macos")]
-    let path = std::env::var("HOME").ok().map(|home| {
-        std::path::PathBuf::from(home)
-            .join("Library")
-            .join("Application Support")
-            .join("Kiro")
-            .join("User")
-            .join("settings.json")
-    });
-    
-    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
-    let path: Option<std::path::PathBuf> = None;
-    
+    let path = KiroPaths::default_installation().settings_path();
+
     path.and_then(|p| {
         if p.exists() {
             std::fs::read_to_string(&p).ok()
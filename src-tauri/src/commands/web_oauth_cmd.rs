@@ -1,11 +1,12 @@
-// Web OAuth 命令 - 直接存储 usage_data
+// Web OAuth 命令 - 登录窗口与待完成状态，token 交换与账号保存见 kiro_core::account_service
 
 use std::sync::{Mutex, OnceLock};
 use tauri::{AppHandle, Emitter, Manager, State, WebviewUrl, WebviewWindowBuilder};
 use crate::state::AppState;
-use crate::account::Account;
-use crate::auth::User;
-use crate::providers::web_oauth::{WebOAuthProvider, WebOAuthInitResult};
+use kiro_core::account::Account;
+use kiro_core::account_service;
+use kiro_core::auth::User;
+use kiro_core::providers::web_oauth::{WebOAuthProvider, WebOAuthInitResult};
 
 static PENDING_LOGIN: OnceLock<Mutex<Option<WebOAuthInitResult>>> = OnceLock::new();
 
@@ -60,92 +61,15 @@ pub async fn web_oauth_complete(
 ) -> Result<String, String> {
     println!("[WebOAuth] web_oauth_complete: callback_url={}", &callback_url[..80.min(callback_url.len())]);
     
-    let url = url::Url::parse(&callback_url)
-        .map_err(|e| format!("Invalid callback URL: {}", e))?;
-    
-    let code = url.query_pairs()
-        .find(|(k, _)| k == "code")
-        .map(|(_, v)| v.to_string())
-        .ok_or("No 'code' parameter in callback URL")?;
-    
-    let returned_state = url.query_pairs()
-        .find(|(k, _)| k == "state")
-        .map(|(_, v)| v.to_string())
-        .ok_or("No 'state' parameter in callback URL")?;
-    
     let init_result = {
         let mut pending_guard = get_pending_login().lock().unwrap();
         pending_guard.take()
     }.ok_or("No pending authentication state found")?;
     
-    let web_provider = WebOAuthProvider::new(&init_result.provider_id);
-    let auth_result = web_provider.complete_login(
-        &code,
-        &returned_state,
-        &init_result.code_verifier,
-        &init_result.state,
-    ).await?;
-
-    let csrf_token = auth_result.csrf_token.as_ref()
-        .ok_or("No csrf_token from ExchangeToken")?;
-    let refresh_token = &auth_result.refresh_token;
-
-    let portal_client = crate::providers::web_oauth::KiroWebPortalClient::new();
-    let user_info = portal_client.get_user_info(
-        &auth_result.access_token,
-        csrf_token,
-        refresh_token,
-        &init_result.idp,
-    ).await?;
-
+    let account = account_service::complete_web_oauth(&state.store, &init_result, &callback_url).await?;
     let provider = &init_result.provider_id;
-    let email = user_info.email.clone()
-        .ok_or("No email in GetUserInfo response")?;
-    let user_id = user_info.user_id.clone();
-
-    let usage = portal_client.get_user_usage_and_limits(
-        &auth_result.access_token,
-        csrf_token,
-        refresh_token,
-        &init_result.idp,
-    ).await?;
-    let usage_data = serde_json::to_value(&usage).unwrap_or(serde_json::Value::Null);
-
-    let mut store = state.store.lock().unwrap();
-    
-    let account = if let Some(existing) = store.accounts.iter_mut().find(|a| a.email == email) {
-        // 更新现有账号
-        existing.access_token = Some(auth_result.access_token.clone());
-        existing.refresh_token = Some(auth_result.refresh_token.clone());
-        existing.provider = Some(provider.clone());
-        existing.user_id = user_id;
-        existing.expires_at = Some(auth_result.expires_at.clone());
-        existing.profile_arn = auth_result.profile_arn.clone();
-        existing.csrf_token = auth_result.csrf_token.clone();
-        existing.usage_data = Some(usage_data);
-        existing.status = "正常".to_string();
-        existing.clone()
-    } else {
-        // 新建账号
-        let mut account = Account::new(email.clone(), format!("Kiro {} (Web OAuth)", provider));
-        account.access_token = Some(auth_result.access_token.clone());
-        account.refresh_token = Some(auth_result.refresh_token.clone());
-        account.provider = Some(provider.clone());
-        account.user_id = user_id;
-        account.expires_at = Some(auth_result.expires_at.clone());
-        account.profile_arn = auth_result.profile_arn.clone();
-        account.csrf_token = auth_result.csrf_token.clone();
-        account.usage_data = Some(usage_data);
-        store.accounts.insert(0, account.clone());
-        account
-    };
-    
-    store.save_to_file();
-    drop(store);
-
-    update_auth_state_web(&state, &email, provider, &auth_result.access_token, &auth_result.refresh_token);
-    println!("[WebOAuth] LOGIN SUCCESS: email={}, provider={}", account.email, provider);
 
+    update_auth_state_web(&state, &account, provider);
     let _ = app_handle.emit("login-success", account.id.clone());
     Ok(format!("Web OAuth login completed for {}", provider))
 }
@@ -155,80 +79,20 @@ pub async fn web_oauth_refresh(
     state: State<'_, AppState>,
     account_id: String,
 ) -> Result<Account, String> {
-    let account = {
-        let store = state.store.lock().unwrap();
-        store.accounts.iter()
-            .find(|a| a.id == account_id)
-            .cloned()
-            .ok_or("Account not found")?
-    };
-
-    // Web OAuth 账号必须有 csrfToken
-    if account.csrf_token.is_none() {
-        return Err("This account is not a Web OAuth account (no csrfToken)".to_string());
-    }
-
-    let access_token = account.access_token.as_ref().ok_or("No access_token found")?;
-    let csrf_token = account.csrf_token.as_ref().ok_or("No csrf_token found")?;
-    let provider = account.provider.as_ref().ok_or("No provider found")?;
-    
-    let refresh_token = account.refresh_token.as_ref().ok_or("No refresh_token found")?;
-    let web_provider = WebOAuthProvider::new(provider);
-    let auth_result = web_provider.refresh_token_impl(access_token, csrf_token, refresh_token).await?;
-
-    let new_csrf = auth_result.csrf_token.clone();
-    
-    let portal_client = crate::providers::web_oauth::KiroWebPortalClient::new();
-    let idp = match provider.as_str() {
-        "Github" => "Github",
-        other => other,
-    };
-    let usage = portal_client.get_user_usage_and_limits(
-        &auth_result.access_token,
-        new_csrf.as_deref().unwrap_or(""),
-        refresh_token,
-        idp,
-    ).await.ok();
-    let usage_data = serde_json::to_value(&usage).unwrap_or(serde_json::Value::Null);
-
-    let mut store = state.store.lock().unwrap();
-    if let Some(a) = store.accounts.iter_mut().find(|a| a.id == account_id) {
-        a.access_token = Some(auth_result.access_token);
-        a.refresh_token = Some(auth_result.refresh_token);
-        a.csrf_token = auth_result.csrf_token;
-        a.expires_at = Some(auth_result.expires_at);
-        a.usage_data = Some(usage_data);
-        a.status = "正常".to_string();
-        if auth_result.profile_arn.is_some() {
-            a.profile_arn = auth_result.profile_arn;
-        }
-        
-        let result = a.clone();
-        store.save_to_file();
-        println!("[WebOAuth] Account refreshed: {}", result.email);
-        return Ok(result);
-    }
-
-    Err("Account not found after refresh".to_string())
+    account_service::refresh_web_oauth_account(&state.store, &account_id).await
 }
 
-fn update_auth_state_web(
-    state: &State<'_, AppState>,
-    email: &str,
-    provider: &str,
-    access_token: &str,
-    refresh_token: &str,
-) {
+fn update_auth_state_web(state: &State<'_, AppState>, account: &Account, provider: &str) {
     let user = User {
         id: uuid::Uuid::new_v4().to_string(),
-        email: email.to_string(),
-        name: email.split('@').next().unwrap_or("User").to_string(),
+        email: account.email.clone(),
+        name: account.email.split('@').next().unwrap_or("User").to_string(),
         avatar: None,
         provider: provider.to_string(),
     };
     *state.auth.user.lock().unwrap() = Some(user);
-    *state.auth.access_token.lock().unwrap() = Some(access_token.to_string());
-    *state.auth.refresh_token.lock().unwrap() = Some(refresh_token.to_string());
+    *state.auth.access_token.lock().unwrap() = account.access_token.clone();
+    *state.auth.refresh_token.lock().unwrap() = account.refresh_token.clone();
 }
 
 #[tauri::command]
@@ -0,0 +1,19 @@
+// 项目文件夹登记命令
+
+use kiro_core::workspaces;
+
+#[tauri::command]
+pub fn list_workspaces() -> Vec<String> {
+    workspaces::list_workspaces()
+}
+
+/// 登记项目文件夹，返回规范化后的路径
+#[tauri::command]
+pub fn add_workspace(path: String) -> Result<String, String> {
+    workspaces::add_workspace(&path)
+}
+
+#[tauri::command]
+pub fn remove_workspace(path: String) -> Result<(), String> {
+    workspaces::remove_workspace(&path)
+}
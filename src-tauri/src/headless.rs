@@ -0,0 +1,253 @@
+// 无界面模式 - 不启动 WebView，只运行本地 HTTP API 与定时刷新 token（用于没有显示器的服务器）
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use chrono::{Local, NaiveDateTime, TimeZone};
+use kiro_core::account::{Account, AccountStore};
+use kiro_core::account_service::refresh_account_token;
+use kiro_core::settings::get_app_settings_inner;
+use crate::http_server::{start_http_server, stop_http_server};
+
+/// 与界面端一致的默认刷新间隔（分钟）
+const DEFAULT_REFRESH_INTERVAL_MINUTES: u64 = 50;
+
+/// 在过期前多久开始刷新（分钟），与界面端的提前量一致
+const REFRESH_MARGIN_MINUTES: i64 = 5;
+
+/// 两个账号刷新之间的间隔，避免请求过于集中
+const REFRESH_DELAY: Duration = Duration::from_millis(500);
+
+pub const USAGE: &str = "\
+用法: kiro-account-manager --headless [选项]
+
+选项:
+  --log-file <路径>            日志写入文件而不是标准输出
+  --refresh-interval <分钟>    token 刷新检查间隔（默认读取设置，否则 50 分钟）
+  --no-refresh                 不自动刷新 token，只运行 HTTP API";
+
+#[derive(Debug, Clone)]
+pub struct HeadlessOptions {
+    pub log_file: Option<PathBuf>,
+    /// None 表示不自动刷新
+    pub refresh_interval: Option<Duration>,
+}
+
+/// 解析命令行参数；没有 `--headless` 时返回 None，按正常界面模式启动
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Option<HeadlessOptions>, String> {
+    let args: Vec<String> = args.into_iter().collect();
+    if !args.iter().any(|a| a == "--headless") {
+        return Ok(None);
+    }
+
+    let mut log_file = None;
+    let mut interval_minutes = None;
+    let mut refresh = true;
+
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--headless" => {}
+            "--log-file" => {
+                let path = iter.next().ok_or("--log-file 缺少路径")?;
+                log_file = Some(PathBuf::from(path));
+            }
+            "--refresh-interval" => {
+                let value = iter.next().ok_or("--refresh-interval 缺少分钟数")?;
+                let minutes = value.parse::<u64>()
+                    .ok()
+                    .filter(|m| *m > 0)
+                    .ok_or_else(|| format!("无效的刷新间隔: {}", value))?;
+                interval_minutes = Some(minutes);
+            }
+            "--no-refresh" => refresh = false,
+            other => return Err(format!("未知参数: {}", other)),
+        }
+    }
+
+    let refresh_interval = refresh.then(|| {
+        let minutes = interval_minutes.unwrap_or_else(|| {
+            get_app_settings_inner().ok()
+                .and_then(|s| s.auto_refresh_interval)
+                .filter(|m| *m > 0)
+                .map(|m| m as u64)
+                .unwrap_or(DEFAULT_REFRESH_INTERVAL_MINUTES)
+        });
+        Duration::from_secs(minutes * 60)
+    });
+
+    Ok(Some(HeadlessOptions { log_file, refresh_interval }))
+}
+
+/// 将标准输出与标准错误重定向到日志文件（现有的 println!/eprintln! 日志随之写入文件）
+#[cfg(unix)]
+fn redirect_output(path: &Path) -> Result<(), String> {
+    use std::os::unix::io::AsRawFd;
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("创建日志目录失败: {}", e))?;
+    }
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("打开日志文件失败: {}", e))?;
+
+    let fd = file.as_raw_fd();
+    // SAFETY: fd 在 file 的生命周期内有效，dup2 只复制描述符
+    let ok = unsafe { libc::dup2(fd, libc::STDOUT_FILENO) != -1 && libc::dup2(fd, libc::STDERR_FILENO) != -1 };
+    if !ok {
+        return Err(format!("重定向日志失败: {}", std::io::Error::last_os_error()));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn redirect_output(_path: &Path) -> Result<(), String> {
+    Err("当前平台不支持 --log-file，请重定向标准输出".to_string())
+}
+
+/// 在下一次检查前会过期（或即将过期）的账号
+fn needs_refresh(account: &Account, lookahead: Duration) -> bool {
+    // 跳过已封禁账号
+    if account.status == "已封禁" || account.status == "封禁" {
+        return false;
+    }
+    if account.refresh_token.is_none() {
+        return false;
+    }
+    let expires_at = match account.expires_at.as_deref()
+        .and_then(|s| NaiveDateTime::parse_from_str(s, "%Y/%m/%d %H:%M:%S").ok())
+        .and_then(|t| Local.from_local_datetime(&t).earliest())
+    {
+        Some(t) => t,
+        None => return false,
+    };
+    let lookahead = chrono::Duration::from_std(lookahead).unwrap_or_default()
+        + chrono::Duration::minutes(REFRESH_MARGIN_MINUTES);
+    expires_at - Local::now() < lookahead
+}
+
+/// 刷新即将过期的 token
+async fn refresh_expiring_tokens(store: &Mutex<AccountStore>, lookahead: Duration) {
+    let accounts: Vec<Account> = {
+        let mut store = store.lock().unwrap();
+        store.reload_from_file();
+        store.get_all()
+    };
+    let expiring: Vec<Account> = accounts.into_iter()
+        .filter(|a| needs_refresh(a, lookahead))
+        .collect();
+
+    if expiring.is_empty() {
+        println!("[Headless] No tokens need refreshing");
+        return;
+    }
+
+    println!("[Headless] Refreshing {} token(s)", expiring.len());
+    let mut failed = 0;
+    for account in &expiring {
+        match refresh_account_token(store, &account.id).await {
+            Ok(_) => println!("[Headless] {} token refreshed", account.email),
+            Err(e) => {
+                failed += 1;
+                eprintln!("[Headless] {} token refresh failed: {}", account.email, e);
+            }
+        }
+        tokio::time::sleep(REFRESH_DELAY).await;
+    }
+    println!("[Headless] Refresh finished ({} ok, {} failed)", expiring.len() - failed, failed);
+}
+
+/// 等待 SIGTERM / SIGINT（Windows 上为 Ctrl+C）
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = sigterm.recv() => println!("[Headless] Received SIGTERM"),
+                    _ = tokio::signal::ctrl_c() => println!("[Headless] Received SIGINT"),
+                }
+            }
+            Err(e) => {
+                eprintln!("[Headless] Failed to install SIGTERM handler: {}", e);
+                let _ = tokio::signal::ctrl_c().await;
+                println!("[Headless] Received SIGINT");
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        println!("[Headless] Received Ctrl+C");
+    }
+}
+
+/// 运行无界面模式，返回进程退出码
+pub fn run(options: HeadlessOptions) -> i32 {
+    if let Some(path) = &options.log_file {
+        if let Err(e) = redirect_output(path) {
+            eprintln!("[Headless] {}", e);
+            return 1;
+        }
+    }
+
+    println!(
+        "[Headless] Kiro Account Manager {} starting (pid {})",
+        env!("CARGO_PKG_VERSION"),
+        std::process::id()
+    );
+
+    let rt = match tokio::runtime::Runtime::new() {
+        Ok(rt) => rt,
+        Err(e) => {
+            eprintln!("[Headless] Failed to create tokio runtime: {}", e);
+            return 1;
+        }
+    };
+
+    let store = Arc::new(Mutex::new(AccountStore::new()));
+    println!("[Headless] Loaded {} account(s)", store.lock().unwrap().accounts.len());
+
+    start_http_server(store.clone());
+
+    rt.block_on(async {
+        let interval = match options.refresh_interval {
+            Some(interval) => interval,
+            None => {
+                println!("[Headless] Token refresh disabled");
+                shutdown_signal().await;
+                return;
+            }
+        };
+        println!("[Headless] Token refresh every {} minute(s)", interval.as_secs() / 60);
+
+        // 第一次 tick 立即触发，启动时先刷新一次
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let shutdown = shutdown_signal();
+        tokio::pin!(shutdown);
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => break,
+                _ = ticker.tick() => {
+                    // 刷新过程中收到信号也立即退出；账号写入是同步完成的，不会写到一半
+                    tokio::select! {
+                        _ = &mut shutdown => break,
+                        _ = refresh_expiring_tokens(&store, interval) => {}
+                    }
+                }
+            }
+        }
+    });
+
+    // 在 runtime 之外停止，等待正在处理的 HTTP 请求完成
+    stop_http_server();
+    drop(rt);
+    println!("[Headless] Shutdown complete");
+    0
+}
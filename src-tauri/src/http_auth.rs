@@ -0,0 +1,124 @@
+// 本地 HTTP API 鉴权 - 每个安装独立的 Bearer Token 与 Origin 白名单
+
+use std::path::PathBuf;
+use kiro_core::paths::app_data_file;
+use kiro_core::settings::AppSettings;
+
+const TOKEN_FILE_NAME: &str = "http-api-token";
+
+/// Tauri WebView 自身的 Origin，始终允许
+const BUILTIN_ORIGINS: &[&str] = &[
+    "tauri://localhost",
+    "http://tauri.localhost",
+    "https://tauri.localhost",
+];
+
+fn get_token_path() -> PathBuf {
+    app_data_file(TOKEN_FILE_NAME)
+}
+
+fn generate_token() -> String {
+    let bytes: [u8; 32] = rand::random();
+    hex::encode(bytes)
+}
+
+/// 写入令牌文件（Unix 下权限为 0600）
+fn write_token_file(path: &PathBuf, token: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::io::Write;
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)
+            .map_err(|e| format!("写入令牌文件失败: {}", e))?;
+        // 文件已存在时 mode 不生效，显式收紧权限
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("设置令牌文件权限失败: {}", e))?;
+        file.write_all(token.as_bytes())
+            .map_err(|e| format!("写入令牌文件失败: {}", e))?;
+    }
+
+    #[cfg(not(unix))]
+    {
+        std::fs::write(path, token)
+            .map_err(|e| format!("写入令牌文件失败: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// 读取 API 令牌，不存在时生成一个新的
+pub fn load_or_create_api_token() -> Result<String, String> {
+    let path = get_token_path();
+    if let Ok(content) = std::fs::read_to_string(&path) {
+        let token = content.trim().to_string();
+        if !token.is_empty() {
+            return Ok(token);
+        }
+    }
+    let token = generate_token();
+    write_token_file(&path, &token)?;
+    Ok(token)
+}
+
+/// 只读取已有的 API 令牌（不生成，供客户端使用）
+pub fn read_api_token() -> Option<String> {
+    std::fs::read_to_string(get_token_path()).ok()
+        .map(|content| content.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
+
+/// 重新生成 API 令牌（旧令牌立即失效）
+pub fn regenerate_api_token() -> Result<String, String> {
+    let token = generate_token();
+    write_token_file(&get_token_path(), &token)?;
+    Ok(token)
+}
+
+/// 常量时间比较，避免通过响应时间猜测令牌
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// 校验 Authorization: Bearer <token>
+pub fn verify_bearer(authorization: Option<&str>, expected: &str) -> bool {
+    authorization
+        .and_then(|v| v.strip_prefix("Bearer ").or_else(|| v.strip_prefix("bearer ")))
+        .map(|t| constant_time_eq(t.trim().as_bytes(), expected.as_bytes()))
+        .unwrap_or(false)
+}
+
+/// 校验查询参数中的令牌（仅用于无法设置请求头的日历订阅）
+pub fn verify_query_token(token: Option<&str>, expected: &str) -> bool {
+    token
+        .map(|t| constant_time_eq(t.as_bytes(), expected.as_bytes()))
+        .unwrap_or(false)
+}
+
+/// 检查 Origin 是否在白名单中
+///
+/// 没有 Origin 头的请求（脚本、curl）不受限制，仍需令牌。
+pub fn is_origin_allowed(origin: Option<&str>, settings: &AppSettings) -> bool {
+    let origin = match origin {
+        Some(o) => o.trim_end_matches('/'),
+        None => return true,
+    };
+    if BUILTIN_ORIGINS.contains(&origin) {
+        return true;
+    }
+    settings.http_allowed_origins.as_ref()
+        .map(|list| list.iter().any(|o| o.trim_end_matches('/') == origin))
+        .unwrap_or(false)
+}
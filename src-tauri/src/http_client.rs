@@ -0,0 +1,165 @@
+// 本地 HTTP API 的类型化客户端 - 与服务端共用 http_types 中的类型，供自动化工具使用
+#![allow(dead_code)]
+
+use reqwest::{Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use kiro_core::account::Account;
+use crate::http_auth::read_api_token;
+use crate::http_discovery::read_discovery_file;
+use crate::http_server::ENDPOINTS;
+use crate::http_types::{
+    DeviceAuthUrlResponse, ErrorResponse, PollDeviceAuthResponse, ReloadAccountsResponse, ResetMachineIdResponse,
+    StartDeviceAuthResponse, StatusResponse, SwitchAccountRequest, SyncAccountsRequest, SyncAccountsResponse,
+};
+use kiro_core::kiro::SwitchAccountResult;
+use kiro_core::usage::NormalizedUsage;
+
+const DEFAULT_BASE_URL: &str = "http://127.0.0.1:23847";
+
+pub struct LocalApiClient {
+    base_url: String,
+    token: String,
+    http: reqwest::Client,
+}
+
+impl LocalApiClient {
+    pub fn new(base_url: impl Into<String>, token: impl Into<String>) -> Result<Self, String> {
+        // 本机接口不走系统代理
+        let http = reqwest::Client::builder()
+            .no_proxy()
+            .build()
+            .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?;
+        Ok(Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            token: token.into(),
+            http,
+        })
+    }
+
+    /// 通过发现文件与令牌文件连接本机正在运行的实例
+    pub fn discover() -> Result<Self, String> {
+        let base_url = read_discovery_file()
+            .map(|info| info.base_url)
+            .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+        let token = read_api_token().ok_or("未找到 API 令牌，请先启动 Kiro Account Manager")?;
+        Self::new(base_url, token)
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    async fn request<B: Serialize, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<T, String> {
+        let mut builder = self.http
+            .request(method, format!("{}{}", self.base_url, path))
+            .bearer_auth(&self.token);
+        if let Some(body) = body {
+            builder = builder.json(body);
+        }
+
+        let response = builder.send().await
+            .map_err(|e| format!("请求 {} 失败: {}", path, e))?;
+        let status = response.status();
+        let bytes = response.bytes().await
+            .map_err(|e| format!("读取响应失败: {}", e))?;
+
+        if status != StatusCode::OK {
+            let message = serde_json::from_slice::<ErrorResponse>(&bytes)
+                .map(|e| e.error)
+                .unwrap_or_else(|_| String::from_utf8_lossy(&bytes).to_string());
+            return Err(format!("{} ({})", message, status.as_u16()));
+        }
+
+        serde_json::from_slice(&bytes)
+            .map_err(|e| format!("解析 {} 响应失败: {}", path, e))
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, String> {
+        self.request::<(), T>(Method::GET, path, None).await
+    }
+
+    async fn post<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: Option<&B>) -> Result<T, String> {
+        self.request(Method::POST, path, body).await
+    }
+
+    fn account_path(id: &str, suffix: &str) -> String {
+        format!("/accounts/{}{}", urlencoding::encode(id), suffix)
+    }
+
+    pub async fn status(&self) -> Result<StatusResponse, String> {
+        self.get("/status").await
+    }
+
+    pub async fn openapi(&self) -> Result<serde_json::Value, String> {
+        self.get("/openapi.json").await
+    }
+
+    /// 对照服务端的 OpenAPI 描述，检查本客户端用到的接口是否都存在
+    pub async fn check_compatibility(&self) -> Result<(), String> {
+        let spec = self.openapi().await?;
+        let missing: Vec<String> = ENDPOINTS.iter()
+            .filter(|e| {
+                spec.get("paths")
+                    .and_then(|p| p.get(e.path))
+                    .and_then(|item| item.get(e.method.to_string().to_ascii_lowercase()))
+                    .is_none()
+            })
+            .map(|e| format!("{} {}", e.method, e.path))
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("服务端缺少接口: {}", missing.join(", ")))
+        }
+    }
+
+    pub async fn accounts(&self, include_secrets: bool) -> Result<Vec<Account>, String> {
+        self.get(&format!("/accounts?include_secrets={}", include_secrets)).await
+    }
+
+    pub async fn account(&self, id: &str, include_secrets: bool) -> Result<Account, String> {
+        self.get(&format!("{}?include_secrets={}", Self::account_path(id, ""), include_secrets)).await
+    }
+
+    pub async fn account_usage(&self, id: &str) -> Result<NormalizedUsage, String> {
+        self.get(&Self::account_path(id, "/usage")).await
+    }
+
+    pub async fn sync_account(&self, id: &str) -> Result<Account, String> {
+        self.post::<(), _>(&Self::account_path(id, "/sync"), None).await
+    }
+
+    pub async fn sync_accounts(&self, request: &SyncAccountsRequest) -> Result<SyncAccountsResponse, String> {
+        self.post("/accounts/sync", Some(request)).await
+    }
+
+    pub async fn switch_account(&self, id: &str, request: &SwitchAccountRequest) -> Result<SwitchAccountResult, String> {
+        self.post(&Self::account_path(id, "/switch"), Some(request)).await
+    }
+
+    pub async fn reload_accounts(&self) -> Result<ReloadAccountsResponse, String> {
+        self.post::<(), _>("/reload_accounts", None).await
+    }
+
+    pub async fn reset_machine_id(&self) -> Result<ResetMachineIdResponse, String> {
+        self.post::<(), _>("/reset_machine_id", None).await
+    }
+
+    pub async fn device_auth_url(&self) -> Result<DeviceAuthUrlResponse, String> {
+        self.get("/get_device_auth_url").await
+    }
+
+    pub async fn start_device_auth(&self) -> Result<StartDeviceAuthResponse, String> {
+        self.post::<(), _>("/start_device_auth", None).await
+    }
+
+    pub async fn poll_device_auth(&self) -> Result<PollDeviceAuthResponse, String> {
+        self.post::<(), _>("/poll_device_auth", None).await
+    }
+}
@@ -0,0 +1,68 @@
+// 本地 HTTP API 发现文件 - 记录实际监听的地址，供外部脚本查找
+
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use kiro_core::paths::{app_data_dir, app_data_file};
+
+const DISCOVERY_FILE_NAME: &str = "http-api.json";
+
+#[cfg(unix)]
+const SOCKET_FILE_NAME: &str = "kiro-account-manager.sock";
+
+/// 发现文件内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveryInfo {
+    pub base_url: String,
+    pub address: String,
+    pub port: u16,
+    pub unix_socket: Option<String>,
+    pub pid: u32,
+    pub version: String,
+}
+
+pub fn discovery_file_path() -> PathBuf {
+    app_data_file(DISCOVERY_FILE_NAME)
+}
+
+/// 默认的 Unix 套接字路径（优先 $XDG_RUNTIME_DIR）
+#[cfg(unix)]
+pub fn default_unix_socket_path() -> PathBuf {
+    dirs::runtime_dir()
+        .unwrap_or_else(app_data_dir)
+        .join(SOCKET_FILE_NAME)
+}
+
+/// 客户端连接用的地址（监听所有网卡时使用回环地址）
+pub fn connect_host(bind_address: &str) -> String {
+    match bind_address {
+        "0.0.0.0" => "127.0.0.1".to_string(),
+        "::" => "[::1]".to_string(),
+        addr if addr.contains(':') && !addr.starts_with('[') => format!("[{}]", addr),
+        addr => addr.to_string(),
+    }
+}
+
+pub fn write_discovery_file(info: &DiscoveryInfo) -> Result<(), String> {
+    let path = discovery_file_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(info)
+        .map_err(|e| format!("序列化失败: {}", e))?;
+    std::fs::write(&path, content)
+        .map_err(|e| format!("写入发现文件失败: {}", e))
+}
+
+pub fn read_discovery_file() -> Option<DiscoveryInfo> {
+    let content = std::fs::read_to_string(discovery_file_path()).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// 删除发现文件（仅当文件属于当前进程时）
+pub fn remove_discovery_file() {
+    if read_discovery_file().map(|info| info.pid == std::process::id()).unwrap_or(false) {
+        let _ = std::fs::remove_file(discovery_file_path());
+    }
+}
@@ -0,0 +1,120 @@
+// 本地 HTTP API 的 OpenAPI 描述 - 由路由表与类型定义生成
+
+use schemars::gen::SchemaSettings;
+use serde_json::{json, Map, Value};
+use kiro_core::events::EventRecord;
+use crate::http_server::{get_http_server_status, ResponseBody, ENDPOINTS};
+use crate::http_types::ErrorResponse;
+
+pub const OPENAPI_VERSION: &str = "3.0.3";
+
+/// 由方法与路径生成 operationId，如 POST /accounts/{id}/sync -> post_accounts_by_id_sync
+fn operation_id(method: &str, path: &str) -> String {
+    let mut id = method.to_ascii_lowercase();
+    for segment in path.split('/').filter(|s| !s.is_empty()) {
+        id.push('_');
+        match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            Some(param) => {
+                id.push_str("by_");
+                id.push_str(param);
+            }
+            None => id.extend(segment.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })),
+        }
+    }
+    id
+}
+
+/// 生成 OpenAPI 文档
+pub fn openapi_spec() -> Value {
+    let mut gen = SchemaSettings::openapi3().into_generator();
+    let error_schema = json!(gen.subschema_for::<ErrorResponse>());
+    // 事件流中 data 字段的结构
+    gen.subschema_for::<EventRecord>();
+
+    let mut paths = Map::new();
+    for endpoint in ENDPOINTS {
+        let method = endpoint.method.to_string();
+
+        let mut parameters: Vec<Value> = endpoint.path.split('/')
+            .filter_map(|s| s.strip_prefix('{').and_then(|s| s.strip_suffix('}')))
+            .map(|name| json!({
+                "name": name,
+                "in": "path",
+                "required": true,
+                "schema": { "type": "string" },
+            }))
+            .collect();
+        parameters.extend(endpoint.query.iter().map(|q| json!({
+            "name": q.name,
+            "in": "query",
+            "required": false,
+            "description": q.description,
+            "schema": { "type": "string" },
+        })));
+
+        let (content_type, schema) = match &endpoint.response {
+            ResponseBody::Json(schema_fn) => ("application/json", json!(schema_fn(&mut gen))),
+            ResponseBody::Text(content_type) => (*content_type, json!({ "type": "string" })),
+        };
+
+        let mut responses = Map::new();
+        responses.insert("200".to_string(), json!({
+            "description": "OK",
+            "content": { content_type: { "schema": schema } },
+        }));
+        if !endpoint.public {
+            responses.insert("401".to_string(), json!({
+                "description": "缺少或错误的 API 令牌",
+                "content": { "application/json": { "schema": error_schema } },
+            }));
+        }
+        responses.insert("default".to_string(), json!({
+            "description": "错误",
+            "content": { "application/json": { "schema": error_schema } },
+        }));
+
+        let mut operation = Map::new();
+        operation.insert("operationId".to_string(), json!(operation_id(&method, endpoint.path)));
+        operation.insert("summary".to_string(), json!(endpoint.summary));
+        if !parameters.is_empty() {
+            operation.insert("parameters".to_string(), json!(parameters));
+        }
+        if let Some(schema_fn) = endpoint.request {
+            operation.insert("requestBody".to_string(), json!({
+                "required": false,
+                "content": { "application/json": { "schema": schema_fn(&mut gen) } },
+            }));
+        }
+        operation.insert("responses".to_string(), Value::Object(responses));
+        if endpoint.public {
+            operation.insert("security".to_string(), json!([]));
+        }
+
+        let item = paths.entry(endpoint.path.to_string()).or_insert_with(|| json!({}));
+        if let Value::Object(item) = item {
+            item.insert(method.to_ascii_lowercase(), Value::Object(operation));
+        }
+    }
+
+    let servers: Vec<Value> = get_http_server_status().base_url
+        .map(|url| json!({ "url": url }))
+        .into_iter()
+        .collect();
+
+    json!({
+        "openapi": OPENAPI_VERSION,
+        "info": {
+            "title": "Kiro Account Manager Local API",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "servers": servers,
+        "paths": paths,
+        "components": {
+            "schemas": gen.take_definitions(),
+            "securitySchemes": {
+                "bearerAuth": { "type": "http", "scheme": "bearer" },
+            },
+        },
+        "security": [{ "bearerAuth": [] }],
+    })
+}
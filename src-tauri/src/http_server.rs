@@ -1,330 +1,1091 @@
-// HTTP 服务器 - 暴露设备授权 URL 给外部脚本
+// HTTP 服务器 - 供外部脚本调用的本地 API
 
+use std::collections::HashMap;
 use std::io::{Read, Write};
-use std::net::TcpListener;
-use std::thread;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use serde::de::DeserializeOwned;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::Serialize;
+use tiny_http::{Header, Method, Request, Response, Server};
 use crate::state::{CURRENT_DEVICE_AUTH_URL, PENDING_DEVICE_AUTH, PendingDeviceAuth};
-use crate::aws_sso_client::{AWSSSOClient, DevicePollResult};
-use crate::codewhisperer_client::CodeWhispererClient;
-use crate::account::{Account, AccountStore};
-use crate::kiro::{get_machine_id, reset_kiro_machine_id_inner};
-use sha2::{Digest, Sha256};
-
-const HTTP_PORT: u16 = 23847;
-
-/// 启动 HTTP 服务器（在后台线程运行）
-pub fn start_http_server() {
-    thread::spawn(|| {
-        let addr = format!("127.0.0.1:{}", HTTP_PORT);
-        let listener = match TcpListener::bind(&addr) {
-            Ok(l) => l,
-            Err(e) => {
-                eprintln!("[HTTP Server] Failed to bind to {}: {}", addr, e);
-                return;
+use kiro_core::account::{diff_accounts, Account, AccountStore};
+use crate::http_types::{
+    DeviceAuthUrlResponse, ErrorResponse, PollDeviceAuthResponse, ReloadAccountsResponse, ResetMachineIdResponse,
+    StartDeviceAuthResponse, StatusResponse, SwitchAccountRequest, SyncAccountResult, SyncAccountsRequest,
+    SyncAccountsResponse,
+};
+use kiro_core::kiro::{reset_kiro_machine_id_inner, switch_kiro_account_inner, SwitchAccountParams, SwitchAccountResult};
+use crate::calendar::{generate_ics, select_accounts};
+use kiro_core::usage::{normalize_usage, NormalizedUsage};
+use crate::http_openapi::openapi_spec;
+use crate::webview::emit_to_webview;
+use kiro_core::events::{self, DeviceAuthState, DomainEvent, EventRecord};
+use kiro_core::account_service::{self, sync_account, DeviceAuthInfo, DeviceAuthPoll};
+use kiro_core::settings::get_app_settings_inner;
+use crate::http_discovery::{connect_host, discovery_file_path, remove_discovery_file, write_discovery_file, DiscoveryInfo};
+#[cfg(unix)]
+use crate::http_discovery::default_unix_socket_path;
+use crate::http_auth::{is_origin_allowed, load_or_create_api_token, verify_bearer, verify_query_token};
+
+const DEFAULT_HTTP_PORT: u16 = 23847;
+
+const DEFAULT_BIND_ADDRESS: &str = "127.0.0.1";
+
+/// 端口被占用时依次尝试的后续端口数量，仍失败则由系统分配
+const PORT_FALLBACK_ATTEMPTS: u16 = 10;
+
+/// 工作线程数量（设备授权等耗时请求不会阻塞其他客户端）
+const WORKER_COUNT: usize = 4;
+
+/// Unix 套接字的工作线程数量
+#[cfg(unix)]
+const UNIX_WORKER_COUNT: usize = 2;
+
+/// 请求体大小上限
+const MAX_BODY_SIZE: u64 = 1024 * 1024;
+
+/// 同时打开的事件流上限（事件流各占一个线程，不占用工作线程）
+const MAX_EVENT_STREAMS: usize = 16;
+
+/// 事件流无事件时的心跳间隔
+const EVENT_STREAM_KEEPALIVE: Duration = Duration::from_secs(15);
+
+/// 生成 JSON Schema 的函数（用于 OpenAPI 描述）
+pub(crate) type SchemaFn = fn(&mut SchemaGenerator) -> Schema;
+
+fn schema_of<T: JsonSchema>(gen: &mut SchemaGenerator) -> Schema {
+    gen.subschema_for::<T>()
+}
+
+/// 响应体类型
+pub(crate) enum ResponseBody {
+    Json(SchemaFn),
+    /// 非 JSON 响应的 Content-Type
+    Text(&'static str),
+}
+
+/// 查询参数说明
+pub(crate) struct QueryParam {
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+/// 路由表项：路径（`{id}` 为路径参数）、允许的方法、是否无需令牌，以及生成 OpenAPI 所需的类型信息
+pub(crate) struct Endpoint {
+    pub path: &'static str,
+    pub method: Method,
+    pub public: bool,
+    pub summary: &'static str,
+    pub query: &'static [QueryParam],
+    pub request: Option<SchemaFn>,
+    pub response: ResponseBody,
+}
+
+impl Endpoint {
+    const fn new(method: Method, path: &'static str, summary: &'static str) -> Self {
+        Self {
+            path,
+            method,
+            public: false,
+            summary,
+            query: &[],
+            request: None,
+            response: ResponseBody::Text("text/plain"),
+        }
+    }
+
+    const fn get(path: &'static str, summary: &'static str) -> Self {
+        Self::new(Method::Get, path, summary)
+    }
+
+    const fn post(path: &'static str, summary: &'static str) -> Self {
+        Self::new(Method::Post, path, summary)
+    }
+
+    const fn public(mut self) -> Self {
+        self.public = true;
+        self
+    }
+
+    const fn query(mut self, query: &'static [QueryParam]) -> Self {
+        self.query = query;
+        self
+    }
+
+    const fn request<T: JsonSchema>(mut self) -> Self {
+        self.request = Some(schema_of::<T>);
+        self
+    }
+
+    const fn json<T: JsonSchema>(mut self) -> Self {
+        self.response = ResponseBody::Json(schema_of::<T>);
+        self
+    }
+
+    const fn text(mut self, content_type: &'static str) -> Self {
+        self.response = ResponseBody::Text(content_type);
+        self
+    }
+}
+
+const INCLUDE_SECRETS_PARAM: QueryParam = QueryParam {
+    name: "include_secrets",
+    description: "为 true 时返回 token / secret 原文，默认以 *** 代替",
+};
+
+const TOKEN_PARAM: QueryParam = QueryParam {
+    name: "token",
+    description: "API 令牌（仅用于无法设置 Authorization 头的客户端）",
+};
+
+/// 会修改状态的接口只接受 POST，防止网页通过 <img>/<a> 等发起 GET 触发
+pub(crate) const ENDPOINTS: &[Endpoint] = &[
+    Endpoint::get("/status", "服务状态").public().json::<StatusResponse>(),
+    Endpoint::get("/openapi.json", "本接口的 OpenAPI 描述").public().json::<serde_json::Value>(),
+    Endpoint::get("/get_device_auth_url", "当前设备授权 URL").json::<DeviceAuthUrlResponse>(),
+    Endpoint::post("/start_device_auth", "发起 BuilderId 设备授权并在后台轮询").json::<StartDeviceAuthResponse>(),
+    Endpoint::post("/poll_device_auth", "轮询设备授权状态").json::<PollDeviceAuthResponse>(),
+    Endpoint::post("/reload_accounts", "从文件重新加载账号并通知界面刷新").json::<ReloadAccountsResponse>(),
+    Endpoint::post("/reset_machine_id", "重置 Kiro IDE 机器码").json::<ResetMachineIdResponse>(),
+    // 日历应用无法设置请求头，额外接受 ?token= 参数
+    Endpoint::get("/calendar.ics", "奖励/试用到期与配额重置日历")
+        .query(&[
+            QueryParam { name: "ids", description: "逗号分隔的账号 ID，覆盖设置中的选择" },
+            TOKEN_PARAM,
+        ])
+        .text("text/calendar"),
+    // Server-Sent Events，EventSource 同样无法设置请求头，接受 ?token=
+    Endpoint::get("/events", "领域事件流（Server-Sent Events，data 为 EventRecord）")
+        .query(&[
+            QueryParam { name: "since", description: "从该序号之后续传（等同 Last-Event-ID）" },
+            TOKEN_PARAM,
+        ])
+        .text("text/event-stream"),
+    // 账号（固定路径需排在带参数的路径之前）
+    Endpoint::get("/accounts", "账号列表").query(&[INCLUDE_SECRETS_PARAM]).json::<Vec<Account>>(),
+    Endpoint::post("/accounts/sync", "批量同步账号")
+        .request::<SyncAccountsRequest>()
+        .json::<SyncAccountsResponse>(),
+    Endpoint::get("/accounts/{id}", "单个账号").query(&[INCLUDE_SECRETS_PARAM]).json::<Account>(),
+    Endpoint::get("/accounts/{id}/usage", "账号用量（统一结构）").json::<NormalizedUsage>(),
+    Endpoint::post("/accounts/{id}/sync", "刷新 token 并同步用量").json::<Account>(),
+    Endpoint::post("/accounts/{id}/switch", "将 Kiro IDE 切换到该账号")
+        .request::<SwitchAccountRequest>()
+        .json::<SwitchAccountResult>(),
+];
+
+/// 按路由模式匹配路径，成功时返回路径参数
+fn match_path(pattern: &str, path: &str) -> Option<Vec<String>> {
+    let pattern_segments: Vec<&str> = pattern.trim_matches('/').split('/').collect();
+    let path_segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    if pattern_segments.len() != path_segments.len() {
+        return None;
+    }
+
+    let mut params = Vec::new();
+    for (p, s) in pattern_segments.iter().zip(path_segments.iter()) {
+        if p.starts_with('{') && p.ends_with('}') {
+            if s.is_empty() {
+                return None;
             }
+            // 路径段按百分号编码解码（与查询串不同，'+' 不表示空格）
+            let decoded = percent_encoding::percent_decode_str(s)
+                .decode_utf8()
+                .map(|v| v.into_owned())
+                .unwrap_or_else(|_| s.to_string());
+            params.push(decoded);
+        } else if p != s {
+            return None;
+        }
+    }
+    Some(params)
+}
+
+/// 账号重新加载后推送给 WebView 的事件
+const ACCOUNTS_RELOADED_EVENT: &str = "accounts-reloaded";
+
+/// 工作线程共享的上下文
+struct ApiContext {
+    store: Arc<Mutex<AccountStore>>,
+    rt: tokio::runtime::Runtime,
+}
+
+/// 运行中的服务器及其工作线程（用于退出时优雅关闭）
+struct RunningServer {
+    listeners: Vec<Listener>,
+}
+
+/// 一个监听器（TCP 或 Unix 套接字）及其工作线程
+struct Listener {
+    server: Arc<Server>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+static RUNNING_SERVER: Mutex<Option<RunningServer>> = Mutex::new(None);
+
+// ============================================================
+// 请求 / 响应
+// ============================================================
+
+/// 解析后的请求
+pub struct ApiRequest {
+    pub method: Method,
+    pub path: String,
+    pub query: HashMap<String, String>,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+/// 读取请求体，超过上限时返回 413
+fn read_body(request: &mut Request) -> Result<Vec<u8>, ApiResponse> {
+    if request.body_length().map(|len| len as u64 > MAX_BODY_SIZE).unwrap_or(false) {
+        return Err(error_response(413, "Request body too large"));
+    }
+    let mut body = Vec::new();
+    request.as_reader()
+        .take(MAX_BODY_SIZE + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| error_response(400, format!("Failed to read body: {}", e)))?;
+    if body.len() as u64 > MAX_BODY_SIZE {
+        return Err(error_response(413, "Request body too large"));
+    }
+    Ok(body)
+}
+
+impl ApiRequest {
+    fn from_request(request: &Request, body: Vec<u8>) -> Self {
+        let (path, query_str) = match request.url().split_once('?') {
+            Some((p, q)) => (p.to_string(), q.to_string()),
+            None => (request.url().to_string(), String::new()),
         };
 
-        println!("[HTTP Server] Listening on http://{}", addr);
-
-        // 创建 tokio runtime 用于异步操作
-        let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
-
-        for stream in listener.incoming() {
-            match stream {
-                Ok(mut stream) => {
-                    let mut buffer = [0; 1024];
-                    if stream.read(&mut buffer).is_ok() {
-                        let request = String::from_utf8_lossy(&buffer);
-
-                        // GET /get_device_auth_url - 获取当前授权 URL
-                        if request.starts_with("GET /get_device_auth_url") {
-                            let url = CURRENT_DEVICE_AUTH_URL.lock().unwrap().clone();
-                            let json = match url {
-                                Some(u) => format!(r#"{{"url":"{}"}}"#, u),
-                                None => r#"{"url":null}"#.to_string(),
-                            };
-
-                            let response = format!(
-                                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nAccess-Control-Allow-Origin: *\r\nContent-Length: {}\r\n\r\n{}",
-                                json.len(),
-                                json
-                            );
-                            let _ = stream.write_all(response.as_bytes());
-                        }
-                        // POST/GET /start_device_auth - 触发设备授权流程
-                        else if request.starts_with("POST /start_device_auth") || request.starts_with("GET /start_device_auth") {
-                            let result = rt.block_on(async {
-                                start_device_auth_internal().await
-                            });
-
-                            let (status, json) = match result {
-                                Ok(info) => ("200 OK", format!(
-                                    r#"{{"success":true,"url":"{}","device_code":"{}","expires_in":{},"interval":{}}}"#,
-                                    info.0, info.1, info.2, info.3
-                                )),
-                                Err(e) => ("500 Internal Server Error", format!(r#"{{"success":false,"error":"{}"}}"#, e.replace('"', "\\\""))),
-                            };
-
-                            let response = format!(
-                                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nAccess-Control-Allow-Origin: *\r\nContent-Length: {}\r\n\r\n{}",
-                                status,
-                                json.len(),
-                                json
-                            );
-                            let _ = stream.write_all(response.as_bytes());
-                        }
-                        // POST/GET /poll_device_auth - 轮询设备授权状态
-                        else if request.starts_with("POST /poll_device_auth") || request.starts_with("GET /poll_device_auth") {
-                            let result = rt.block_on(async {
-                                poll_device_auth_internal().await
-                            });
-
-                            let (status, json) = match result {
-                                Ok(poll_result) => ("200 OK", poll_result),
-                                Err(e) => ("500 Internal Server Error", format!(r#"{{"status":"error","error":"{}"}}"#, e.replace('"', "\\\""))),
-                            };
-
-                            let response = format!(
-                                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nAccess-Control-Allow-Origin: *\r\nContent-Length: {}\r\n\r\n{}",
-                                status,
-                                json.len(),
-                                json
-                            );
-                            let _ = stream.write_all(response.as_bytes());
-                        }
-                        // GET /status - 获取服务状态
-                        else if request.starts_with("GET /status") {
-                            let json = r#"{"status":"running","version":"1.5.1"}"#;
-                            let response = format!(
-                                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nAccess-Control-Allow-Origin: *\r\nContent-Length: {}\r\n\r\n{}",
-                                json.len(),
-                                json
-                            );
-                            let _ = stream.write_all(response.as_bytes());
-                        }
-                        // GET /reload_accounts - 重新加载账号（通知前端刷新）
-                        else if request.starts_with("GET /reload_accounts") || request.starts_with("POST /reload_accounts") {
-                            // 重新加载账号存储
-                            let store = AccountStore::new();
-                            let count = store.accounts.len();
-                            let json = format!(r#"{{"success":true,"count":{}}}"#, count);
-                            let response = format!(
-                                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nAccess-Control-Allow-Origin: *\r\nContent-Length: {}\r\n\r\n{}",
-                                json.len(),
-                                json
-                            );
-                            let _ = stream.write_all(response.as_bytes());
-                            println!("[HTTP Server] Accounts reloaded: {} accounts", count);
-                        }
-                        // GET/POST /reset_machine_id - 重置机器码
-                        else if request.starts_with("GET /reset_machine_id") || request.starts_with("POST /reset_machine_id") {
-                            let result = reset_kiro_machine_id_inner();
-                            let (status, json) = match result {
-                                Ok(info) => {
-                                    let machine_id = info.machine_id.unwrap_or_default();
-                                    println!("[HTTP Server] Machine ID reset to: {}", &machine_id[..16.min(machine_id.len())]);
-                                    ("200 OK", format!(
-                                        r#"{{"success":true,"machineId":"{}","sqmId":"{}","devDeviceId":"{}"}}"#,
-                                        machine_id,
-                                        info.sqm_id.unwrap_or_default(),
-                                        info.dev_device_id.unwrap_or_default()
-                                    ))
-                                }
-                                Err(e) => ("500 Internal Server Error", format!(r#"{{"success":false,"error":"{}"}}"#, e.replace('"', "\\\""))),
-                            };
-                            let response = format!(
-                                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nAccess-Control-Allow-Origin: *\r\nContent-Length: {}\r\n\r\n{}",
-                                status,
-                                json.len(),
-                                json
-                            );
-                            let _ = stream.write_all(response.as_bytes());
-                        }
-                        else {
-                            // 404 for other paths
-                            let json = r#"{"error":"Not Found","endpoints":["/get_device_auth_url","/start_device_auth","/poll_device_auth","/reload_accounts","/reset_machine_id","/status"]}"#;
-                            let response = format!(
-                                "HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\nAccess-Control-Allow-Origin: *\r\nContent-Length: {}\r\n\r\n{}",
-                                json.len(),
-                                json
-                            );
-                            let _ = stream.write_all(response.as_bytes());
-                        }
-                    }
+        let query = url::form_urlencoded::parse(query_str.as_bytes())
+            .into_owned()
+            .collect();
+
+        let headers = request.headers().iter()
+            .map(|h| (h.field.as_str().as_str().to_ascii_lowercase(), h.value.as_str().to_string()))
+            .collect();
+
+        Self {
+            method: request.method().clone(),
+            path,
+            query,
+            headers,
+            body,
+        }
+    }
+
+    /// 解析 JSON 请求体（空请求体视为默认值）
+    pub fn json_body<T: DeserializeOwned + Default>(&self) -> Result<T, ApiResponse> {
+        if self.body.iter().all(|b| b.is_ascii_whitespace()) {
+            return Ok(T::default());
+        }
+        serde_json::from_slice(&self.body)
+            .map_err(|e| error_response(400, format!("Invalid JSON body: {}", e)))
+    }
+
+    /// 查询参数是否为 true / 1
+    pub fn query_flag(&self, name: &str) -> bool {
+        self.query.get(name)
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false)
+    }
+
+    /// 获取请求头（名称不区分大小写）
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(&name.to_ascii_lowercase()).map(|s| s.as_str())
+    }
+}
+
+/// 待发送的响应
+pub struct ApiResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+    pub extra_headers: Vec<(&'static str, String)>,
+}
+
+impl ApiResponse {
+    pub fn json<T: Serialize>(status: u16, value: &T) -> Self {
+        let body = serde_json::to_vec(value)
+            .unwrap_or_else(|e| format!(r#"{{"error":"Serialize failed: {}"}}"#, e).into_bytes());
+        Self {
+            status,
+            content_type: "application/json",
+            body,
+            extra_headers: Vec::new(),
+        }
+    }
+
+    pub fn text(status: u16, content_type: &'static str, body: String) -> Self {
+        Self {
+            status,
+            content_type,
+            body: body.into_bytes(),
+            extra_headers: Vec::new(),
+        }
+    }
+
+    pub fn with_header(mut self, name: &'static str, value: String) -> Self {
+        self.extra_headers.push((name, value));
+        self
+    }
+}
+
+fn error_response(status: u16, error: impl Into<String>) -> ApiResponse {
+    ApiResponse::json(status, &ErrorResponse { success: false, error: error.into() })
+}
+
+#[derive(Serialize)]
+struct NotFoundResponse {
+    error: &'static str,
+    endpoints: Vec<&'static str>,
+}
+
+// ============================================================
+// 服务器生命周期
+// ============================================================
+
+/// 服务器状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HttpServerState {
+    Bound,
+    Failed,
+    Disabled,
+    Stopped,
+}
+
+/// 服务器状态详情（供界面展示）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpServerStatus {
+    pub state: HttpServerState,
+    pub bind_address: String,
+    pub requested_port: u16,
+    /// 实际监听的端口（端口被占用时可能与设置不同）
+    pub port: Option<u16>,
+    pub base_url: Option<String>,
+    pub unix_socket: Option<String>,
+    pub error: Option<String>,
+    pub discovery_file: String,
+}
+
+impl HttpServerStatus {
+    fn new(state: HttpServerState, bind_address: &str, requested_port: u16) -> Self {
+        Self {
+            state,
+            bind_address: bind_address.to_string(),
+            requested_port,
+            port: None,
+            base_url: None,
+            unix_socket: None,
+            error: None,
+            discovery_file: discovery_file_path().to_string_lossy().to_string(),
+        }
+    }
+}
+
+static SERVER_STATUS: Mutex<Option<HttpServerStatus>> = Mutex::new(None);
+
+/// 状态变化时推送给 WebView 的事件
+const STATUS_EVENT: &str = "http-server-status";
+
+fn set_status(status: HttpServerStatus) {
+    emit_to_webview(STATUS_EVENT, status.clone());
+    *SERVER_STATUS.lock().unwrap() = Some(status);
+}
+
+/// 获取当前服务器状态
+pub fn get_http_server_status() -> HttpServerStatus {
+    SERVER_STATUS.lock().unwrap().clone().unwrap_or_else(|| {
+        let settings = get_app_settings_inner().unwrap_or_default();
+        HttpServerStatus::new(
+            HttpServerState::Stopped,
+            settings.http_bind_address.as_deref().unwrap_or(DEFAULT_BIND_ADDRESS),
+            settings.http_port.unwrap_or(DEFAULT_HTTP_PORT),
+        )
+    })
+}
+
+/// 绑定 TCP 端口：设置的端口被占用时依次尝试后续端口，最后交给系统分配
+fn bind_tcp(address: &str, port: u16) -> Result<Server, String> {
+    let candidates = (0..=PORT_FALLBACK_ATTEMPTS)
+        .filter_map(|i| port.checked_add(i))
+        .chain(std::iter::once(0));
+
+    let mut last_error = String::new();
+    for candidate in candidates {
+        match Server::http((address, candidate)) {
+            Ok(server) => return Ok(server),
+            Err(e) => {
+                println!("[HTTP Server] Failed to bind to {}:{}: {}", address, candidate, e);
+                last_error = e.to_string();
+            }
+        }
+    }
+    Err(format!("无法绑定 {}: {}", address, last_error))
+}
+
+/// 绑定 Unix 套接字（仅当前用户可访问）
+#[cfg(unix)]
+fn bind_unix(path: &std::path::Path) -> Result<Server, String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if path.exists() {
+        // 无法连接说明是上次异常退出遗留的套接字文件
+        if std::os::unix::net::UnixStream::connect(path).is_ok() {
+            return Err(format!("{} 已被其他进程占用", path.display()));
+        }
+        let _ = std::fs::remove_file(path);
+    }
+    if let Some(parent) = path.parent().filter(|p| !p.exists()) {
+        use std::os::unix::fs::DirBuilderExt;
+        std::fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(parent)
+            .map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+
+    // umask 是进程级的，不能为 bind 临时修改；改为在仅本用户可访问的临时目录中 bind，
+    // 收紧权限后再移动到目标位置，套接字出现在目标路径时已是 0600
+    let parent = path.parent().unwrap_or_else(|| std::path::Path::new("."));
+    let staging = parent.join(format!(".kam-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&staging);
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        std::fs::DirBuilder::new()
+            .mode(0o700)
+            .create(&staging)
+            .map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+    let staged = staging.join("sock");
+    let result = Server::http_unix(&staged)
+        .map_err(|e| format!("无法监听 {}: {}", path.display(), e))
+        .and_then(|server| {
+            std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))
+                .map_err(|e| format!("设置套接字权限失败: {}", e))?;
+            std::fs::rename(&staged, path)
+                .map_err(|e| format!("无法监听 {}: {}", path.display(), e))?;
+            Ok(server)
+        });
+    let _ = std::fs::remove_dir_all(&staging);
+    result
+}
+
+/// 为一个监听器启动工作线程
+fn spawn_workers(server: &Arc<Server>, ctx: &Arc<ApiContext>, count: usize) -> Vec<JoinHandle<()>> {
+    (0..count)
+        .map(|_| {
+            let server = server.clone();
+            let ctx = ctx.clone();
+            thread::spawn(move || {
+                // recv 返回错误说明服务器被 unblock，退出线程
+                while let Ok(request) = server.recv() {
+                    handle_request(request, &ctx);
                 }
-                Err(e) => {
-                    eprintln!("[HTTP Server] Connection error: {}", e);
+            })
+        })
+        .collect()
+}
+
+/// 启动 HTTP 服务器（在后台线程运行），与 Tauri 命令共享账号存储
+pub fn start_http_server(store: Arc<Mutex<AccountStore>>) {
+    let settings = get_app_settings_inner().unwrap_or_default();
+    let bind_address = settings.http_bind_address.clone()
+        .filter(|a| !a.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_BIND_ADDRESS.to_string());
+    let requested_port = settings.http_port.unwrap_or(DEFAULT_HTTP_PORT);
+    let mut status = HttpServerStatus::new(HttpServerState::Disabled, &bind_address, requested_port);
+
+    if !settings.http_server_enabled.unwrap_or(true) {
+        println!("[HTTP Server] Disabled in settings");
+        set_status(status);
+        return;
+    }
+
+    if RUNNING_SERVER.lock().unwrap().is_some() {
+        return;
+    }
+
+    status.state = HttpServerState::Failed;
+
+    // 首次启动时生成令牌，供外部脚本读取
+    if let Err(e) = load_or_create_api_token() {
+        eprintln!("[HTTP Server] Failed to prepare API token: {}", e);
+        status.error = Some(e);
+        set_status(status);
+        return;
+    }
+
+    let server = match bind_tcp(&bind_address, requested_port) {
+        Ok(s) => Arc::new(s),
+        Err(e) => {
+            eprintln!("[HTTP Server] {}", e);
+            status.error = Some(e);
+            set_status(status);
+            return;
+        }
+    };
+    let port = server.server_addr().to_ip().map(|a| a.port()).unwrap_or(requested_port);
+    let base_url = format!("http://{}:{}", connect_host(&bind_address), port);
+    if port != requested_port {
+        println!("[HTTP Server] Port {} is busy, using {}", requested_port, port);
+    }
+    println!("[HTTP Server] Listening on {}", base_url);
+
+    // 所有工作线程共享一个 tokio runtime 用于异步操作
+    let rt = match tokio::runtime::Runtime::new() {
+        Ok(rt) => rt,
+        Err(e) => {
+            eprintln!("[HTTP Server] Failed to create tokio runtime: {}", e);
+            status.error = Some(format!("Failed to create tokio runtime: {}", e));
+            set_status(status);
+            return;
+        }
+    };
+    let ctx = Arc::new(ApiContext { store, rt });
+
+    #[cfg_attr(not(unix), allow(unused_mut))]
+    let mut listeners = vec![Listener { workers: spawn_workers(&server, &ctx, WORKER_COUNT), server }];
+
+    // Unix 套接字监听失败不影响 TCP 服务
+    #[cfg(unix)]
+    if settings.http_unix_socket.unwrap_or(false) {
+        let path = settings.http_unix_socket_path.clone()
+            .filter(|p| !p.trim().is_empty())
+            .map(std::path::PathBuf::from)
+            .unwrap_or_else(default_unix_socket_path);
+        match bind_unix(&path) {
+            Ok(unix_server) => {
+                let unix_server = Arc::new(unix_server);
+                println!("[HTTP Server] Listening on unix:{}", path.display());
+                listeners.push(Listener {
+                    workers: spawn_workers(&unix_server, &ctx, UNIX_WORKER_COUNT),
+                    server: unix_server,
+                });
+                status.unix_socket = Some(path.to_string_lossy().to_string());
+            }
+            Err(e) => {
+                eprintln!("[HTTP Server] {}", e);
+                status.error = Some(e);
+            }
+        }
+    }
+
+    let discovery = DiscoveryInfo {
+        base_url: base_url.clone(),
+        address: bind_address.clone(),
+        port,
+        unix_socket: status.unix_socket.clone(),
+        pid: std::process::id(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    if let Err(e) = write_discovery_file(&discovery) {
+        eprintln!("[HTTP Server] {}", e);
+    }
+
+    status.state = HttpServerState::Bound;
+    status.port = Some(port);
+    status.base_url = Some(base_url);
+    set_status(status);
+
+    *RUNNING_SERVER.lock().unwrap() = Some(RunningServer { listeners });
+}
+
+/// 停止 HTTP 服务器：唤醒所有工作线程并等待当前请求处理完成
+pub fn stop_http_server() {
+    let running = RUNNING_SERVER.lock().unwrap().take();
+    if let Some(running) = running {
+        events::disconnect_subscribers();
+        for listener in running.listeners {
+            for _ in 0..listener.workers.len() {
+                listener.server.unblock();
+            }
+            for worker in listener.workers {
+                let _ = worker.join();
+            }
+        }
+        remove_discovery_file();
+        let previous = get_http_server_status();
+        set_status(HttpServerStatus::new(HttpServerState::Stopped, &previous.bind_address, previous.requested_port));
+        println!("[HTTP Server] Stopped");
+    }
+}
+
+/// 处理单个请求并记录日志
+fn handle_request(mut request: Request, ctx: &ApiContext) {
+    let started = Instant::now();
+    let (api_request, body_error) = match read_body(&mut request) {
+        Ok(body) => (ApiRequest::from_request(&request, body), None),
+        Err(resp) => (ApiRequest::from_request(&request, Vec::new()), Some(resp)),
+    };
+    let settings = get_app_settings_inner().unwrap_or_default();
+
+    let origin = api_request.header("origin").map(|s| s.to_string());
+    let origin_allowed = is_origin_allowed(origin.as_deref(), &settings);
+
+    // 只向白名单中的 Origin 返回 CORS 头，不再使用 *
+    let mut cors_headers = Vec::new();
+    if let (Some(o), true) = (origin, origin_allowed) {
+        cors_headers.push(("Access-Control-Allow-Origin", o));
+        cors_headers.push(("Vary", "Origin".to_string()));
+    }
+
+    let response = if !origin_allowed {
+        error_response(403, "Origin not allowed")
+    } else if api_request.method == Method::Options {
+        preflight_response()
+    } else {
+        // 未授权时不暴露请求体错误
+        match (authorize(&api_request), body_error) {
+            (Err(resp), _) | (Ok(_), Some(resp)) => resp,
+            (Ok((endpoint, _)), None) if endpoint.path == "/events" => {
+                if events::subscriber_count() >= MAX_EVENT_STREAMS {
+                    error_response(503, "Too many event streams")
+                } else {
+                    open_event_stream(request, &api_request, cors_headers);
+                    println!("[HTTP Server] GET /events -> 200 (stream opened)");
+                    return;
+                }
+            }
+            (Ok((endpoint, params)), None) => route(&api_request, endpoint, &params, ctx),
+        }
+    };
+
+    send_response(request, &api_request, response, cors_headers, started);
+}
+
+/// 写出响应并记录日志
+fn send_response(
+    request: Request,
+    api_request: &ApiRequest,
+    response: ApiResponse,
+    mut headers: Vec<(&'static str, String)>,
+    started: Instant,
+) {
+
+    println!(
+        "[HTTP Server] {} {} -> {} ({} ms)",
+        api_request.method,
+        api_request.path,
+        response.status,
+        started.elapsed().as_millis()
+    );
+
+    let mut http_response = Response::from_data(response.body)
+        .with_status_code(response.status);
+    headers.insert(0, ("Content-Type", response.content_type.to_string()));
+    headers.extend(response.extra_headers);
+    for (name, value) in headers {
+        if let Ok(header) = Header::from_bytes(name.as_bytes(), value.as_bytes()) {
+            http_response.add_header(header);
+        }
+    }
+
+    if let Err(e) = request.respond(http_response) {
+        eprintln!("[HTTP Server] Failed to send response: {}", e);
+    }
+}
+
+/// CORS 预检响应
+fn preflight_response() -> ApiResponse {
+    let mut response = ApiResponse::text(204, "text/plain", String::new());
+    response.extra_headers.push(("Access-Control-Allow-Methods", "GET, POST, OPTIONS".to_string()));
+    response.extra_headers.push(("Access-Control-Allow-Headers", "Authorization, Content-Type".to_string()));
+    response.extra_headers.push(("Access-Control-Max-Age", "600".to_string()));
+    response
+}
+
+/// 检查路由、方法与令牌，返回匹配的路由及路径参数
+fn authorize(req: &ApiRequest) -> Result<(&'static Endpoint, Vec<String>), ApiResponse> {
+    let matches: Vec<(&'static Endpoint, Vec<String>)> = ENDPOINTS.iter()
+        .filter_map(|e| match_path(e.path, &req.path).map(|params| (e, params)))
+        .collect();
+
+    if matches.is_empty() {
+        return Err(ApiResponse::json(404, &NotFoundResponse {
+            error: "Not Found",
+            endpoints: ENDPOINTS.iter().map(|e| e.path).collect(),
+        }));
+    }
+
+    let (endpoint, params) = match matches.iter().find(|(e, _)| e.method == req.method) {
+        Some(m) => m.clone(),
+        None => {
+            let allow: Vec<String> = matches.iter().map(|(e, _)| e.method.to_string()).collect();
+            return Err(error_response(405, "Method Not Allowed")
+                .with_header("Allow", allow.join(", ")));
+        }
+    };
+
+    if endpoint.public {
+        return Ok((endpoint, params));
+    }
+
+    let token = load_or_create_api_token().map_err(|e| error_response(500, e))?;
+    let authorized = verify_bearer(req.header("authorization"), &token)
+        || (matches!(endpoint.path, "/calendar.ics" | "/events")
+            && verify_query_token(req.query.get("token").map(|s| s.as_str()), &token));
+
+    if authorized {
+        Ok((endpoint, params))
+    } else {
+        Err(error_response(401, "Unauthorized")
+            .with_header("WWW-Authenticate", "Bearer".to_string()))
+    }
+}
+
+/// 路由分发（方法与令牌已在 authorize 中校验）
+fn route(req: &ApiRequest, endpoint: &Endpoint, params: &[String], ctx: &ApiContext) -> ApiResponse {
+    let rt = &ctx.rt;
+    match endpoint.path {
+        // 获取当前授权 URL
+        "/get_device_auth_url" => {
+            let url = CURRENT_DEVICE_AUTH_URL.lock().unwrap().clone();
+            ApiResponse::json(200, &DeviceAuthUrlResponse { url })
+        }
+        // 触发设备授权流程
+        "/start_device_auth" => {
+            match rt.block_on(start_device_auth_internal(ctx.store.clone())) {
+                Ok((url, device_code, expires_in, interval)) => ApiResponse::json(200, &StartDeviceAuthResponse {
+                    success: true,
+                    url,
+                    device_code,
+                    expires_in,
+                    interval,
+                }),
+                Err(e) => error_response(500, e),
+            }
+        }
+        // 轮询设备授权状态
+        "/poll_device_auth" => {
+            match rt.block_on(poll_device_auth_internal(&ctx.store)) {
+                Ok(result) => ApiResponse::json(200, &result),
+                Err(e) => ApiResponse::json(500, &PollDeviceAuthResponse::Error { error: e }),
+            }
+        }
+        // OpenAPI 描述（由路由表与类型定义生成）
+        "/openapi.json" => ApiResponse::json(200, &openapi_spec()),
+        // 获取服务状态
+        "/status" => {
+            ApiResponse::json(200, &StatusResponse {
+                status: "running".to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            })
+        }
+        // 从文件重新加载共享的账号存储，并通知前端刷新
+        "/reload_accounts" => {
+            let (count, diff) = {
+                let mut store = ctx.store.lock().unwrap();
+                let previous = store.get_all();
+                store.reload_from_file();
+                (store.accounts.len(), diff_accounts(&previous, &store.accounts))
+            };
+            println!(
+                "[HTTP Server] Accounts reloaded: {} accounts (+{} -{} ~{})",
+                count,
+                diff.added.len(),
+                diff.removed.len(),
+                diff.changed.len()
+            );
+            let response = ReloadAccountsResponse { success: true, count, diff };
+            emit_to_webview(ACCOUNTS_RELOADED_EVENT, response.clone());
+            ApiResponse::json(200, &response)
+        }
+        // 重置机器码
+        "/reset_machine_id" => {
+            match reset_kiro_machine_id_inner(None) {
+                Ok(info) => {
+                    let machine_id = info.machine_id.unwrap_or_default();
+                    println!("[HTTP Server] Machine ID reset to: {}", &machine_id[..16.min(machine_id.len())]);
+                    ApiResponse::json(200, &ResetMachineIdResponse {
+                        success: true,
+                        machine_id,
+                        sqm_id: info.sqm_id.unwrap_or_default(),
+                        dev_device_id: info.dev_device_id.unwrap_or_default(),
+                    })
                 }
+                Err(e) => error_response(500, e),
+            }
+        }
+        // 奖励/试用到期与配额重置日历（需在设置中开启）
+        "/calendar.ics" => {
+            let settings = get_app_settings_inner().unwrap_or_default();
+            if !settings.calendar_feed_enabled.unwrap_or(false) {
+                return error_response(404, "Calendar feed disabled");
             }
+            // ?ids=a,b 可覆盖设置中选择的账号
+            let ids: Option<Vec<String>> = req.query.get("ids")
+                .map(|v| v.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect())
+                .or(settings.calendar_account_ids);
+            let accounts = ctx.store.lock().unwrap().get_all();
+            let accounts = select_accounts(&accounts, ids.as_deref());
+            let (ics, _) = generate_ics(&accounts);
+            ApiResponse::text(200, "text/calendar; charset=utf-8", ics)
+                .with_header("Content-Disposition", "inline; filename=\"kiro-accounts.ics\"".to_string())
         }
+        // 账号列表（默认隐藏密钥，?include_secrets=true 时返回原文）
+        "/accounts" => {
+            let include_secrets = req.query_flag("include_secrets");
+            let accounts = ctx.store.lock().unwrap().get_all();
+            let accounts: Vec<Account> = if include_secrets {
+                accounts
+            } else {
+                accounts.iter().map(Account::redacted).collect()
+            };
+            ApiResponse::json(200, &accounts)
+        }
+        // 单个账号
+        "/accounts/{id}" => {
+            match find_account(ctx, &params[0]) {
+                Some(account) if req.query_flag("include_secrets") => ApiResponse::json(200, &account),
+                Some(account) => ApiResponse::json(200, &account.redacted()),
+                None => error_response(404, "Account not found"),
+            }
+        }
+        // 账号用量（统一结构）
+        "/accounts/{id}/usage" => {
+            let account = match find_account(ctx, &params[0]) {
+                Some(a) => a,
+                None => return error_response(404, "Account not found"),
+            };
+            match normalize_usage(&account) {
+                Some(usage) => ApiResponse::json(200, &usage),
+                None => error_response(404, "No usage data, sync the account first"),
+            }
+        }
+        // 同步单个账号
+        "/accounts/{id}/sync" => {
+            let account = match find_account(ctx, &params[0]) {
+                Some(a) => a,
+                None => return error_response(404, "Account not found"),
+            };
+            match rt.block_on(sync_account(&ctx.store, &account.id)) {
+                Ok(account) => ApiResponse::json(200, &account.redacted()),
+                Err(e) => error_response(502, e),
+            }
+        }
+        // 批量同步
+        "/accounts/sync" => {
+            let body: SyncAccountsRequest = match req.json_body() {
+                Ok(b) => b,
+                Err(resp) => return resp,
+            };
+            let ids = match (body.all, body.ids) {
+                (true, _) => ctx.store.lock().unwrap().accounts.iter().map(|a| a.id.clone()).collect(),
+                (false, Some(ids)) if !ids.is_empty() => ids,
+                _ => return error_response(400, "Specify \"ids\" or \"all\": true"),
+            };
+
+            let results: Vec<SyncAccountResult> = ids.into_iter()
+                .map(|id| match rt.block_on(sync_account(&ctx.store, &id)) {
+                    Ok(account) => SyncAccountResult { id, success: true, account: Some(account.redacted()), error: None },
+                    Err(e) => SyncAccountResult { id, success: false, account: None, error: Some(e) },
+                })
+                .collect();
+            let succeeded = results.iter().filter(|r| r.success).count();
+            println!("[HTTP Server] Synced {}/{} accounts", succeeded, results.len());
+            ApiResponse::json(200, &SyncAccountsResponse {
+                succeeded,
+                failed: results.len() - succeeded,
+                results,
+            })
+        }
+        // 切换 IDE 到指定账号
+        "/accounts/{id}/switch" => {
+            let body: SwitchAccountRequest = match req.json_body() {
+                Ok(b) => b,
+                Err(resp) => return resp,
+            };
+            let account = match find_account(ctx, &params[0]) {
+                Some(a) => a,
+                None => return error_response(404, "Account not found"),
+            };
+            let mut switch_params = match SwitchAccountParams::from_account(&account, body.reset_machine_id, body.auto_restart) {
+                Ok(p) => p,
+                Err(e) => return error_response(400, e),
+            };
+            switch_params.force_close = Some(body.force_close);
+            switch_params.installation_id = body.installation_id.clone();
+            match switch_kiro_account_inner(switch_params) {
+                Ok(result) if result.awaiting_force_close => {
+                    println!("[HTTP Server] Switch to {} waiting for force close confirmation", account.email);
+                    ApiResponse::json(200, &result)
+                }
+                Ok(result) => {
+                    println!("[HTTP Server] Switched to account: {}", account.email);
+                    ApiResponse::json(200, &result)
+                }
+                Err(e) => error_response(500, e),
+            }
+        }
+        _ => error_response(404, "Not Found"),
+    }
+}
+
+/// 从共享存储中查找账号（只读快照，与文件的同步由 /reload_accounts 负责）
+fn find_account(ctx: &ApiContext, id: &str) -> Option<Account> {
+    let store = ctx.store.lock().unwrap();
+    store.accounts.iter().find(|a| a.id == id).cloned()
+}
+
+// ============================================================
+// 事件流（Server-Sent Events）
+// ============================================================
+
+/// 打开事件流：在独立线程中持续推送，直到客户端断开或服务器停止
+///
+/// 客户端可通过 Last-Event-ID 请求头或 ?since= 参数从指定序号之后续传；
+/// 若该序号已不在历史中，先发送 resync 事件，客户端应重新拉取 /accounts。
+fn open_event_stream(
+    request: Request,
+    api_request: &ApiRequest,
+    cors_headers: Vec<(&'static str, String)>,
+) {
+    let since = api_request.header("last-event-id")
+        .or_else(|| api_request.query.get("since").map(|s| s.as_str()))
+        .and_then(|v| v.trim().parse::<u64>().ok());
+    let subscription = events::subscribe(since);
+
+    thread::spawn(move || {
+        let mut writer = request.into_writer();
+        let mut head = String::from(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n",
+        );
+        for (name, value) in cors_headers {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        head.push_str("\r\nretry: 3000\n\n");
+        if writer.write_all(head.as_bytes()).is_err() {
+            return;
+        }
+
+        if subscription.missed && writer.write_all(b"event: resync\ndata: {}\n\n").is_err() {
+            return;
+        }
+        for record in &subscription.backlog {
+            if write_event(&mut writer, record).is_err() {
+                return;
+            }
+        }
+        if writer.flush().is_err() {
+            return;
+        }
+
+        loop {
+            let result = match subscription.receiver.recv_timeout(EVENT_STREAM_KEEPALIVE) {
+                Ok(record) => write_event(&mut writer, &record),
+                Err(RecvTimeoutError::Timeout) => writer.write_all(b": keep-alive\n\n"),
+                // 服务器停止时订阅者被断开
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+            if result.and_then(|_| writer.flush()).is_err() {
+                break;
+            }
+        }
+        println!("[HTTP Server] Event stream closed");
     });
 }
 
+fn write_event(writer: &mut dyn Write, record: &EventRecord) -> std::io::Result<()> {
+    let data = serde_json::to_string(record).unwrap_or_else(|_| "{}".to_string());
+    write!(writer, "id: {}\nevent: {}\ndata: {}\n\n", record.seq, record.event.name(), data)
+}
+
+// ============================================================
+// 设备授权
+// ============================================================
+
 /// 内部函数：触发设备授权流程
 /// 返回 (url, device_code, expires_in, interval)
 /// 同时启动后台轮询线程，模拟手动点击 "AWS Builder ID" 的行为
-async fn start_device_auth_internal() -> Result<(String, String, i64, i64), String> {
+async fn start_device_auth_internal(store: Arc<Mutex<AccountStore>>) -> Result<(String, String, i64, i64), String> {
     let region = "us-east-1";
-    let start_url = "https://view.awsapps.com/start";
 
     println!("[HTTP Server] Starting device authorization...");
 
-    let sso_client = AWSSSOClient::new(region);
-
-    // Step 1: 注册客户端
-    let client_reg = sso_client.register_device_client(start_url).await?;
-    println!("[HTTP Server] Client registered: {}", &client_reg.client_id);
-
-    // Step 2: 发起设备授权
-    let device_auth = sso_client.start_device_authorization(
-        &client_reg.client_id,
-        &client_reg.client_secret,
-        start_url,
-    ).await?;
-
-    let url = device_auth.verification_uri_complete.clone()
-        .unwrap_or_else(|| device_auth.verification_uri.clone());
-
-    println!("[HTTP Server] Device auth URL: {}", url);
-    println!("[HTTP Server] User Code: {}", device_auth.user_code);
-    println!("[HTTP Server] Device Code: {}", device_auth.device_code);
+    let info = account_service::start_device_auth(region).await?;
+    let url = info.url();
 
     // 计算过期时间
-    let expires_at = chrono::Utc::now().timestamp() + device_auth.expires_in;
-    let interval = device_auth.interval.unwrap_or(5);
+    let expires_at = chrono::Utc::now().timestamp() + info.expires_in;
 
     // 保存待处理的设备授权信息
     *PENDING_DEVICE_AUTH.lock().unwrap() = Some(PendingDeviceAuth {
-        device_code: device_auth.device_code.clone(),
-        client_id: client_reg.client_id.clone(),
-        client_secret: client_reg.client_secret.clone(),
+        device_code: info.device_code.clone(),
+        client_id: info.client_id.clone(),
+        client_secret: info.client_secret.clone(),
         region: region.to_string(),
         expires_at,
     });
 
     // 更新全局 URL
     *CURRENT_DEVICE_AUTH_URL.lock().unwrap() = Some(url.clone());
+    events::publish(DomainEvent::DeviceAuth {
+        state: DeviceAuthState::Started,
+        url: Some(url.clone()),
+        email: None,
+    });
 
     // 启动后台轮询线程（模拟手动点击 "AWS Builder ID" 的行为）
-    let client_id = client_reg.client_id.clone();
-    let client_secret = client_reg.client_secret.clone();
-    let device_code = device_auth.device_code.clone();
-    let poll_interval = interval as u64;
-    let poll_expires_in = device_auth.expires_in;
-
+    let poll_info = info.clone();
     std::thread::spawn(move || {
-        let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+        let rt = match tokio::runtime::Runtime::new() {
+            Ok(rt) => rt,
+            Err(e) => {
+                eprintln!("[HTTP Server] Failed to create tokio runtime for device auth polling: {}", e);
+                clear_device_auth_state(DeviceAuthState::Cancelled, None);
+                return;
+            }
+        };
         rt.block_on(async {
-            background_poll_device_auth(
-                client_id,
-                client_secret,
-                device_code,
-                region.to_string(),
-                poll_interval,
-                poll_expires_in,
-            ).await;
+            background_poll_device_auth(&store, &poll_info, region).await;
         });
     });
 
-    Ok((url, device_auth.device_code, device_auth.expires_in, interval))
+    Ok((url, info.device_code, info.expires_in, info.interval))
 }
 
-/// 后台轮询设备授权状态
-async fn background_poll_device_auth(
-    client_id: String,
-    client_secret: String,
-    device_code: String,
-    region: String,
-    mut interval: u64,
-    expires_in: i64,
-) {
-    use std::time::{Duration, Instant};
+/// 清除全局设备授权状态并发布结束事件
+fn clear_device_auth_state(state: DeviceAuthState, email: Option<String>) {
+    *CURRENT_DEVICE_AUTH_URL.lock().unwrap() = None;
+    *PENDING_DEVICE_AUTH.lock().unwrap() = None;
+    events::publish(DomainEvent::DeviceAuth { state, url: None, email });
+}
 
+/// 后台轮询设备授权状态
+async fn background_poll_device_auth(store: &Mutex<AccountStore>, info: &DeviceAuthInfo, region: &str) {
     println!("[HTTP Server] Starting background polling...");
 
-    let sso_client = AWSSSOClient::new(&region);
-    let timeout = Instant::now() + Duration::from_secs(expires_in as u64);
-    let start_url = "https://view.awsapps.com/start";
+    let mut interval = info.interval as u64;
+    let timeout = Instant::now() + Duration::from_secs(info.expires_in as u64);
 
     loop {
         if Instant::now() > timeout {
             println!("[HTTP Server] Background polling timed out");
-            *CURRENT_DEVICE_AUTH_URL.lock().unwrap() = None;
-            *PENDING_DEVICE_AUTH.lock().unwrap() = None;
+            clear_device_auth_state(DeviceAuthState::Expired, None);
             break;
         }
 
         tokio::time::sleep(Duration::from_secs(interval)).await;
 
-        match sso_client.poll_device_token(&client_id, &client_secret, &device_code).await {
-            Ok(DevicePollResult::Success(token)) => {
+        match account_service::poll_device_auth(store, &info.device_code, &info.client_id, &info.client_secret, region).await {
+            Ok(DeviceAuthPoll::Success(account)) => {
                 println!("[HTTP Server] Background poll: Authorization successful!");
 
                 // 清除全局状态
-                *CURRENT_DEVICE_AUTH_URL.lock().unwrap() = None;
-                *PENDING_DEVICE_AUTH.lock().unwrap() = None;
-
-                // 计算 client_id_hash
-                let mut hasher = Sha256::new();
-                hasher.update(start_url.as_bytes());
-                let client_id_hash = hex::encode(hasher.finalize());
-
-                // 获取用户信息
-                let machine_id = get_machine_id();
-                let cw_client = CodeWhispererClient::new(&machine_id);
-                let usage = cw_client.get_usage_limits(&token.access_token).await.ok();
-                let usage_data = serde_json::to_value(&usage).unwrap_or(serde_json::Value::Null);
-
-                let email = usage.as_ref()
-                    .and_then(|u| u.user_info.as_ref())
-                    .and_then(|ui| ui.email.clone())
-                    .unwrap_or_else(|| "user@builder.id".to_string());
-                let user_id = usage.as_ref()
-                    .and_then(|u| u.user_info.as_ref())
-                    .and_then(|ui| ui.user_id.clone());
-
-                let expires_at = chrono::Local::now() + chrono::Duration::seconds(token.expires_in);
-
-                // 保存账号到文件
-                let mut store = AccountStore::new();
-
-                if let Some(existing) = store.accounts.iter_mut()
-                    .find(|a| a.email == email && a.provider.as_deref() == Some("BuilderId"))
-                {
-                    existing.access_token = Some(token.access_token.clone());
-                    existing.refresh_token = Some(token.refresh_token.clone());
-                    existing.user_id = user_id;
-                    existing.expires_at = Some(expires_at.format("%Y/%m/%d %H:%M:%S").to_string());
-                    existing.client_id_hash = Some(client_id_hash);
-                    existing.client_id = Some(client_id.clone());
-                    existing.client_secret = Some(client_secret.clone());
-                    existing.region = Some(region.clone());
-                    existing.sso_session_id = token.aws_sso_app_session_id;
-                    existing.id_token = token.id_token;
-                    existing.usage_data = Some(usage_data);
-                    existing.status = "正常".to_string();
-                } else {
-                    let mut account = Account::new(email.clone(), "Kiro BuilderId 账号".to_string());
-                    account.access_token = Some(token.access_token.clone());
-                    account.refresh_token = Some(token.refresh_token.clone());
-                    account.provider = Some("BuilderId".to_string());
-                    account.user_id = user_id;
-                    account.expires_at = Some(expires_at.format("%Y/%m/%d %H:%M:%S").to_string());
-                    account.client_id_hash = Some(client_id_hash);
-                    account.client_id = Some(client_id.clone());
-                    account.client_secret = Some(client_secret.clone());
-                    account.region = Some(region.clone());
-                    account.sso_session_id = token.aws_sso_app_session_id;
-                    account.id_token = token.id_token;
-                    account.usage_data = Some(usage_data);
-                    store.accounts.insert(0, account);
-                }
-
-                store.save_to_file();
-                println!("[HTTP Server] Account saved: {}", email);
+                clear_device_auth_state(DeviceAuthState::Succeeded, Some(account.email));
 
                 // 注册成功后自动重置机器码
-                match reset_kiro_machine_id_inner() {
+                match reset_kiro_machine_id_inner(None) {
                     Ok(info) => {
                         let mid = info.machine_id.unwrap_or_default();
                         println!("[HTTP Server] Machine ID reset: {}...", &mid[..16.min(mid.len())]);
@@ -336,25 +1097,23 @@ async fn background_poll_device_auth(
 
                 break;
             }
-            Ok(DevicePollResult::Pending) => {
+            Ok(DeviceAuthPoll::Pending) => {
                 // 继续轮询
                 continue;
             }
-            Ok(DevicePollResult::SlowDown) => {
+            Ok(DeviceAuthPoll::SlowDown) => {
                 // 增加轮询间隔
                 interval += 5;
                 continue;
             }
-            Ok(DevicePollResult::Expired) => {
+            Ok(DeviceAuthPoll::Expired) => {
                 println!("[HTTP Server] Background poll: Device code expired");
-                *CURRENT_DEVICE_AUTH_URL.lock().unwrap() = None;
-                *PENDING_DEVICE_AUTH.lock().unwrap() = None;
+                clear_device_auth_state(DeviceAuthState::Expired, None);
                 break;
             }
-            Ok(DevicePollResult::Denied) => {
+            Ok(DeviceAuthPoll::Denied) => {
                 println!("[HTTP Server] Background poll: Authorization denied");
-                *CURRENT_DEVICE_AUTH_URL.lock().unwrap() = None;
-                *PENDING_DEVICE_AUTH.lock().unwrap() = None;
+                clear_device_auth_state(DeviceAuthState::Denied, None);
                 break;
             }
             Err(e) => {
@@ -367,7 +1126,7 @@ async fn background_poll_device_auth(
 }
 
 /// 内部函数：轮询设备授权状态
-async fn poll_device_auth_internal() -> Result<String, String> {
+async fn poll_device_auth_internal(store: &Mutex<AccountStore>) -> Result<PollDeviceAuthResponse, String> {
     let pending = {
         PENDING_DEVICE_AUTH.lock().unwrap().clone()
     };
@@ -377,87 +1136,25 @@ async fn poll_device_auth_internal() -> Result<String, String> {
     // 检查是否过期
     let now = chrono::Utc::now().timestamp();
     if now > pending.expires_at {
-        // 清除状态
-        *PENDING_DEVICE_AUTH.lock().unwrap() = None;
-        *CURRENT_DEVICE_AUTH_URL.lock().unwrap() = None;
-        return Ok(r#"{"status":"expired"}"#.to_string());
+        clear_device_auth_state(DeviceAuthState::Expired, None);
+        return Ok(PollDeviceAuthResponse::Expired);
     }
 
-    let sso_client = AWSSSOClient::new(&pending.region);
-
-    match sso_client.poll_device_token(&pending.client_id, &pending.client_secret, &pending.device_code).await? {
-        DevicePollResult::Success(token) => {
+    match account_service::poll_device_auth(
+        store,
+        &pending.device_code,
+        &pending.client_id,
+        &pending.client_secret,
+        &pending.region,
+    ).await? {
+        DeviceAuthPoll::Success(account) => {
             println!("[HTTP Server] Authorization successful!");
 
             // 清除全局状态
-            *CURRENT_DEVICE_AUTH_URL.lock().unwrap() = None;
-            *PENDING_DEVICE_AUTH.lock().unwrap() = None;
-
-            // 计算 client_id_hash
-            let start_url = "https://view.awsapps.com/start";
-            let mut hasher = Sha256::new();
-            hasher.update(start_url.as_bytes());
-            let client_id_hash = hex::encode(hasher.finalize());
-
-            // 获取用户信息
-            let machine_id = get_machine_id();
-            let cw_client = CodeWhispererClient::new(&machine_id);
-            let usage = cw_client.get_usage_limits(&token.access_token).await.ok();
-            let usage_data = serde_json::to_value(&usage).unwrap_or(serde_json::Value::Null);
-
-            let email = usage.as_ref()
-                .and_then(|u| u.user_info.as_ref())
-                .and_then(|ui| ui.email.clone())
-                .unwrap_or_else(|| "user@builder.id".to_string());
-            let user_id = usage.as_ref()
-                .and_then(|u| u.user_info.as_ref())
-                .and_then(|ui| ui.user_id.clone());
-
-            let expires_at = chrono::Local::now() + chrono::Duration::seconds(token.expires_in);
-
-            // 保存账号到文件
-            let mut store = AccountStore::new();
-
-            let account = if let Some(existing) = store.accounts.iter_mut()
-                .find(|a| a.email == email && a.provider.as_deref() == Some("BuilderId"))
-            {
-                existing.access_token = Some(token.access_token.clone());
-                existing.refresh_token = Some(token.refresh_token.clone());
-                existing.user_id = user_id;
-                existing.expires_at = Some(expires_at.format("%Y/%m/%d %H:%M:%S").to_string());
-                existing.client_id_hash = Some(client_id_hash);
-                existing.client_id = Some(pending.client_id.clone());
-                existing.client_secret = Some(pending.client_secret.clone());
-                existing.region = Some(pending.region.clone());
-                existing.sso_session_id = token.aws_sso_app_session_id;
-                existing.id_token = token.id_token;
-                existing.usage_data = Some(usage_data);
-                existing.status = "正常".to_string();
-                existing.clone()
-            } else {
-                let mut account = Account::new(email.clone(), "Kiro BuilderId 账号".to_string());
-                account.access_token = Some(token.access_token.clone());
-                account.refresh_token = Some(token.refresh_token.clone());
-                account.provider = Some("BuilderId".to_string());
-                account.user_id = user_id;
-                account.expires_at = Some(expires_at.format("%Y/%m/%d %H:%M:%S").to_string());
-                account.client_id_hash = Some(client_id_hash);
-                account.client_id = Some(pending.client_id.clone());
-                account.client_secret = Some(pending.client_secret.clone());
-                account.region = Some(pending.region.clone());
-                account.sso_session_id = token.aws_sso_app_session_id;
-                account.id_token = token.id_token;
-                account.usage_data = Some(usage_data);
-                store.accounts.insert(0, account.clone());
-                account
-            };
-
-            store.save_to_file();
-
-            println!("[HTTP Server] Account saved: {}", email);
+            clear_device_auth_state(DeviceAuthState::Succeeded, Some(account.email.clone()));
 
             // 注册成功后自动重置机器码，为下一次注册准备新的机器码
-            let new_machine_id = match reset_kiro_machine_id_inner() {
+            let new_machine_id = match reset_kiro_machine_id_inner(None) {
                 Ok(info) => {
                     let mid = info.machine_id.unwrap_or_default();
                     println!("[HTTP Server] Machine ID reset for next registration: {}...", &mid[..16.min(mid.len())]);
@@ -470,29 +1167,22 @@ async fn poll_device_auth_internal() -> Result<String, String> {
             };
 
             // 返回结果，包含新机器码信息
-            let response_json = if let Some(new_mid) = new_machine_id {
-                format!(r#"{{"status":"success","email":"{}","account_id":"{}","machine_id_reset":true,"new_machine_id":"{}"}}"#, email, account.id, new_mid)
-            } else {
-                format!(r#"{{"status":"success","email":"{}","account_id":"{}","machine_id_reset":false}}"#, email, account.id)
-            };
-
-            Ok(response_json)
-        }
-        DevicePollResult::Pending => {
-            Ok(r#"{"status":"pending"}"#.to_string())
-        }
-        DevicePollResult::SlowDown => {
-            Ok(r#"{"status":"slow_down"}"#.to_string())
+            Ok(PollDeviceAuthResponse::Success {
+                email: account.email,
+                account_id: account.id,
+                machine_id_reset: new_machine_id.is_some(),
+                new_machine_id,
+            })
         }
-        DevicePollResult::Expired => {
-            *CURRENT_DEVICE_AUTH_URL.lock().unwrap() = None;
-            *PENDING_DEVICE_AUTH.lock().unwrap() = None;
-            Ok(r#"{"status":"expired"}"#.to_string())
+        DeviceAuthPoll::Pending => Ok(PollDeviceAuthResponse::Pending),
+        DeviceAuthPoll::SlowDown => Ok(PollDeviceAuthResponse::SlowDown),
+        DeviceAuthPoll::Expired => {
+            clear_device_auth_state(DeviceAuthState::Expired, None);
+            Ok(PollDeviceAuthResponse::Expired)
         }
-        DevicePollResult::Denied => {
-            *CURRENT_DEVICE_AUTH_URL.lock().unwrap() = None;
-            *PENDING_DEVICE_AUTH.lock().unwrap() = None;
-            Ok(r#"{"status":"denied"}"#.to_string())
+        DeviceAuthPoll::Denied => {
+            clear_device_auth_state(DeviceAuthState::Denied, None);
+            Ok(PollDeviceAuthResponse::Denied)
         }
     }
 }
@@ -0,0 +1,115 @@
+// 本地 HTTP API 的请求 / 响应类型（服务端、客户端与 OpenAPI 描述共用）
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use kiro_core::account::{Account, AccountDiff};
+
+/// 错误响应
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ErrorResponse {
+    pub success: bool,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StatusResponse {
+    pub status: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DeviceAuthUrlResponse {
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StartDeviceAuthResponse {
+    pub success: bool,
+    pub url: String,
+    pub device_code: String,
+    pub expires_in: i64,
+    pub interval: i64,
+}
+
+/// 设备授权轮询结果
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum PollDeviceAuthResponse {
+    Success {
+        email: String,
+        account_id: String,
+        machine_id_reset: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        new_machine_id: Option<String>,
+    },
+    Pending,
+    SlowDown,
+    Expired,
+    Denied,
+    Error {
+        error: String,
+    },
+}
+
+/// 重新加载结果：账号总数及与加载前相比的差异
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReloadAccountsResponse {
+    pub success: bool,
+    pub count: usize,
+    #[serde(flatten)]
+    pub diff: AccountDiff,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetMachineIdResponse {
+    pub success: bool,
+    pub machine_id: String,
+    pub sqm_id: String,
+    pub dev_device_id: String,
+}
+
+/// POST /accounts/sync 请求体：指定 ids 或 all=true
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncAccountsRequest {
+    #[serde(default)]
+    pub ids: Option<Vec<String>>,
+    #[serde(default)]
+    pub all: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncAccountResult {
+    pub id: String,
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<Account>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncAccountsResponse {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<SyncAccountResult>,
+}
+
+/// POST /accounts/{id}/switch 请求体
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchAccountRequest {
+    #[serde(default)]
+    pub reset_machine_id: bool,
+    #[serde(default)]
+    pub auto_restart: bool,
+    /// 确认可强制关闭未在超时内退出的 IDE
+    #[serde(default)]
+    pub force_close: bool,
+    /// 目标 Kiro 安装 ID（为空时为默认安装）
+    #[serde(default)]
+    pub installation_id: Option<String>,
+}
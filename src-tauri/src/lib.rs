@@ -0,0 +1,261 @@
+mod auto_register;
+mod calendar;
+pub mod commands;
+mod headless;
+mod http_auth;
+pub mod http_client;
+mod http_discovery;
+mod http_openapi;
+pub mod http_server;
+pub mod http_types;
+mod overage;
+mod state;
+mod webview;
+
+use kiro_core::account::AccountStore;
+use kiro_core::auth::AuthState;
+use kiro_core::deep_link_handler;
+use state::AppState;
+use std::sync::{Arc, Mutex};
+use tauri::{Listener, Manager};
+
+// 导入命令
+use commands::account_cmd::{
+    get_accounts, reload_accounts, delete_account, delete_accounts, update_account, sync_account,
+    refresh_account_token, verify_account, add_account_by_social, add_local_kiro_account,
+    add_account_by_idc, import_accounts, export_accounts
+};
+use commands::app_settings_cmd::*;
+use commands::auth_cmd::*;
+use commands::browser_cmd::*;
+use commands::calendar_cmd::*;
+use commands::http_api_cmd::*;
+use commands::installation_cmd::*;
+use commands::kiro_cmd::*;
+use commands::kiro_settings_cmd::*;
+use commands::machine_guid_cmd::*;
+use commands::mcp_cmd::*;
+use commands::mcp_profile_cmd::*;
+use commands::mcp_secret_cmd::*;
+use commands::overage_cmd::*;
+use commands::powers_cmd::*;
+use commands::process_cmd::*;
+use commands::proxy_cmd::*;
+use commands::sso_import_cmd::*;
+use commands::update_cmd::*;
+use commands::web_oauth_cmd::*;
+use commands::steering_cmd::*;
+use commands::auto_register_cmd::*;
+use commands::workspace_cmd::*;
+
+/// 应用入口（GUI 与 --headless 无界面模式）
+pub fn run() {
+    // --headless：不启动 WebView，只运行 HTTP API 与 token 定时刷新
+    match headless::parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => std::process::exit(headless::run(options)),
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, headless::USAGE);
+            std::process::exit(2);
+        }
+    }
+
+    tauri::Builder::default()
+        .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_process::init())
+        .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_fs::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .setup(|app| {
+            // 领域事件同时推送给 WebView
+            webview::set_app_handle(app.handle().clone());
+            
+            // 启动 HTTP 服务器
+            http_server::start_http_server(app.state::<AppState>().store.clone());
+            
+            // 监听 deep link 事件 (使用 kiro:// 协议)
+            #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+            {
+                use tauri_plugin_deep_link::DeepLinkExt;
+                let _ = app.deep_link().register("kiro");
+            }
+            
+            // 监听 deep link URL
+            let app_handle = app.handle().clone();
+            app.listen("deep-link://new-url", move |event| {
+                let payload = event.payload();
+                println!("[DeepLink] Received: {}", payload);
+                // 处理 OAuth 回调
+                deep_link_handler::handle_deep_link(payload);
+                // 聚焦窗口
+                if let Some(window) = app_handle.get_webview_window("main") {
+                    let _ = window.set_focus();
+                }
+            });
+            
+            Ok(())
+        })
+        .manage(AppState {
+            store: Arc::new(Mutex::new(AccountStore::new())),
+            auth: AuthState::new(),
+            pending_login: Mutex::new(None),
+        })
+        .invoke_handler(tauri::generate_handler![
+            // 账号命令
+            get_accounts,
+            reload_accounts,
+            delete_account,
+            delete_accounts,
+            update_account,
+            sync_account,
+            refresh_account_token,
+            verify_account,
+            add_account_by_social,
+            add_local_kiro_account,
+            add_account_by_idc,
+            import_accounts,
+            export_accounts,
+            // Auth 命令
+            get_current_user,
+            logout,
+            kiro_login,
+            get_supported_providers,
+            handle_kiro_social_callback,
+            add_kiro_account,
+            get_device_auth_url,
+            poll_device_auth,
+            clear_device_auth_url,
+            // Kiro IDE 命令
+            get_kiro_local_token,
+            switch_kiro_account,
+            get_kiro_telemetry_info,
+            reset_kiro_machine_id,
+            // 进程管理命令
+            close_kiro_ide,
+            start_kiro_ide,
+            is_kiro_ide_running,
+            // Kiro IDE 安装登记命令
+            list_kiro_installations,
+            save_kiro_installation,
+            remove_kiro_installation,
+            // Kiro IDE 设置命令
+            get_kiro_settings,
+            set_kiro_proxy,
+            set_kiro_model,
+            // 应用设置命令
+            get_app_settings,
+            save_app_settings,
+            // 本地 HTTP API 命令
+            get_http_api_token,
+            regenerate_http_api_token,
+            set_http_server_enabled,
+            get_http_server_status,
+            restart_http_server,
+            // 账号绑定机器码命令
+            bind_machine_id_to_account,
+            unbind_machine_id_from_account,
+            get_bound_machine_id,
+            get_all_bound_machine_ids,
+            // 系统机器码命令
+            get_system_machine_guid,
+            backup_machine_guid,
+            restore_machine_guid,
+            reset_system_machine_guid,
+            get_machine_guid_backup,
+            set_custom_machine_guid,
+            clear_macos_override,
+            generate_machine_guid,
+            // Web OAuth 命令 (Cognito + CBOR)
+            web_oauth_initiate,
+            web_oauth_complete,
+            web_oauth_refresh,
+            web_oauth_login,
+            web_oauth_close_window,
+            // 浏览器检测
+            detect_installed_browsers,
+            // MCP 管理命令
+            get_mcp_config,
+            save_mcp_server,
+            delete_mcp_server,
+            toggle_mcp_server,
+            validate_mcp_config,
+            test_mcp_server,
+            get_effective_mcp_config,
+            preview_mcp_import,
+            import_mcp_servers,
+            export_mcp_servers,
+            get_mcp_server_tools,
+            refresh_mcp_server_tools,
+            save_mcp_tool_settings,
+            // MCP 配置方案命令
+            list_mcp_profiles,
+            save_mcp_profile,
+            capture_mcp_profile,
+            delete_mcp_profile,
+            apply_mcp_profile,
+            // MCP 密钥命令
+            list_mcp_secrets,
+            set_mcp_secret,
+            delete_mcp_secret,
+            // 项目文件夹命令
+            list_workspaces,
+            add_workspace,
+            remove_workspace,
+            // 超额费用估算命令
+            estimate_overage_costs,
+            // 日历导出命令
+            export_calendar_ics,
+            // Powers 管理命令
+            get_powers_registry,
+            get_installed_powers,
+            get_all_powers,
+            install_power,
+            uninstall_power,
+            // 代理检测命令
+            detect_system_proxy,
+            // SSO Token 导入命令
+            import_from_sso_token,
+            // 更新检查命令
+            check_update,
+            // Steering 管理命令
+            get_steering_files,
+            get_steering_file,
+            save_steering_file,
+            delete_steering_file,
+            create_steering_file,
+            // 自动注册命令
+            get_auto_register_config,
+            save_auto_register_config,
+            test_email_connection,
+            test_proxy_connection,
+            detect_chrome,
+            detect_python_env,
+            detect_all_python_versions,
+            launch_chrome_incognito,
+            check_roxy_service,
+            get_registration_progress,
+            start_auto_register,
+            stop_auto_register,
+            reset_auto_register_state,
+            get_registration_history,
+            add_registration_record,
+            clear_registration_history,
+            export_registration_history,
+            // 脚本管理命令
+            get_script_content,
+            save_script_content,
+            get_script_path_cmd,
+            reset_script_to_default,
+            open_script_folder
+        ])
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|_app_handle, event| {
+            // 应用退出时关闭 HTTP 服务器
+            if let tauri::RunEvent::Exit = event {
+                http_server::stop_http_server();
+            }
+        });
+}
@@ -0,0 +1,308 @@
+// 超额费用估算 - 基于账号 usage_data 中的订阅与配额信息
+
+use chrono::{DateTime, Local, Months, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use kiro_core::account::Account;
+use kiro_core::codewhisperer_client::{CodeWhispererUsageResponse, UsageBreakdown};
+use kiro_core::usage::parse_usage_data;
+
+/// 未返回 currency 时使用的默认币种
+const DEFAULT_CURRENCY: &str = "USD";
+
+/// 默认预警阈值：预计超额用量达到上限的 80% 时提醒
+pub const DEFAULT_WARN_RATIO: f64 = 0.8;
+
+/// 单个账号的超额费用估算
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountOverageEstimate {
+    pub account_id: String,
+    pub email: String,
+    pub currency: String,
+    pub overage_enabled: bool,
+    pub overage_rate: Option<f64>,
+    pub overage_cap: Option<f64>,
+    /// 本周期可用额度（基础额度 + 试用 + 奖励）
+    pub included_limit: f64,
+    pub current_usage: f64,
+    pub projected_usage: f64,
+    pub current_overage_units: f64,
+    pub projected_overage_units: f64,
+    pub current_cost: f64,
+    pub projected_cost: f64,
+    /// 达到上限时的最高费用
+    pub cap_cost: Option<f64>,
+    pub next_reset: Option<String>,
+    pub warning: Option<String>,
+}
+
+/// 按币种汇总的团队费用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyTotal {
+    pub currency: String,
+    pub account_count: usize,
+    pub current_cost: f64,
+    pub projected_cost: f64,
+    pub cap_cost: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverageCostReport {
+    pub generated_at: String,
+    pub accounts: Vec<AccountOverageEstimate>,
+    pub totals: Vec<CurrencyTotal>,
+    /// 没有可用 usage 数据的账号 ID
+    pub skipped: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// 是否开启了超额计费
+fn is_overage_enabled(usage: &CodeWhispererUsageResponse) -> bool {
+    if let Some(status) = usage.overage_configuration.as_ref().and_then(|c| c.overage_status.as_deref()) {
+        return status.eq_ignore_ascii_case("ENABLED");
+    }
+    usage.subscription_info.as_ref()
+        .and_then(|s| s.overage_capability.as_deref())
+        .map(|c| c.eq_ignore_ascii_case("OVERAGE_CAPABLE"))
+        .unwrap_or(false)
+}
+
+/// 计算本周期可用额度与已用量（基础 + 活跃试用 + 活跃奖励）
+fn included_limit_and_usage(breakdown: &UsageBreakdown) -> (f64, f64) {
+    let mut limit = breakdown.usage_limit_with_precision
+        .or(breakdown.usage_limit.map(|v| v as f64))
+        .unwrap_or(0.0);
+    let mut used = breakdown.current_usage_with_precision
+        .or(breakdown.current_usage.map(|v| v as f64))
+        .unwrap_or(0.0);
+
+    if let Some(trial) = &breakdown.free_trial_info {
+        if trial.free_trial_status.as_deref().map(|s| s.eq_ignore_ascii_case("ACTIVE")).unwrap_or(false) {
+            limit += trial.usage_limit_with_precision
+                .or(trial.usage_limit.map(|v| v as f64))
+                .unwrap_or(0.0);
+            used += trial.current_usage_with_precision
+                .or(trial.current_usage.map(|v| v as f64))
+                .unwrap_or(0.0);
+        }
+    }
+
+    for bonus in breakdown.bonuses.iter().flatten() {
+        if bonus.status.as_deref().map(|s| s.eq_ignore_ascii_case("ACTIVE")).unwrap_or(false) {
+            limit += bonus.usage_limit.unwrap_or(0.0);
+            used += bonus.current_usage.unwrap_or(0.0);
+        }
+    }
+
+    (limit, used)
+}
+
+/// 计算当前计费周期已经过的比例（0, 1]
+///
+/// 只知道下一次重置时间，周期起点按重置时间往前推一个月计算；
+/// 为避免周期刚开始时外推值过大，已过时间至少按 1 天计。
+fn period_elapsed_ratio(next_reset: Option<f64>, now: DateTime<Utc>) -> f64 {
+    let reset = match next_reset.and_then(|ts| Utc.timestamp_opt(ts as i64, 0).single()) {
+        Some(r) if r > now => r,
+        _ => return 1.0,
+    };
+    let start = match reset.checked_sub_months(Months::new(1)) {
+        Some(s) => s,
+        None => return 1.0,
+    };
+    let total = (reset - start).num_seconds() as f64;
+    let elapsed = ((now - start).num_seconds() as f64).max(86_400.0);
+    if total <= 0.0 {
+        return 1.0;
+    }
+    (elapsed / total).clamp(f64::EPSILON, 1.0)
+}
+
+/// 估算单个账号的超额费用
+pub fn estimate_account(account: &Account, warn_ratio: f64, now: DateTime<Utc>) -> Option<AccountOverageEstimate> {
+    let usage = parse_usage_data(account)?;
+    let breakdown = usage.usage_breakdown_list.as_ref()?.first()?.clone();
+
+    let overage_enabled = is_overage_enabled(&usage);
+    let currency = breakdown.currency.clone().unwrap_or_else(|| DEFAULT_CURRENCY.to_string());
+    let rate = breakdown.overage_rate;
+    let cap = breakdown.overage_cap_with_precision
+        .or(breakdown.overage_cap.map(|v| v as f64));
+    let next_reset_ts = breakdown.next_date_reset.or(usage.next_date_reset);
+
+    let (included_limit, current_usage) = included_limit_and_usage(&breakdown);
+    let projected_usage = current_usage / period_elapsed_ratio(next_reset_ts, now);
+
+    // 超额用量不会超过上限
+    let clamp_to_cap = |units: f64| match cap {
+        Some(c) => units.min(c),
+        None => units,
+    };
+    let current_overage_units = clamp_to_cap(
+        breakdown.current_overages_with_precision
+            .or(breakdown.current_overages.map(|v| v as f64))
+            .unwrap_or_else(|| (current_usage - included_limit).max(0.0)),
+    );
+    let projected_overage_units = clamp_to_cap((projected_usage - included_limit).max(0.0))
+        .max(current_overage_units);
+
+    // 账号返回的实际费用优先；关闭超额后仍可能有本周期此前产生的费用，只是不会再增长
+    let current_cost = breakdown.overage_charges.unwrap_or(match (overage_enabled, rate) {
+        (true, Some(r)) => current_overage_units * r,
+        _ => 0.0,
+    });
+    let (projected_cost, cap_cost) = match (overage_enabled, rate) {
+        (true, Some(r)) => ((projected_overage_units * r).max(current_cost), cap.map(|c| c * r)),
+        _ => (current_cost, None),
+    };
+
+    let warning = if overage_enabled {
+        match cap {
+            Some(c) if c > 0.0 && projected_overage_units >= c => {
+                Some(format!("{}: 预计本周期超额用量将达到上限 {}", account.email, c))
+            }
+            Some(c) if c > 0.0 && projected_overage_units >= c * warn_ratio => Some(format!(
+                "{}: 预计本周期超额用量 {:.1} 已接近上限 {} ({:.0}%)",
+                account.email,
+                projected_overage_units,
+                c,
+                projected_overage_units / c * 100.0
+            )),
+            _ => None,
+        }
+    } else {
+        None
+    };
+
+    let next_reset = next_reset_ts
+        .and_then(|ts| Local.timestamp_opt(ts as i64, 0).single())
+        .map(|dt| dt.format("%Y/%m/%d %H:%M:%S").to_string());
+
+    Some(AccountOverageEstimate {
+        account_id: account.id.clone(),
+        email: account.email.clone(),
+        currency,
+        overage_enabled,
+        overage_rate: rate,
+        overage_cap: cap,
+        included_limit,
+        current_usage,
+        projected_usage,
+        current_overage_units,
+        projected_overage_units,
+        current_cost,
+        projected_cost,
+        cap_cost,
+        next_reset,
+        warning,
+    })
+}
+
+/// 估算一组账号的超额费用，并按币种汇总
+pub fn estimate_accounts(accounts: &[Account], warn_ratio: f64) -> OverageCostReport {
+    let now = Utc::now();
+    let mut estimates = Vec::new();
+    let mut skipped = Vec::new();
+
+    for account in accounts {
+        match estimate_account(account, warn_ratio, now) {
+            Some(e) => estimates.push(e),
+            None => skipped.push(account.id.clone()),
+        }
+    }
+
+    let mut by_currency: BTreeMap<String, CurrencyTotal> = BTreeMap::new();
+    for e in &estimates {
+        let total = by_currency.entry(e.currency.clone()).or_insert_with(|| CurrencyTotal {
+            currency: e.currency.clone(),
+            account_count: 0,
+            current_cost: 0.0,
+            projected_cost: 0.0,
+            cap_cost: 0.0,
+        });
+        total.account_count += 1;
+        total.current_cost += e.current_cost;
+        total.projected_cost += e.projected_cost;
+        total.cap_cost += e.cap_cost.unwrap_or(0.0);
+    }
+
+    let warnings = estimates.iter().filter_map(|e| e.warning.clone()).collect();
+
+    OverageCostReport {
+        generated_at: Local::now().format("%Y/%m/%d %H:%M:%S").to_string(),
+        accounts: estimates,
+        totals: by_currency.into_values().collect(),
+        skipped,
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// 基础额度 1000、超额单价 0.04、上限 100 的账号；不带重置时间，预计用量即当前用量
+    fn account(current_usage: f64, overage_status: &str, overage_charges: Option<f64>) -> Account {
+        let mut account = Account::new("dev@example.com".to_string(), String::new());
+        account.usage_data = Some(json!({
+            "overageConfiguration": { "overageStatus": overage_status },
+            "usageBreakdownList": [{
+                "usageLimitWithPrecision": 1000.0,
+                "currentUsageWithPrecision": current_usage,
+                "overageRate": 0.04,
+                "overageCapWithPrecision": 100.0,
+                "overageCharges": overage_charges,
+                "currency": "USD",
+            }],
+        }));
+        account
+    }
+
+    fn estimate(account: &Account) -> AccountOverageEstimate {
+        estimate_account(account, DEFAULT_WARN_RATIO, Utc::now()).unwrap()
+    }
+
+    #[test]
+    fn no_warning_below_threshold() {
+        let e = estimate(&account(1050.0, "ENABLED", None));
+        assert_eq!(e.current_overage_units, 50.0);
+        assert!((e.current_cost - 2.0).abs() < 1e-9);
+        assert!(e.warning.is_none());
+    }
+
+    #[test]
+    fn warns_when_approaching_cap() {
+        let e = estimate(&account(1085.0, "ENABLED", None));
+        assert_eq!(e.projected_overage_units, 85.0);
+        assert!(e.warning.unwrap().contains("已接近上限"));
+    }
+
+    #[test]
+    fn overage_units_are_clamped_to_cap() {
+        let e = estimate(&account(1300.0, "ENABLED", None));
+        assert_eq!(e.current_overage_units, 100.0);
+        assert_eq!(e.cap_cost, Some(4.0));
+        assert!(e.warning.unwrap().contains("将达到上限"));
+    }
+
+    #[test]
+    fn disabled_overage_reports_actual_charges() {
+        let e = estimate(&account(1300.0, "DISABLED", Some(1.5)));
+        assert!(!e.overage_enabled);
+        assert_eq!(e.current_cost, 1.5);
+        assert_eq!(e.projected_cost, 1.5);
+        assert_eq!(e.cap_cost, None);
+        assert!(e.warning.is_none());
+    }
+
+    #[test]
+    fn disabled_overage_without_charges_costs_nothing() {
+        let e = estimate(&account(1300.0, "DISABLED", None));
+        assert_eq!(e.current_cost, 0.0);
+        assert_eq!(e.projected_cost, 0.0);
+    }
+}
@@ -1,8 +1,8 @@
 // 应用全局状态
 
-use std::sync::Mutex;
-use crate::auth::AuthState;
-use crate::account::AccountStore;
+use std::sync::{Arc, Mutex};
+use kiro_core::auth::AuthState;
+use kiro_core::account::AccountStore;
 
 #[derive(Clone)]
 pub struct PendingLogin {
@@ -29,7 +29,8 @@ pub static CURRENT_DEVICE_AUTH_URL: Mutex<Option<String>> = Mutex::new(None);
 pub static PENDING_DEVICE_AUTH: Mutex<Option<PendingDeviceAuth>> = Mutex::new(None);
 
 pub struct AppState {
-    pub store: Mutex<AccountStore>,
+    /// 与 HTTP 服务器共享的账号存储
+    pub store: Arc<Mutex<AccountStore>>,
     pub auth: AuthState,
     pub pending_login: Mutex<Option<PendingLogin>>,
 }
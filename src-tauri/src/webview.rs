@@ -0,0 +1,24 @@
+// 向 WebView 推送 Tauri 事件 - 领域事件总线（kiro_core::events）的 GUI 端
+
+use std::sync::OnceLock;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+/// 推送给 WebView 的领域事件名
+pub const DOMAIN_EVENT_NAME: &str = "domain-event";
+
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+/// 设置 AppHandle，之后的领域事件同时推送给 WebView
+pub fn set_app_handle(handle: AppHandle) {
+    if APP_HANDLE.set(handle).is_ok() {
+        kiro_core::events::set_listener(|record| emit_to_webview(DOMAIN_EVENT_NAME, record));
+    }
+}
+
+/// 直接向 WebView 推送一个 Tauri 事件（不进入事件总线）
+pub fn emit_to_webview<S: Serialize + Clone>(event: &str, payload: S) {
+    if let Some(handle) = APP_HANDLE.get() {
+        let _ = handle.emit(event, payload);
+    }
+}
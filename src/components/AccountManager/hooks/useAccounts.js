@@ -114,6 +114,8 @@ export function useAccounts() {
   useEffect(() => {
     loadAccounts()
     const unlistenLoginSuccess = listen('login-success', () => loadAccounts())
+    // 本地 API 的 /reload_accounts 触发
+    const unlistenAccountsReloaded = listen('accounts-reloaded', () => loadAccounts())
     const unlistenKiroLoginData = listen('kiro-login-data', async (event) => {
       try {
         const data = typeof event.payload === 'string' ? JSON.parse(event.payload) : event.payload
@@ -142,6 +144,7 @@ export function useAccounts() {
 
     return () => {
       unlistenLoginSuccess.then(fn => fn())
+      unlistenAccountsReloaded.then(fn => fn())
       unlistenKiroLoginData.then(fn => fn())
       clearInterval(interval)
     }
@@ -169,7 +169,15 @@ function AccountManager() {
         params.profileArn = account.profileArn || 'arn:aws:codewhisperer:us-east-1:699475941385:profile/EHGA3GRVQMUK'
       }
       
-      await invoke('switch_kiro_account', { params })
+      const result = await invoke('switch_kiro_account', { params })
+      // IDE 未在超时内正常退出（可能有未保存的修改），用户确认后才强制关闭
+      if (result?.awaitingForceClose) {
+        const force = await showConfirm(t('switch.forceCloseTitle'), t('switch.forceCloseMessage'), {
+          confirmText: t('switch.forceCloseBtn'),
+        })
+        if (!force) return
+        await invoke('switch_kiro_account', { params: { ...params, forceClose: true } })
+      }
       
       // 更新当前账号标识
       invoke('get_kiro_local_token').then(setLocalToken).catch(() => setLocalToken(null))
@@ -197,7 +205,7 @@ function AccountManager() {
     } finally {
       setSwitchingId(null)
     }
-  }, [switchDialog, setSwitchingId])
+  }, [switchDialog, setSwitchingId, showConfirm, t])
 
   return (
     <div className={`h-full flex flex-col ${colors.main}`}>
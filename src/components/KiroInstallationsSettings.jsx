@@ -0,0 +1,203 @@
+import { useState, useEffect } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { open } from '@tauri-apps/plugin-dialog'
+import { Layers, Plus, Pencil, Trash2, FolderOpen, Check, X } from 'lucide-react'
+import { useTheme } from '../contexts/ThemeContext'
+import { useDialog } from '../contexts/DialogContext'
+import { useI18n } from '../i18n.jsx'
+
+const EMPTY_FORM = { id: '', name: '', executablePath: '', dataDir: '', homeDir: '' }
+
+// Kiro IDE 安装登记：稳定版与预览版并存、便携数据目录，切换账号时可选择目标安装
+function KiroInstallationsSettings() {
+  const { theme, colors } = useTheme()
+  const { showConfirm, showError } = useDialog()
+  const { t } = useI18n()
+  const isDark = theme === 'dark'
+
+  const [installations, setInstallations] = useState([])
+  const [form, setForm] = useState(null) // 正在新增或编辑的安装
+  const [saving, setSaving] = useState(false)
+
+  const loadInstallations = () => {
+    invoke('list_kiro_installations').then(setInstallations).catch(() => {})
+  }
+
+  useEffect(() => {
+    loadInstallations()
+  }, [])
+
+  const handleEdit = (installation) => {
+    setForm({
+      id: installation.id,
+      name: installation.name,
+      executablePath: installation.executablePath || '',
+      dataDir: installation.dataDir || '',
+      homeDir: installation.homeDir || '',
+    })
+  }
+
+  const handleBrowse = async (field, directory) => {
+    try {
+      const selected = await open({ multiple: false, directory })
+      if (selected) setForm(prev => ({ ...prev, [field]: selected }))
+    } catch (err) {
+      console.error('Failed to open dialog:', err)
+    }
+  }
+
+  const handleSave = async () => {
+    if (!form.name.trim()) {
+      await showError(t('settings.saveFailed'), t('settings.installationNameRequired'))
+      return
+    }
+    setSaving(true)
+    try {
+      // 空字符串按未设置处理
+      await invoke('save_kiro_installation', {
+        installation: {
+          id: form.id,
+          name: form.name.trim(),
+          executablePath: form.executablePath.trim() || null,
+          dataDir: form.dataDir.trim() || null,
+          homeDir: form.homeDir.trim() || null,
+        },
+      })
+      setForm(null)
+      loadInstallations()
+    } catch (err) {
+      await showError(t('settings.saveFailed'), t('settings.saveFailed') + ': ' + err)
+    } finally {
+      setSaving(false)
+    }
+  }
+
+  const handleRemove = async (installation) => {
+    const confirmed = await showConfirm(t('settings.removeInstallation'), t('settings.confirmRemoveInstallation', { name: installation.name }))
+    if (!confirmed) return
+    try {
+      await invoke('remove_kiro_installation', { id: installation.id })
+      loadInstallations()
+    } catch (err) {
+      await showError(t('common.error'), err.toString())
+    }
+  }
+
+  const pathFields = [
+    { field: 'executablePath', label: t('settings.installationExecutable'), directory: false },
+    { field: 'dataDir', label: t('settings.installationDataDir'), directory: true },
+    { field: 'homeDir', label: t('settings.installationHomeDir'), directory: true },
+  ]
+
+  return (
+    <section className={`card-glow ${colors.card} rounded-2xl p-6 shadow-sm border ${colors.cardBorder} mb-6 animate-slide-in-left delay-500`}>
+      <div className="flex items-center justify-between mb-5">
+        <div>
+          <div className="flex items-center gap-2 mb-1">
+            <Layers size={18} className="text-purple-500" />
+            <h2 className={`text-lg font-semibold ${colors.text}`}>{t('settings.kiroInstallations')}</h2>
+          </div>
+          <p className={`text-sm ${colors.textMuted}`}>{t('settings.kiroInstallationsDesc')}</p>
+        </div>
+        {!form && (
+          <button
+            onClick={() => setForm({ ...EMPTY_FORM })}
+            className="btn-icon px-4 py-2 rounded-xl flex items-center gap-2 text-sm font-medium bg-blue-500 text-white hover:bg-blue-600 transition-all"
+          >
+            <Plus size={16} />
+            {t('settings.addInstallation')}
+          </button>
+        )}
+      </div>
+
+      {installations.length === 0 && !form && (
+        <p className={`text-sm text-center py-4 ${colors.textMuted}`}>{t('settings.noInstallations')}</p>
+      )}
+
+      <div className="space-y-2">
+        {installations.map(installation => (
+          <div key={installation.id} className={`flex items-center justify-between ${isDark ? 'bg-white/5' : 'bg-gray-50'} rounded-xl p-4`}>
+            <div className="min-w-0">
+              <p className={`text-sm font-medium ${colors.text}`}>{installation.name}</p>
+              {[installation.executablePath, installation.dataDir, installation.homeDir].filter(Boolean).map(path => (
+                <p key={path} className={`text-xs font-mono ${colors.textMuted} truncate`}>{path}</p>
+              ))}
+            </div>
+            <div className="flex items-center gap-1 flex-shrink-0">
+              <button
+                onClick={() => handleEdit(installation)}
+                className={`btn-icon p-2 rounded-lg ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'} transition-colors`}
+                title={t('common.edit')}
+              >
+                <Pencil size={16} className={colors.textMuted} />
+              </button>
+              <button
+                onClick={() => handleRemove(installation)}
+                className="btn-icon p-2 rounded-lg text-red-500 hover:bg-red-500/10 transition-colors"
+                title={t('common.delete')}
+              >
+                <Trash2 size={16} />
+              </button>
+            </div>
+          </div>
+        ))}
+      </div>
+
+      {/* 新增/编辑表单 */}
+      {form && (
+        <div className={`${isDark ? 'bg-white/5' : 'bg-gray-50'} rounded-xl p-4 mt-3 space-y-3`}>
+          <div>
+            <label className={`block text-sm ${colors.textMuted} mb-2`}>{t('settings.installationName')}</label>
+            <input
+              type="text"
+              value={form.name}
+              onChange={(e) => setForm({ ...form, name: e.target.value })}
+              placeholder="Kiro Preview"
+              className={`w-full px-4 py-2 border rounded-xl ${colors.text} ${colors.input} ${colors.inputFocus} focus:ring-2 transition-all`}
+            />
+          </div>
+          {pathFields.map(({ field, label, directory }) => (
+            <div key={field}>
+              <label className={`block text-sm ${colors.textMuted} mb-2`}>{label}</label>
+              <div className="flex gap-2">
+                <input
+                  type="text"
+                  value={form[field]}
+                  onChange={(e) => setForm({ ...form, [field]: e.target.value })}
+                  className={`flex-1 px-4 py-2 border rounded-xl font-mono text-sm ${colors.text} ${colors.input} ${colors.inputFocus} focus:ring-2 transition-all`}
+                />
+                <button
+                  onClick={() => handleBrowse(field, directory)}
+                  className={`btn-icon p-2 rounded-xl ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-200'} transition-colors`}
+                  title={t('settings.browse')}
+                >
+                  <FolderOpen size={18} className={colors.textMuted} />
+                </button>
+              </div>
+            </div>
+          ))}
+          <p className={`text-xs ${colors.textMuted}`}>{t('settings.installationPathsTip')}</p>
+          <div className="flex justify-end gap-2">
+            <button
+              onClick={() => setForm(null)}
+              className={`btn-icon px-4 py-2 rounded-xl flex items-center gap-2 text-sm border ${colors.cardBorder} ${colors.text} ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'} transition-all`}
+            >
+              <X size={16} />
+              {t('common.cancel')}
+            </button>
+            <button
+              onClick={handleSave}
+              disabled={saving}
+              className="btn-icon px-4 py-2 rounded-xl flex items-center gap-2 text-sm font-medium bg-blue-500 text-white hover:bg-blue-600 disabled:opacity-50 transition-all"
+            >
+              <Check size={16} />
+              {t('common.save')}
+            </button>
+          </div>
+        </div>
+      )}
+    </section>
+  )
+}
+
+export default KiroInstallationsSettings
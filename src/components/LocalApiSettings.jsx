@@ -0,0 +1,214 @@
+import { useState, useEffect } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import { Server, Copy, Check, Eye, EyeOff, RefreshCw } from 'lucide-react'
+import { useTheme } from '../contexts/ThemeContext'
+import { useDialog } from '../contexts/DialogContext'
+import { useI18n } from '../i18n.jsx'
+
+// 本地 HTTP API 设置：开关、运行状态、监听地址与 Bearer 令牌（外部脚本通过 Authorization 头访问）
+function LocalApiSettings() {
+  const { theme, colors } = useTheme()
+  const { showConfirm, showError } = useDialog()
+  const { t } = useI18n()
+  const isDark = theme === 'dark'
+
+  const [enabled, setEnabled] = useState(true)
+  const [token, setToken] = useState('')
+  const [showToken, setShowToken] = useState(false)
+  const [copied, setCopied] = useState(false)
+  const [busy, setBusy] = useState(false)
+  const [status, setStatus] = useState(null)
+  const [bindAddress, setBindAddress] = useState('127.0.0.1')
+  const [port, setPort] = useState('23847')
+  const [unixSocket, setUnixSocket] = useState(false)
+
+  useEffect(() => {
+    invoke('get_app_settings')
+      .then(settings => {
+        setEnabled(settings?.httpServerEnabled ?? true)
+        setBindAddress(settings?.httpBindAddress || '127.0.0.1')
+        setPort(String(settings?.httpPort ?? 23847))
+        setUnixSocket(settings?.httpUnixSocket ?? false)
+      })
+      .catch(() => {})
+    invoke('get_http_api_token').then(setToken).catch(() => {})
+    invoke('get_http_server_status').then(setStatus).catch(() => {})
+    // 服务器启动、失败或停止时后端推送最新状态
+    const unlisten = listen('http-server-status', (event) => setStatus(event.payload))
+    return () => { unlisten.then(fn => fn()) }
+  }, [])
+
+  const handleToggle = async (checked) => {
+    setBusy(true)
+    try {
+      await invoke('set_http_server_enabled', { enabled: checked })
+      setEnabled(checked)
+    } catch (err) {
+      await showError(t('settings.saveFailed'), t('settings.saveFailed') + ': ' + err)
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  // 保存监听设置并重启服务器
+  const handleApplyListen = async () => {
+    const portNumber = parseInt(port)
+    if (!portNumber || portNumber < 1 || portNumber > 65535) {
+      await showError(t('settings.saveFailed'), t('settings.invalidPort'))
+      return
+    }
+    setBusy(true)
+    try {
+      await invoke('save_app_settings', {
+        settings: { httpBindAddress: bindAddress.trim() || '127.0.0.1', httpPort: portNumber, httpUnixSocket: unixSocket },
+      })
+      if (enabled) setStatus(await invoke('restart_http_server'))
+    } catch (err) {
+      await showError(t('settings.saveFailed'), t('settings.saveFailed') + ': ' + err)
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  const stateStyles = {
+    bound: 'bg-green-500/20 text-green-500',
+    failed: 'bg-red-500/20 text-red-500',
+  }
+
+  const handleRegenerate = async () => {
+    const confirmed = await showConfirm(t('settings.regenerateToken'), t('settings.confirmRegenerateToken'))
+    if (!confirmed) return
+    try {
+      setToken(await invoke('regenerate_http_api_token'))
+      setShowToken(true)
+    } catch (err) {
+      await showError(t('common.error'), err.toString())
+    }
+  }
+
+  const handleCopy = () => {
+    navigator.clipboard.writeText(token)
+    setCopied(true)
+    setTimeout(() => setCopied(false), 1500)
+  }
+
+  return (
+    <section className={`card-glow ${colors.card} rounded-2xl p-6 shadow-sm border ${colors.cardBorder} mb-6 animate-slide-in-left delay-400`}>
+      <div className="flex items-center gap-2 mb-1">
+        <Server size={18} className="text-blue-500" />
+        <h2 className={`text-lg font-semibold ${colors.text}`}>{t('settings.localApi')}</h2>
+      </div>
+      <p className={`text-sm ${colors.textMuted} mb-5`}>{t('settings.localApiDesc')}</p>
+
+      <label className={`flex items-start gap-3 cursor-pointer ${isDark ? 'bg-white/5 hover:bg-white/10' : 'bg-gray-50 hover:bg-gray-100'} rounded-xl p-4 transition-all hover:scale-[1.01] mb-3`}>
+        <input
+          type="checkbox"
+          checked={enabled}
+          disabled={busy}
+          onChange={(e) => handleToggle(e.target.checked)}
+          className="mt-0.5 w-4 h-4 rounded-lg border-gray-300 text-blue-500 focus:ring-blue-500"
+        />
+        <div>
+          <span className={`text-sm font-medium ${colors.text}`}>{t('settings.localApiEnabled')}</span>
+          <p className={`text-xs ${colors.textMuted} mt-0.5`}>{t('settings.localApiEnabledDesc')}</p>
+        </div>
+      </label>
+
+      {/* 运行状态 */}
+      {status && (
+        <div className={`${isDark ? 'bg-white/5' : 'bg-gray-50'} rounded-xl p-4 mb-4`}>
+          <div className="flex items-center gap-2">
+            <span className={`text-sm ${colors.text}`}>{t('settings.localApiStatus')}</span>
+            <span className={`text-xs px-2 py-0.5 rounded-full ${stateStyles[status.state] || (isDark ? 'bg-white/10 text-gray-400' : 'bg-gray-200 text-gray-500')}`}>
+              {t(`settings.localApiState.${status.state}`)}
+            </span>
+          </div>
+          {status.baseUrl && (
+            <p className={`text-xs ${colors.textMuted} mt-2`}>
+              {t('settings.listeningOn')}: <code className="font-mono">{status.baseUrl}</code>
+              {status.port && status.port !== status.requestedPort && ` (${t('settings.portInUse', { port: status.requestedPort })})`}
+            </p>
+          )}
+          {status.unixSocket && (
+            <p className={`text-xs ${colors.textMuted} mt-1`}>{t('settings.unixSocket')}: <code className="font-mono">{status.unixSocket}</code></p>
+          )}
+          {status.error && <p className="text-xs text-red-500 mt-1 break-all">{status.error}</p>}
+          <p className={`text-xs ${colors.textMuted} mt-1`}>{t('settings.discoveryFile')}: <code className="font-mono break-all">{status.discoveryFile}</code></p>
+        </div>
+      )}
+
+      {/* 监听设置 */}
+      <div className="flex gap-3 mb-3">
+        <div className="flex-1">
+          <label className={`block text-sm ${colors.textMuted} mb-2`}>{t('settings.bindAddress')}</label>
+          <input
+            type="text"
+            value={bindAddress}
+            onChange={(e) => setBindAddress(e.target.value)}
+            placeholder="127.0.0.1"
+            className={`w-full px-4 py-2 border rounded-xl ${colors.text} ${colors.input} ${colors.inputFocus} focus:ring-2 transition-all`}
+          />
+        </div>
+        <div className="w-32">
+          <label className={`block text-sm ${colors.textMuted} mb-2`}>{t('settings.port')}</label>
+          <input
+            type="number"
+            min="1"
+            max="65535"
+            value={port}
+            onChange={(e) => setPort(e.target.value)}
+            className={`w-full px-4 py-2 border rounded-xl ${colors.text} ${colors.input} ${colors.inputFocus} focus:ring-2 transition-all`}
+          />
+        </div>
+        <button
+          onClick={handleApplyListen}
+          disabled={busy}
+          className="btn-icon self-end px-5 py-2 rounded-xl flex items-center gap-2 font-medium shadow-sm bg-blue-500 text-white hover:bg-blue-600 disabled:opacity-50 transition-all"
+        >
+          {busy ? <RefreshCw size={16} className="animate-spin" /> : <Check size={16} />}
+          {t('settings.apply')}
+        </button>
+      </div>
+      <label className={`flex items-center gap-2 text-sm ${colors.text} mb-5`}>
+        <input
+          type="checkbox"
+          checked={unixSocket}
+          onChange={(e) => setUnixSocket(e.target.checked)}
+          className="w-4 h-4 rounded-lg border-gray-300 text-blue-500 focus:ring-blue-500"
+        />
+        {t('settings.unixSocketEnabled')}
+      </label>
+
+      <label className={`block text-sm ${colors.textMuted} mb-2`}>{t('settings.apiToken')}</label>
+      <div className="flex items-center gap-2">
+        <code className={`flex-1 text-sm ${isDark ? 'bg-white/10' : 'bg-gray-100'} px-3 py-2 rounded-lg font-mono ${colors.text} truncate`}>
+          {token ? (showToken ? token : '•'.repeat(32)) : '-'}
+        </code>
+        <button
+          onClick={() => setShowToken(!showToken)}
+          className={`btn-icon p-2 rounded-lg ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'} transition-colors`}
+        >
+          {showToken ? <EyeOff size={16} className={colors.textMuted} /> : <Eye size={16} className={colors.textMuted} />}
+        </button>
+        <button
+          onClick={handleCopy}
+          disabled={!token}
+          className={`btn-icon p-2 rounded-lg ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'} transition-colors`}
+        >
+          {copied ? <Check size={16} className="text-green-500" /> : <Copy size={16} className={colors.textMuted} />}
+        </button>
+        <button
+          onClick={handleRegenerate}
+          className={`btn-icon p-2 rounded-lg ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'} transition-colors`}
+          title={t('settings.regenerateToken')}
+        >
+          <RefreshCw size={16} className={colors.textMuted} />
+        </button>
+      </div>
+      <p className={`text-xs ${colors.textMuted} mt-3`}>{t('settings.apiTokenTip')}</p>
+    </section>
+  )
+}
+
+export default LocalApiSettings
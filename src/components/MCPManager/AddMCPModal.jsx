@@ -1,11 +1,11 @@
 import { useState } from 'react'
-import { X, Plus, Minus } from 'lucide-react'
+import { X, Plus, Minus, KeyRound } from 'lucide-react'
 import { invoke } from '@tauri-apps/api/core'
 import { useTheme } from '../../contexts/ThemeContext'
 import { useI18n } from '../../i18n'
 import { MCP_TEMPLATES } from './MCPTemplates'
 
-function AddMCPModal({ onClose, onSuccess }) {
+function AddMCPModal({ workspace, onClose, onSuccess }) {
   const { theme, colors } = useTheme()
   const { t } = useI18n()
   const isDark = theme === 'dark'
@@ -41,6 +41,19 @@ function AddMCPModal({ onClose, onSuccess }) {
     setEnvList(envList.map((item, i) => i === index ? { ...item, [field]: value } : item))
   }
 
+  // 把环境变量的值存入密钥库，配置中只保留 ${secret:NAME} 引用
+  const storeEnvAsSecret = async (index) => {
+    const { key, value } = envList[index]
+    if (!key.trim() || !value || value.startsWith('${secret:')) return
+    const secretName = `${name.trim() || 'mcp'}.${key.trim()}`.replace(/[^A-Za-z0-9_.-]/g, '_')
+    try {
+      await invoke('set_mcp_secret', { name: secretName, value })
+      updateEnv(index, 'value', '${secret:' + secretName + '}')
+    } catch (e) {
+      setError(String(e))
+    }
+  }
+
   // 保存
   const handleSave = async () => {
     if (!name.trim()) {
@@ -67,7 +80,7 @@ function AddMCPModal({ onClose, onSuccess }) {
         autoApprove: []
       }
 
-      await invoke('save_mcp_server', { name: name.trim(), config })
+      await invoke('save_mcp_server', { name: name.trim(), config, workspace })
       onSuccess()
     } catch (e) {
       setError(String(e))
@@ -176,6 +189,14 @@ function AddMCPModal({ onClose, onSuccess }) {
                   placeholder="value"
                   className={`flex-1 px-3 py-2 rounded-lg border ${colors.cardBorder} ${isDark ? 'bg-white/5' : 'bg-gray-50'} ${colors.text} text-sm focus:outline-none focus:ring-2 focus:ring-purple-500/30`}
                 />
+                <button
+                  onClick={() => storeEnvAsSecret(i)}
+                  disabled={!env.key.trim() || !env.value || env.value.startsWith('${secret:')}
+                  className={`p-2 rounded-lg ${colors.textMuted} ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'} disabled:opacity-30`}
+                  title={t('mcpManager.storeAsSecret')}
+                >
+                  <KeyRound size={16} />
+                </button>
                 <button onClick={() => removeEnv(i)} className="p-2 text-red-500 hover:bg-red-500/10 rounded-lg">
                   <Minus size={16} />
                 </button>
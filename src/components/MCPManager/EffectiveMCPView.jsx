@@ -0,0 +1,75 @@
+import { useState, useEffect } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { AlertCircle } from 'lucide-react'
+import { useTheme } from '../../contexts/ThemeContext'
+import { useI18n } from '../../i18n'
+
+// 来源标签颜色
+const SCOPE_STYLES = {
+  power: { dark: 'bg-amber-500/20 text-amber-300', light: 'bg-amber-50 text-amber-600' },
+  user: { dark: 'bg-blue-500/20 text-blue-300', light: 'bg-blue-50 text-blue-600' },
+  workspace: { dark: 'bg-green-500/20 text-green-300', light: 'bg-green-50 text-green-600' },
+}
+
+// 合并后的生效配置：每个服务器来自哪个文件、覆盖了哪些条目
+function EffectiveMCPView({ workspace }) {
+  const { theme, colors } = useTheme()
+  const { t } = useI18n()
+  const isDark = theme === 'dark'
+  const [effective, setEffective] = useState(null)
+  const [error, setError] = useState('')
+
+  useEffect(() => {
+    setError('')
+    invoke('get_effective_mcp_config', { workspace })
+      .then(setEffective)
+      .catch(e => setError(String(e)))
+  }, [workspace])
+
+  const scopeBadge = (scope) => (
+    <span className={`text-xs px-2 py-0.5 rounded ${SCOPE_STYLES[scope][isDark ? 'dark' : 'light']}`}>
+      {t(`mcpManager.scope.${scope}`)}
+    </span>
+  )
+
+  if (error) return <p className="text-sm text-red-500">{error}</p>
+  if (!effective) return <div className={`text-center py-12 ${colors.textMuted}`}>{t('common.loading')}</div>
+
+  return (
+    <div className="space-y-4">
+      {effective.errors.map((e, i) => (
+        <div key={i} className="flex items-start gap-2 text-sm text-red-500">
+          <AlertCircle size={14} className="mt-0.5 shrink-0" />
+          <span><code>{e.source.path}</code>: {e.message}</span>
+        </div>
+      ))}
+
+      {effective.servers.length === 0 ? (
+        <p className={`text-center py-12 ${colors.textMuted}`}>{t('mcpManager.noServers')}</p>
+      ) : (
+        <div className={`${colors.card} border ${colors.cardBorder} rounded-xl divide-y ${isDark ? 'divide-white/10' : 'divide-gray-100'}`}>
+          {effective.servers.map(server => (
+            <div key={server.name} className="px-4 py-3">
+              <div className="flex items-center gap-2">
+                <span className={`font-medium ${colors.text} ${server.disabled ? 'opacity-50' : ''}`}>{server.name}</span>
+                {scopeBadge(server.source.scope)}
+                {server.disabled && <span className={`text-xs ${colors.textMuted}`}>{t('mcpManager.disabled')}</span>}
+              </div>
+              <code className={`block text-xs mt-1 truncate ${colors.textMuted}`} title={server.source.path}>
+                {server.source.path}
+              </code>
+              {server.overrides.length > 0 && (
+                <div className={`flex items-center gap-1.5 mt-1.5 text-xs ${colors.textMuted}`}>
+                  {t('mcpManager.overrides')}:
+                  {server.overrides.map((o, i) => <span key={i} title={o.path}>{scopeBadge(o.scope)}</span>)}
+                </div>
+              )}
+            </div>
+          ))}
+        </div>
+      )}
+    </div>
+  )
+}
+
+export default EffectiveMCPView
@@ -0,0 +1,280 @@
+import { useState, useEffect } from 'react'
+import { X, FileUp, Copy, Check, Download, AlertTriangle } from 'lucide-react'
+import { invoke } from '@tauri-apps/api/core'
+import { open, save } from '@tauri-apps/plugin-dialog'
+import { useTheme } from '../../contexts/ThemeContext'
+import { useI18n } from '../../i18n'
+
+// 其他客户端的配置格式（名称为产品名，不翻译）
+const FORMATS = [
+  { value: 'kiro', label: 'Kiro' },
+  { value: 'claude-desktop', label: 'Claude Desktop' },
+  { value: 'claude-code', label: 'Claude Code' },
+  { value: 'cursor', label: 'Cursor' },
+  { value: 'windsurf', label: 'Windsurf' },
+  { value: 'vscode', label: 'VS Code' },
+]
+
+function ImportExportMCPModal({ workspace, onClose, onImported }) {
+  const { theme, colors } = useTheme()
+  const { t } = useI18n()
+  const isDark = theme === 'dark'
+
+  const [tab, setTab] = useState('import')
+  const [format, setFormat] = useState('') // 导入时空字符串为自动识别
+  const [content, setContent] = useState('')
+  const [file, setFile] = useState(null)
+  const [preview, setPreview] = useState(null)
+  const [selected, setSelected] = useState({})
+  const [exported, setExported] = useState(null)
+  const [exportFormat, setExportFormat] = useState('cursor')
+  const [copied, setCopied] = useState(false)
+  const [busy, setBusy] = useState(false)
+  const [error, setError] = useState('')
+
+  const selectClass = `px-3 py-2 rounded-lg border text-sm ${colors.input} ${colors.text}`
+  const tabClass = (active) => `px-3 py-1.5 rounded-lg text-sm ${active ? 'bg-purple-500 text-white' : `${colors.text} ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'}`}`
+
+  // 导出内容随格式刷新
+  useEffect(() => {
+    if (tab !== 'export') return
+    setError('')
+    invoke('export_mcp_servers', { format: exportFormat, workspace })
+      .then(setExported)
+      .catch(e => setError(String(e)))
+  }, [tab, exportFormat, workspace])
+
+  const handleChooseFile = async () => {
+    const path = await open({ filters: [{ name: 'JSON', extensions: ['json', 'jsonc'] }] })
+    if (path) {
+      setFile(path)
+      setContent('')
+    }
+  }
+
+  const handlePreview = async () => {
+    setError('')
+    try {
+      const result = await invoke('preview_mcp_import', {
+        content: file ? null : content,
+        file,
+        format: format || null,
+        workspace,
+      })
+      setPreview(result)
+      // 默认选中会产生变化的条目
+      setSelected(Object.fromEntries(result.entries.map(e => [e.name, e.conflict !== 'identical'])))
+    } catch (e) {
+      setError(String(e))
+    }
+  }
+
+  const handleImport = async () => {
+    const servers = Object.fromEntries(
+      preview.entries.filter(e => selected[e.name]).map(e => [e.name, e.config])
+    )
+    setBusy(true)
+    setError('')
+    try {
+      await invoke('import_mcp_servers', { servers, workspace })
+      onImported()
+    } catch (e) {
+      setError(String(e))
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  const handleCopy = () => {
+    navigator.clipboard.writeText(exported.content)
+    setCopied(true)
+    setTimeout(() => setCopied(false), 1500)
+  }
+
+  const handleSave = async () => {
+    try {
+      const { writeTextFile } = await import('@tauri-apps/plugin-fs')
+      const filePath = await save({
+        defaultPath: exportFormat === 'claude-code' ? '.mcp.json' : 'mcp.json',
+        filters: [{ name: 'JSON', extensions: ['json'] }],
+      })
+      if (!filePath) return
+      await writeTextFile(filePath, exported.content)
+    } catch (e) {
+      setError(String(e))
+    }
+  }
+
+  const selectedCount = preview ? preview.entries.filter(e => selected[e.name]).length : 0
+
+  const skippedList = (skipped) => skipped.length > 0 && (
+    <div>
+      <p className={`text-sm font-medium mb-1 ${colors.text}`}>{t('mcpManager.skipped')}</p>
+      <ul className={`text-xs space-y-0.5 ${colors.textMuted}`}>
+        {skipped.map(s => <li key={s.name}><code>{s.name}</code>: {s.reason}</li>)}
+      </ul>
+    </div>
+  )
+
+  const warningList = (warnings) => warnings.map((w, i) => (
+    <p key={i} className="flex items-start gap-1 text-xs text-yellow-500">
+      <AlertTriangle size={12} className="mt-0.5 shrink-0" />{w}
+    </p>
+  ))
+
+  return (
+    <div className="fixed inset-0 bg-black/50 flex items-center justify-center z-50" onClick={onClose}>
+      <div
+        className={`${colors.card} rounded-2xl shadow-2xl w-[640px] max-h-[85vh] flex flex-col`}
+        onClick={e => e.stopPropagation()}
+      >
+        {/* 标题 */}
+        <div className={`flex items-center justify-between px-6 py-4 border-b ${colors.cardBorder}`}>
+          <div className="flex items-center gap-2">
+            <button onClick={() => setTab('import')} className={tabClass(tab === 'import')}>{t('mcpManager.import')}</button>
+            <button onClick={() => setTab('export')} className={tabClass(tab === 'export')}>{t('mcpManager.export')}</button>
+          </div>
+          <button onClick={onClose} className={`p-1 rounded-lg ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'}`}>
+            <X size={20} className={colors.textMuted} />
+          </button>
+        </div>
+
+        {/* 内容 */}
+        <div className="flex-1 overflow-auto p-6 space-y-4">
+          {tab === 'import' ? (
+            <>
+              <div className="flex items-center gap-2">
+                <select value={format} onChange={e => setFormat(e.target.value)} className={selectClass}>
+                  <option value="">{t('mcpManager.formatAuto')}</option>
+                  {FORMATS.map(f => <option key={f.value} value={f.value}>{f.label}</option>)}
+                </select>
+                <button
+                  onClick={handleChooseFile}
+                  className={`px-3 py-2 rounded-lg border text-sm flex items-center gap-1.5 ${colors.cardBorder} ${colors.text} ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'}`}
+                >
+                  <FileUp size={14} />{t('mcpManager.chooseFile')}
+                </button>
+                {file && (
+                  <code className={`flex-1 text-xs truncate ${colors.textMuted}`} title={file}>
+                    {file}
+                  </code>
+                )}
+              </div>
+
+              {!file && (
+                <textarea
+                  value={content}
+                  onChange={e => setContent(e.target.value)}
+                  placeholder={t('mcpManager.pasteConfig')}
+                  rows={8}
+                  className={`w-full px-3 py-2 rounded-lg border text-xs font-mono ${colors.input} ${colors.text}`}
+                />
+              )}
+
+              <button
+                onClick={handlePreview}
+                disabled={!file && !content.trim()}
+                className={`px-4 py-2 rounded-lg border text-sm ${colors.cardBorder} ${colors.text} ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'} disabled:opacity-50`}
+              >
+                {t('mcpManager.preview')}
+              </button>
+
+              {preview && (
+                <>
+                  <p className={`text-xs ${colors.textMuted}`}>
+                    {t('mcpManager.detectedFormat')}: {FORMATS.find(f => f.value === preview.format)?.label}
+                  </p>
+                  <ul className="space-y-2">
+                    {preview.entries.map(entry => (
+                      <li key={entry.name} className={`p-3 rounded-lg border ${colors.cardBorder}`}>
+                        <label className="flex items-center gap-2 cursor-pointer">
+                          <input
+                            type="checkbox"
+                            checked={!!selected[entry.name]}
+                            onChange={e => setSelected(prev => ({ ...prev, [entry.name]: e.target.checked }))}
+                          />
+                          <span className={`text-sm font-medium ${colors.text}`}>{entry.name}</span>
+                          {entry.conflict === 'different' && (
+                            <span className="text-xs px-2 py-0.5 rounded bg-yellow-500/20 text-yellow-500">{t('mcpManager.conflictDifferent')}</span>
+                          )}
+                          {entry.conflict === 'identical' && (
+                            <span className={`text-xs px-2 py-0.5 rounded ${isDark ? 'bg-white/10' : 'bg-gray-100'} ${colors.textMuted}`}>{t('mcpManager.conflictIdentical')}</span>
+                          )}
+                        </label>
+                        <code className={`block text-xs mt-1 truncate ${colors.textMuted}`}>
+                          {entry.config.command ? [entry.config.command, ...(entry.config.args || [])].join(' ') : entry.config.url}
+                        </code>
+                        {entry.conflict === 'different' && (
+                          <details className="mt-1">
+                            <summary className={`text-xs cursor-pointer ${colors.textMuted}`}>{t('mcpManager.showExisting')}</summary>
+                            <pre className={`text-xs p-2 mt-1 rounded overflow-auto ${isDark ? 'bg-black/30' : 'bg-gray-50'} ${colors.textMuted}`}>
+                              {JSON.stringify(entry.existing, null, 2)}
+                            </pre>
+                          </details>
+                        )}
+                        {warningList(entry.warnings)}
+                      </li>
+                    ))}
+                  </ul>
+                  {skippedList(preview.skipped)}
+                </>
+              )}
+            </>
+          ) : (
+            <>
+              <select value={exportFormat} onChange={e => setExportFormat(e.target.value)} className={selectClass}>
+                {FORMATS.map(f => <option key={f.value} value={f.value}>{f.label}</option>)}
+              </select>
+              {exported && (
+                <>
+                  <textarea
+                    value={exported.content}
+                    readOnly
+                    rows={12}
+                    className={`w-full px-3 py-2 rounded-lg border text-xs font-mono ${colors.input} ${colors.text}`}
+                  />
+                  {warningList(exported.warnings)}
+                  {skippedList(exported.skipped)}
+                </>
+              )}
+            </>
+          )}
+
+          {error && <p className="text-sm text-red-500">{error}</p>}
+        </div>
+
+        {/* 底部 */}
+        <div className={`flex justify-end gap-2 px-6 py-4 border-t ${colors.cardBorder}`}>
+          {tab === 'import' ? (
+            <button
+              onClick={handleImport}
+              disabled={busy || selectedCount === 0}
+              className="px-4 py-2 bg-purple-500 text-white rounded-lg text-sm hover:bg-purple-600 disabled:opacity-50"
+            >
+              {t('mcpManager.importSelected', { count: selectedCount })}
+            </button>
+          ) : (
+            <>
+              <button
+                onClick={handleCopy}
+                disabled={!exported}
+                className={`px-4 py-2 rounded-lg border text-sm flex items-center gap-1.5 ${colors.cardBorder} ${colors.text} ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'} disabled:opacity-50`}
+              >
+                {copied ? <Check size={14} className="text-green-500" /> : <Copy size={14} />}{t('common.copy')}
+              </button>
+              <button
+                onClick={handleSave}
+                disabled={!exported}
+                className="px-4 py-2 bg-purple-500 text-white rounded-lg text-sm flex items-center gap-1.5 hover:bg-purple-600 disabled:opacity-50"
+              >
+                <Download size={14} />{t('mcpManager.saveToFile')}
+              </button>
+            </>
+          )}
+        </div>
+      </div>
+    </div>
+  )
+}
+
+export default ImportExportMCPModal
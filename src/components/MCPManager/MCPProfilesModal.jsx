@@ -0,0 +1,135 @@
+import { useState, useEffect } from 'react'
+import { X, Play, Trash2, Save } from 'lucide-react'
+import { invoke } from '@tauri-apps/api/core'
+import { useTheme } from '../../contexts/ThemeContext'
+import { useDialog } from '../../contexts/DialogContext'
+import { useI18n } from '../../i18n'
+
+// MCP 配置方案：保存当前用户级服务器组合，一键切换
+function MCPProfilesModal({ onClose, onApplied }) {
+  const { theme, colors } = useTheme()
+  const { t } = useI18n()
+  const { showConfirm, showSuccess } = useDialog()
+  const isDark = theme === 'dark'
+
+  const [profiles, setProfiles] = useState([])
+  const [name, setName] = useState('')
+  const [error, setError] = useState('')
+
+  const loadProfiles = () => invoke('list_mcp_profiles').then(setProfiles).catch(e => setError(String(e)))
+
+  useEffect(() => { loadProfiles() }, [])
+
+  // 以当前配置新建方案
+  const handleCapture = async () => {
+    if (!name.trim()) return
+    setError('')
+    try {
+      await invoke('capture_mcp_profile', { name: name.trim() })
+      setName('')
+      loadProfiles()
+    } catch (e) {
+      setError(String(e))
+    }
+  }
+
+  const handleApply = async (profile) => {
+    const confirmed = await showConfirm(t('mcpManager.applyProfile'), t('mcpManager.confirmApplyProfile', { name: profile.name }))
+    if (!confirmed) return
+    setError('')
+    try {
+      const backup = await invoke('apply_mcp_profile', { id: profile.id })
+      onApplied()
+      showSuccess(t('mcpManager.profileApplied'), backup ? t('mcpManager.backupSaved', { path: backup }) : '')
+    } catch (e) {
+      setError(String(e))
+    }
+  }
+
+  const handleDelete = async (profile) => {
+    const confirmed = await showConfirm(t('mcpManager.deleteProfile'), `${t('mcpManager.confirmDelete')} ${profile.name}？`)
+    if (!confirmed) return
+    try {
+      await invoke('delete_mcp_profile', { id: profile.id })
+      loadProfiles()
+    } catch (e) {
+      setError(String(e))
+    }
+  }
+
+  return (
+    <div className="fixed inset-0 bg-black/50 flex items-center justify-center z-50" onClick={onClose}>
+      <div
+        className={`${colors.card} rounded-2xl shadow-2xl w-[520px] max-h-[80vh] flex flex-col`}
+        onClick={e => e.stopPropagation()}
+      >
+        {/* 标题 */}
+        <div className={`flex items-center justify-between px-6 py-4 border-b ${colors.cardBorder}`}>
+          <h2 className={`text-lg font-semibold ${colors.text}`}>{t('mcpManager.profiles')}</h2>
+          <button onClick={onClose} className={`p-1 rounded-lg ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'}`}>
+            <X size={20} className={colors.textMuted} />
+          </button>
+        </div>
+
+        {/* 内容 */}
+        <div className="flex-1 overflow-auto p-6 space-y-4">
+          <div className="flex gap-2">
+            <input
+              type="text"
+              value={name}
+              onChange={e => setName(e.target.value)}
+              onKeyDown={e => e.key === 'Enter' && handleCapture()}
+              placeholder={t('mcpManager.profileName')}
+              className={`flex-1 px-3 py-2 rounded-lg border text-sm ${colors.input} ${colors.text}`}
+            />
+            <button
+              onClick={handleCapture}
+              disabled={!name.trim()}
+              className="px-3 py-2 bg-purple-500 text-white rounded-lg text-sm flex items-center gap-1.5 hover:bg-purple-600 disabled:opacity-50"
+            >
+              <Save size={14} />{t('mcpManager.saveCurrent')}
+            </button>
+          </div>
+
+          {error && <p className="text-sm text-red-500">{error}</p>}
+
+          {profiles.length === 0 ? (
+            <p className={`text-sm text-center py-6 ${colors.textMuted}`}>{t('mcpManager.noProfiles')}</p>
+          ) : (
+            <ul className="space-y-2">
+              {profiles.map(profile => {
+                const names = Object.keys(profile.servers || {})
+                return (
+                  <li key={profile.id} className={`flex items-center gap-3 p-3 rounded-lg border ${colors.cardBorder}`}>
+                    <div className="flex-1 min-w-0">
+                      <p className={`text-sm font-medium ${colors.text}`}>{profile.name}</p>
+                      <p className={`text-xs truncate ${colors.textMuted}`} title={names.join(', ')}>
+                        {names.length} · {names.join(', ')}
+                      </p>
+                    </div>
+                    <button
+                      onClick={() => handleApply(profile)}
+                      className={`p-1.5 rounded-lg text-green-500 ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'}`}
+                      title={t('mcpManager.applyProfile')}
+                    >
+                      <Play size={16} />
+                    </button>
+                    <button
+                      onClick={() => handleDelete(profile)}
+                      className={`p-1.5 rounded-lg text-red-500 ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'}`}
+                      title={t('mcpManager.deleteProfile')}
+                    >
+                      <Trash2 size={16} />
+                    </button>
+                  </li>
+                )
+              })}
+            </ul>
+          )}
+        </div>
+      </div>
+    </div>
+  )
+}
+
+export default MCPProfilesModal
@@ -0,0 +1,159 @@
+import { useState, useEffect } from 'react'
+import { X, Trash2, RefreshCw, Copy, Check } from 'lucide-react'
+import { invoke } from '@tauri-apps/api/core'
+import { useTheme } from '../../contexts/ThemeContext'
+import { useDialog } from '../../contexts/DialogContext'
+import { useI18n } from '../../i18n'
+
+// MCP 密钥库：配置中以 ${secret:NAME} 引用，写入 mcp.json 时才解析为实际值
+function MCPSecretsModal({ onClose, onChanged }) {
+  const { theme, colors } = useTheme()
+  const { t } = useI18n()
+  const { showConfirm, showSuccess } = useDialog()
+  const isDark = theme === 'dark'
+
+  const [secrets, setSecrets] = useState([])
+  const [name, setName] = useState('')
+  const [value, setValue] = useState('')
+  const [copiedName, setCopiedName] = useState(null)
+  const [error, setError] = useState('')
+
+  const inputClass = `px-3 py-2 rounded-lg border text-sm ${colors.input} ${colors.text}`
+
+  const loadSecrets = () => invoke('list_mcp_secrets').then(setSecrets).catch(e => setError(String(e)))
+
+  useEffect(() => { loadSecrets() }, [])
+
+  // 新增或轮换（同名即轮换）
+  const handleSave = async () => {
+    if (!name.trim() || !value) return
+    const rotating = secrets.some(s => s.name === name.trim())
+    if (rotating) {
+      const confirmed = await showConfirm(t('mcpManager.rotateSecret'), t('mcpManager.confirmRotateSecret', { name: name.trim() }))
+      if (!confirmed) return
+    }
+    setError('')
+    try {
+      const updated = await invoke('set_mcp_secret', { name: name.trim(), value })
+      setName('')
+      setValue('')
+      loadSecrets()
+      if (updated.length > 0) {
+        onChanged()
+        showSuccess(t('mcpManager.secretRotated'), updated.join('\n'))
+      }
+    } catch (e) {
+      setError(String(e))
+    }
+  }
+
+  const handleDelete = async (secret) => {
+    const confirmed = await showConfirm(t('mcpManager.deleteSecret'), `${t('mcpManager.confirmDelete')} ${secret.name}？`)
+    if (!confirmed) return
+    setError('')
+    try {
+      await invoke('delete_mcp_secret', { name: secret.name })
+      loadSecrets()
+    } catch (e) {
+      setError(String(e))
+    }
+  }
+
+  const handleCopy = (secretName) => {
+    navigator.clipboard.writeText('${secret:' + secretName + '}')
+    setCopiedName(secretName)
+    setTimeout(() => setCopiedName(null), 1500)
+  }
+
+  return (
+    <div className="fixed inset-0 bg-black/50 flex items-center justify-center z-50" onClick={onClose}>
+      <div
+        className={`${colors.card} rounded-2xl shadow-2xl w-[560px] max-h-[80vh] flex flex-col`}
+        onClick={e => e.stopPropagation()}
+      >
+        {/* 标题 */}
+        <div className={`flex items-center justify-between px-6 py-4 border-b ${colors.cardBorder}`}>
+          <h2 className={`text-lg font-semibold ${colors.text}`}>{t('mcpManager.secrets')}</h2>
+          <button onClick={onClose} className={`p-1 rounded-lg ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'}`}>
+            <X size={20} className={colors.textMuted} />
+          </button>
+        </div>
+
+        {/* 内容 */}
+        <div className="flex-1 overflow-auto p-6 space-y-4">
+          <p className={`text-xs ${colors.textMuted}`}>{t('mcpManager.secretsHint')}</p>
+
+          <div className="flex gap-2">
+            <input
+              type="text"
+              value={name}
+              onChange={e => setName(e.target.value)}
+              placeholder={t('mcpManager.secretName')}
+              className={`w-40 ${inputClass}`}
+            />
+            <input
+              type="password"
+              value={value}
+              onChange={e => setValue(e.target.value)}
+              onKeyDown={e => e.key === 'Enter' && handleSave()}
+              placeholder={t('mcpManager.secretValue')}
+              className={`flex-1 ${inputClass}`}
+            />
+            <button
+              onClick={handleSave}
+              disabled={!name.trim() || !value}
+              className="px-3 py-2 bg-purple-500 text-white rounded-lg text-sm hover:bg-purple-600 disabled:opacity-50"
+            >
+              {t('common.save')}
+            </button>
+          </div>
+
+          {error && <p className="text-sm text-red-500 whitespace-pre-wrap">{error}</p>}
+
+          {secrets.length === 0 ? (
+            <p className={`text-sm text-center py-6 ${colors.textMuted}`}>{t('mcpManager.noSecrets')}</p>
+          ) : (
+            <ul className="space-y-2">
+              {secrets.map(secret => (
+                <li key={secret.name} className={`flex items-center gap-3 p-3 rounded-lg border ${colors.cardBorder}`}>
+                  <div className="flex-1 min-w-0">
+                    <code className={`text-sm ${colors.text}`}>{'${secret:' + secret.name + '}'}</code>
+                    <p className={`text-xs truncate ${colors.textMuted}`} title={secret.usedBy.join('\n')}>
+                      {secret.usedBy.length > 0
+                        ? t('mcpManager.secretUsedBy', { count: secret.usedBy.length })
+                        : t('mcpManager.secretUnused')}
+                      {' · '}{new Date(secret.updatedAt).toLocaleString()}
+                    </p>
+                  </div>
+                  <button
+                    onClick={() => handleCopy(secret.name)}
+                    className={`p-1.5 rounded-lg ${colors.textMuted} ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'}`}
+                    title={t('common.copy')}
+                  >
+                    {copiedName === secret.name ? <Check size={16} className="text-green-500" /> : <Copy size={16} />}
+                  </button>
+                  <button
+                    onClick={() => { setName(secret.name); setValue('') }}
+                    className={`p-1.5 rounded-lg ${colors.textMuted} ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'}`}
+                    title={t('mcpManager.rotateSecret')}
+                  >
+                    <RefreshCw size={16} />
+                  </button>
+                  <button
+                    onClick={() => handleDelete(secret)}
+                    className={`p-1.5 rounded-lg text-red-500 ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'}`}
+                    title={t('mcpManager.deleteSecret')}
+                  >
+                    <Trash2 size={16} />
+                  </button>
+                </li>
+              ))}
+            </ul>
+          )}
+        </div>
+      </div>
+    </div>
+  )
+}
+
+export default MCPSecretsModal
@@ -1,8 +1,8 @@
-import { Server, Edit2, Trash2, Terminal } from 'lucide-react'
+import { Server, Edit2, Trash2, Terminal, Activity, Wrench } from 'lucide-react'
 import { useTheme } from '../../contexts/ThemeContext'
 import { useI18n } from '../../i18n'
 
-function MCPServerCard({ name, config, onToggle, onEdit, onDelete }) {
+function MCPServerCard({ name, config, onToggle, onTest, onTools, onEdit, onDelete }) {
   const { theme, colors } = useTheme()
   const { t } = useI18n()
   const isDark = theme === 'dark'
@@ -74,6 +74,22 @@ function MCPServerCard({ name, config, onToggle, onEdit, onDelete }) {
             }`} />
           </button>
           
+          <button
+            onClick={onTest}
+            className={`p-2 rounded-lg ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'} transition-colors`}
+            title={t('mcpManager.test')}
+          >
+            <Activity size={16} className={colors.textMuted} />
+          </button>
+          
+          <button
+            onClick={onTools}
+            className={`p-2 rounded-lg ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'} transition-colors`}
+            title={t('mcpManager.toolSettings')}
+          >
+            <Wrench size={16} className={colors.textMuted} />
+          </button>
+          
           <button
             onClick={onEdit}
             className={`p-2 rounded-lg ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'} transition-colors`}
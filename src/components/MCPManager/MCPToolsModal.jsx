@@ -0,0 +1,211 @@
+import { useState, useEffect, useCallback } from 'react'
+import { X, RefreshCw, AlertTriangle, Trash2 } from 'lucide-react'
+import { invoke } from '@tauri-apps/api/core'
+import { useTheme } from '../../contexts/ThemeContext'
+import { useI18n } from '../../i18n'
+
+// autoApprove 中表示全部工具的通配符
+const WILDCARD = '*'
+
+// 逐个工具的自动批准与禁用设置，工具名来自服务器的 tools/list
+function MCPToolsModal({ name, workspace, onClose, onSaved }) {
+  const { theme, colors } = useTheme()
+  const { t } = useI18n()
+  const isDark = theme === 'dark'
+
+  const [info, setInfo] = useState(null)
+  const [autoApprove, setAutoApprove] = useState([])
+  const [disabledTools, setDisabledTools] = useState([])
+  const [refreshing, setRefreshing] = useState(false)
+  const [saving, setSaving] = useState(false)
+  const [error, setError] = useState('')
+
+  const applyInfo = (result) => {
+    setInfo(result)
+    setAutoApprove(result.autoApprove)
+    setDisabledTools(result.disabledTools)
+  }
+
+  const handleRefresh = useCallback(async () => {
+    setRefreshing(true)
+    setError('')
+    try {
+      const result = await invoke('refresh_mcp_server_tools', { name, workspace })
+      // 保留尚未保存的勾选
+      setInfo(result)
+    } catch (e) {
+      setError(String(e))
+    } finally {
+      setRefreshing(false)
+    }
+  }, [name, workspace])
+
+  // 打开时读取缓存，从未获取过则立即获取
+  useEffect(() => {
+    invoke('get_mcp_server_tools', { name, workspace })
+      .then(result => {
+        applyInfo(result)
+        if (!result.fetchedAt) handleRefresh()
+      })
+      .catch(e => setError(String(e)))
+  }, [name, workspace, handleRefresh])
+
+  const toggle = (list, setList, tool, checked) => {
+    setList(checked ? [...list, tool] : list.filter(x => x !== tool))
+  }
+
+  const handleSave = async () => {
+    setSaving(true)
+    setError('')
+    try {
+      applyInfo(await invoke('save_mcp_tool_settings', { name, autoApprove, disabledTools, workspace }))
+      onSaved()
+      onClose()
+    } catch (e) {
+      setError(String(e))
+    } finally {
+      setSaving(false)
+    }
+  }
+
+  const toolNames = new Set((info?.tools || []).map(tool => tool.name))
+  const approveAll = autoApprove.includes(WILDCARD)
+  // 只有获取过工具列表才能判断哪些条目已失效
+  const staleOf = (list) => info?.fetchedAt ? list.filter(x => x !== WILDCARD && !toolNames.has(x)) : []
+  const stale = [
+    ...staleOf(autoApprove).map(tool => ({ tool, list: autoApprove, setList: setAutoApprove, label: t('mcpManager.autoApprove') })),
+    ...staleOf(disabledTools).map(tool => ({ tool, list: disabledTools, setList: setDisabledTools, label: t('mcpManager.disabledTool') })),
+  ]
+
+  return (
+    <div className="fixed inset-0 bg-black/50 flex items-center justify-center z-50" onClick={onClose}>
+      <div
+        className={`${colors.card} rounded-2xl shadow-2xl w-[620px] max-h-[85vh] flex flex-col`}
+        onClick={e => e.stopPropagation()}
+      >
+        {/* 标题 */}
+        <div className={`flex items-center justify-between px-6 py-4 border-b ${colors.cardBorder}`}>
+          <h2 className={`text-lg font-semibold ${colors.text}`}>{t('mcpManager.toolSettings')}: {name}</h2>
+          <div className="flex items-center gap-1">
+            <button
+              onClick={handleRefresh}
+              disabled={refreshing}
+              className={`p-1 rounded-lg ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'}`}
+              title={t('mcpManager.refreshTools')}
+            >
+              <RefreshCw size={18} className={`${colors.textMuted} ${refreshing ? 'animate-spin' : ''}`} />
+            </button>
+            <button onClick={onClose} className={`p-1 rounded-lg ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'}`}>
+              <X size={20} className={colors.textMuted} />
+            </button>
+          </div>
+        </div>
+
+        {/* 内容 */}
+        <div className="flex-1 overflow-auto p-6 space-y-4">
+          {info?.fetchedAt && (
+            <p className={`text-xs ${colors.textMuted}`}>
+              {t('mcpManager.toolsFetchedAt', { time: new Date(info.fetchedAt).toLocaleString() })}
+            </p>
+          )}
+          {info?.configChanged && (
+            <p className="flex items-center gap-1.5 text-xs text-yellow-500">
+              <AlertTriangle size={12} />{t('mcpManager.toolsConfigChanged')}
+            </p>
+          )}
+          {error && <p className="text-sm text-red-500 break-all">{error}</p>}
+
+          <label className={`flex items-center gap-2 text-sm ${colors.text}`}>
+            <input
+              type="checkbox"
+              checked={approveAll}
+              onChange={e => toggle(autoApprove, setAutoApprove, WILDCARD, e.target.checked)}
+            />
+            {t('mcpManager.autoApproveAll')}
+          </label>
+
+          {info && info.tools.length === 0 && !refreshing ? (
+            <p className={`text-sm text-center py-6 ${colors.textMuted}`}>{t('mcpManager.noTools')}</p>
+          ) : (
+            <table className="w-full text-sm">
+              <thead>
+                <tr className={colors.textMuted}>
+                  <th className="text-left font-medium pb-2">{t('mcpManager.toolName')}</th>
+                  <th className="font-medium pb-2 w-24">{t('mcpManager.autoApprove')}</th>
+                  <th className="font-medium pb-2 w-24">{t('mcpManager.disabledTool')}</th>
+                </tr>
+              </thead>
+              <tbody>
+                {(info?.tools || []).map(tool => (
+                  <tr key={tool.name} className={`border-t ${colors.cardBorder}`}>
+                    <td className="py-2 pr-2">
+                      <code className={colors.text}>{tool.name}</code>
+                      {tool.description && <p className={`text-xs line-clamp-2 ${colors.textMuted}`}>{tool.description}</p>}
+                    </td>
+                    <td className="text-center">
+                      <input
+                        type="checkbox"
+                        checked={approveAll || autoApprove.includes(tool.name)}
+                        disabled={approveAll}
+                        onChange={e => toggle(autoApprove, setAutoApprove, tool.name, e.target.checked)}
+                      />
+                    </td>
+                    <td className="text-center">
+                      <input
+                        type="checkbox"
+                        checked={disabledTools.includes(tool.name)}
+                        onChange={e => toggle(disabledTools, setDisabledTools, tool.name, e.target.checked)}
+                      />
+                    </td>
+                  </tr>
+                ))}
+              </tbody>
+            </table>
+          )}
+
+          {stale.length > 0 && (
+            <div>
+              <p className="flex items-center gap-1.5 text-sm font-medium text-yellow-500 mb-2">
+                <AlertTriangle size={14} />{t('mcpManager.staleTools')}
+              </p>
+              <ul className="space-y-1">
+                {stale.map(({ tool, list, setList, label }) => (
+                  <li key={`${label}-${tool}`} className="flex items-center gap-2 text-sm">
+                    <code className={colors.text}>{tool}</code>
+                    <span className={`text-xs ${colors.textMuted}`}>{label}</span>
+                    <button
+                      onClick={() => toggle(list, setList, tool, false)}
+                      className="p-1 text-red-500 hover:bg-red-500/10 rounded"
+                      title={t('common.delete')}
+                    >
+                      <Trash2 size={14} />
+                    </button>
+                  </li>
+                ))}
+              </ul>
+            </div>
+          )}
+        </div>
+
+        {/* 底部 */}
+        <div className={`flex justify-end gap-2 px-6 py-4 border-t ${colors.cardBorder}`}>
+          <button
+            onClick={onClose}
+            className={`px-4 py-2 rounded-lg border text-sm ${colors.cardBorder} ${colors.text} ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'}`}
+          >
+            {t('common.cancel')}
+          </button>
+          <button
+            onClick={handleSave}
+            disabled={saving || !info}
+            className="px-4 py-2 bg-purple-500 text-white rounded-lg text-sm hover:bg-purple-600 disabled:opacity-50"
+          >
+            {t('common.save')}
+          </button>
+        </div>
+      </div>
+    </div>
+  )
+}
+
+export default MCPToolsModal
@@ -0,0 +1,106 @@
+import { useState, useEffect } from 'react'
+import { X, CheckCircle, XCircle, Loader2 } from 'lucide-react'
+import { invoke } from '@tauri-apps/api/core'
+import { useTheme } from '../../contexts/ThemeContext'
+import { useI18n } from '../../i18n'
+
+function TestMCPModal({ name, workspace, onClose }) {
+  const { theme, colors } = useTheme()
+  const { t } = useI18n()
+  const isDark = theme === 'dark'
+
+  const [report, setReport] = useState(null)
+  const [error, setError] = useState('')
+
+  // 打开即开始测试
+  useEffect(() => {
+    invoke('test_mcp_server', { name, workspace })
+      .then(setReport)
+      .catch(e => setError(String(e)))
+  }, [name, workspace])
+
+  const serverInfo = report?.serverInfo
+    ? [report.serverInfo.name, report.serverInfo.version].filter(Boolean).join(' ')
+    : ''
+
+  return (
+    <div className="fixed inset-0 bg-black/50 flex items-center justify-center z-50" onClick={onClose}>
+      <div
+        className={`${colors.card} rounded-2xl shadow-2xl w-[560px] max-h-[80vh] flex flex-col`}
+        onClick={e => e.stopPropagation()}
+      >
+        {/* 标题 */}
+        <div className={`flex items-center justify-between px-6 py-4 border-b ${colors.cardBorder}`}>
+          <h2 className={`text-lg font-semibold ${colors.text}`}>{t('mcpManager.test')}: {name}</h2>
+          <button onClick={onClose} className={`p-1 rounded-lg ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'}`}>
+            <X size={20} className={colors.textMuted} />
+          </button>
+        </div>
+
+        {/* 内容 */}
+        <div className="flex-1 overflow-auto p-6 space-y-4">
+          {!report && !error && (
+            <div className={`flex items-center gap-2 ${colors.textMuted}`}>
+              <Loader2 size={16} className="animate-spin" />{t('mcpManager.testing')}
+            </div>
+          )}
+
+          {error && <p className="text-sm text-red-500">{error}</p>}
+
+          {report && (
+            <>
+              <div className="flex items-center gap-2">
+                {report.success
+                  ? <CheckCircle size={18} className="text-green-500" />
+                  : <XCircle size={18} className="text-red-500" />}
+                <span className={`font-medium ${colors.text}`}>
+                  {report.success ? t('mcpManager.testOk') : t('mcpManager.testFailed')}
+                </span>
+                <span className={`text-sm ${colors.textMuted}`}>
+                  {report.transport} · {report.latencyMs} ms
+                </span>
+              </div>
+
+              {report.error && <p className="text-sm text-red-500 break-all">{report.error}</p>}
+
+              {serverInfo && (
+                <p className={`text-sm ${colors.text}`}>
+                  <span className={colors.textMuted}>{t('mcpManager.serverInfo')}: </span>
+                  {serverInfo}
+                  {report.protocolVersion && <span className={colors.textMuted}> ({report.protocolVersion})</span>}
+                </p>
+              )}
+
+              {report.success && (
+                <div>
+                  <p className={`text-sm font-medium mb-2 ${colors.text}`}>
+                    {t('mcpManager.tools')} ({report.tools.length})
+                  </p>
+                  <ul className="space-y-1">
+                    {report.tools.map(tool => (
+                      <li key={tool.name} className="text-sm">
+                        <code className={colors.text}>{tool.name}</code>
+                        {tool.description && <span className={`${colors.textMuted} ml-2`}>{tool.description}</span>}
+                      </li>
+                    ))}
+                  </ul>
+                </div>
+              )}
+
+              {report.stderr && (
+                <div>
+                  <p className={`text-sm font-medium mb-2 ${colors.text}`}>stderr</p>
+                  <pre className={`text-xs p-3 rounded-lg overflow-auto max-h-48 whitespace-pre-wrap ${isDark ? 'bg-black/30' : 'bg-gray-50'} ${colors.textMuted}`}>
+                    {report.stderr}
+                  </pre>
+                </div>
+              )}
+            </>
+          )}
+        </div>
+      </div>
+    </div>
+  )
+}
+
+export default TestMCPModal
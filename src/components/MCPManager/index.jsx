@@ -1,42 +1,91 @@
 import { useState, useEffect, useCallback } from 'react'
 import { invoke } from '@tauri-apps/api/core'
+import { open } from '@tauri-apps/plugin-dialog'
 import { useTheme } from '../../contexts/ThemeContext'
 import { useDialog } from '../../contexts/DialogContext'
 import { useI18n } from '../../i18n'
-import { Server, Plus, Sparkles } from 'lucide-react'
+import { Server, Plus, Sparkles, ShieldCheck, AlertCircle, AlertTriangle, X, FolderPlus, Layers, Bookmark, ArrowLeftRight, KeyRound } from 'lucide-react'
 import MCPServerCard from './MCPServerCard'
 import AddMCPModal from './AddMCPModal'
 import EditMCPModal from './EditMCPModal'
+import TestMCPModal from './TestMCPModal'
+import MCPToolsModal from './MCPToolsModal'
+import EffectiveMCPView from './EffectiveMCPView'
+import MCPProfilesModal from './MCPProfilesModal'
+import ImportExportMCPModal from './ImportExportMCPModal'
+import MCPSecretsModal from './MCPSecretsModal'
 
 function MCPManager() {
-  const { colors } = useTheme()
+  const { theme, colors } = useTheme()
+  const isDark = theme === 'dark'
   const { showConfirm } = useDialog()
   const { t } = useI18n()
   const [servers, setServers] = useState({})
   const [loading, setLoading] = useState(true)
   const [showAddModal, setShowAddModal] = useState(false)
   const [editingServer, setEditingServer] = useState(null) // { name, config }
+  const [testingServer, setTestingServer] = useState(null) // 正在测试的服务器名
+  const [toolsServer, setToolsServer] = useState(null) // 正在设置工具的服务器名
+  const [diagnostics, setDiagnostics] = useState(null) // 校验结果，null 表示未显示
+  const [workspaces, setWorkspaces] = useState([]) // 登记的项目文件夹
+  const [workspace, setWorkspace] = useState('') // 当前编辑的配置，空字符串为用户级
+  const [showEffective, setShowEffective] = useState(false)
+  const [showProfiles, setShowProfiles] = useState(false)
+  const [showImportExport, setShowImportExport] = useState(false)
+  const [showSecrets, setShowSecrets] = useState(false)
+  const scope = workspace || null
+
+  // 校验配置文件
+  const handleValidate = useCallback(async () => {
+    try {
+      const report = await invoke('validate_mcp_config', { workspace: scope })
+      setDiagnostics(report.diagnostics || [])
+    } catch (e) {
+      console.error('校验 MCP 配置失败:', e)
+    }
+  }, [scope])
 
   // 加载配置
   const loadConfig = useCallback(async () => {
     try {
-      const config = await invoke('get_mcp_config')
+      const config = await invoke('get_mcp_config', { workspace: scope })
       setServers(config.mcpServers || {})
     } catch (e) {
       console.error('加载 MCP 配置失败:', e)
+      setServers({})
+      // 加载失败时直接展示具体问题
+      handleValidate()
     } finally {
       setLoading(false)
     }
-  }, [])
+  }, [scope, handleValidate])
 
   useEffect(() => {
+    setDiagnostics(null)
     loadConfig()
   }, [loadConfig])
 
+  useEffect(() => {
+    invoke('list_workspaces').then(setWorkspaces).catch(() => {})
+  }, [])
+
+  // 登记项目文件夹并切换过去
+  const handleAddWorkspace = async () => {
+    const selected = await open({ directory: true })
+    if (!selected) return
+    try {
+      const path = await invoke('add_workspace', { path: selected })
+      setWorkspaces(await invoke('list_workspaces'))
+      setWorkspace(path)
+    } catch (e) {
+      console.error('登记项目文件夹失败:', e)
+    }
+  }
+
   // 切换启用/禁用
   const handleToggle = async (name, disabled) => {
     try {
-      await invoke('toggle_mcp_server', { name, disabled })
+      await invoke('toggle_mcp_server', { name, disabled, workspace: scope })
       setServers(prev => ({
         ...prev,
         [name]: { ...prev[name], disabled }
@@ -51,7 +100,7 @@ function MCPManager() {
     const confirmed = await showConfirm(t('mcpManager.deleteServer'), `${t('mcpManager.confirmDelete')} ${name}？`)
     if (confirmed) {
       try {
-        await invoke('delete_mcp_server', { name })
+        await invoke('delete_mcp_server', { name, workspace: scope })
         setServers(prev => {
           const next = { ...prev }
           delete next[name]
@@ -79,19 +128,103 @@ function MCPManager() {
               <p className={`text-sm ${colors.textMuted}`}>{t('mcpManager.subtitle')}</p>
             </div>
           </div>
-          <button
-            onClick={() => setShowAddModal(true)}
-            className="px-4 py-2 bg-gradient-to-r from-purple-500 to-pink-600 text-white rounded-xl text-sm font-medium hover:from-purple-600 hover:to-pink-700 flex items-center gap-1.5 shadow-lg shadow-purple-500/25"
-          >
-            <Plus size={16} />{t('mcpManager.addServer')}
-          </button>
+          <div className="flex items-center gap-2">
+            <select
+              value={workspace}
+              onChange={e => setWorkspace(e.target.value)}
+              className={`px-3 py-2 rounded-xl text-sm border max-w-[240px] ${colors.input} ${colors.text}`}
+              title={workspace || t('mcpManager.userScope')}
+            >
+              <option value="">{t('mcpManager.userScope')}</option>
+              {workspaces.map(w => <option key={w} value={w}>{w}</option>)}
+            </select>
+            <button
+              onClick={handleAddWorkspace}
+              className={`p-2 rounded-xl border ${colors.cardBorder} ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'} transition-colors`}
+              title={t('mcpManager.addWorkspace')}
+            >
+              <FolderPlus size={16} className={colors.textMuted} />
+            </button>
+            {!workspace && (
+              <button
+                onClick={() => setShowProfiles(true)}
+                className={`px-4 py-2 rounded-xl text-sm font-medium flex items-center gap-1.5 border ${colors.cardBorder} ${colors.text} ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'} transition-colors`}
+              >
+                <Bookmark size={16} />{t('mcpManager.profiles')}
+              </button>
+            )}
+            <button
+              onClick={() => setShowEffective(v => !v)}
+              className={`px-4 py-2 rounded-xl text-sm font-medium flex items-center gap-1.5 border ${colors.cardBorder} ${colors.text} ${showEffective ? (isDark ? 'bg-white/10' : 'bg-gray-100') : ''} ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'} transition-colors`}
+            >
+              <Layers size={16} />{t('mcpManager.effective')}
+            </button>
+            <button
+              onClick={() => setShowSecrets(true)}
+              className={`p-2 rounded-xl border ${colors.cardBorder} ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'} transition-colors`}
+              title={t('mcpManager.secrets')}
+            >
+              <KeyRound size={16} className={colors.textMuted} />
+            </button>
+            <button
+              onClick={() => setShowImportExport(true)}
+              className={`p-2 rounded-xl border ${colors.cardBorder} ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'} transition-colors`}
+              title={t('mcpManager.importExport')}
+            >
+              <ArrowLeftRight size={16} className={colors.textMuted} />
+            </button>
+            <button
+              onClick={handleValidate}
+              className={`px-4 py-2 rounded-xl text-sm font-medium flex items-center gap-1.5 border ${colors.cardBorder} ${colors.text} ${isDark ? 'hover:bg-white/10' : 'hover:bg-gray-100'} transition-colors`}
+            >
+              <ShieldCheck size={16} />{t('mcpManager.validate')}
+            </button>
+            <button
+              onClick={() => setShowAddModal(true)}
+              className="px-4 py-2 bg-gradient-to-r from-purple-500 to-pink-600 text-white rounded-xl text-sm font-medium hover:from-purple-600 hover:to-pink-700 flex items-center gap-1.5 shadow-lg shadow-purple-500/25"
+            >
+              <Plus size={16} />{t('mcpManager.addServer')}
+            </button>
+          </div>
         </div>
       </div>
 
+      {/* 校验结果 */}
+      {diagnostics && (
+        <div className={`mx-6 mt-4 p-4 rounded-xl border ${colors.card} ${colors.cardBorder}`}>
+          <div className="flex items-center justify-between mb-2">
+            <span className={`text-sm font-medium ${colors.text}`}>{t('mcpManager.validationTitle')}</span>
+            <button onClick={() => setDiagnostics(null)} className={colors.textMuted}>
+              <X size={16} />
+            </button>
+          </div>
+          {diagnostics.length === 0 ? (
+            <p className="text-sm text-green-500">{t('mcpManager.validationOk')}</p>
+          ) : (
+            <ul className="space-y-1.5">
+              {diagnostics.map((d, i) => (
+                <li key={i} className="flex items-start gap-2 text-sm">
+                  {d.severity === 'error'
+                    ? <AlertCircle size={14} className="text-red-500 mt-0.5 shrink-0" />
+                    : <AlertTriangle size={14} className="text-yellow-500 mt-0.5 shrink-0" />}
+                  <span className={colors.text}>
+                    <code className={colors.textMuted}>{d.path || '/'}</code>
+                    {d.line != null && <span className={`${colors.textMuted} ml-1`}>({t('mcpManager.line', { line: d.line, column: d.column })})</span>}
+                    <span className="ml-2">{d.message}</span>
+                  </span>
+                </li>
+              ))}
+            </ul>
+          )}
+        </div>
+      )}
+
 
       {/* 列表 */}
       <div className="flex-1 overflow-auto p-6">
-        {loading ? (
+        {showEffective ? (
+          <EffectiveMCPView workspace={scope} />
+        ) : loading ? (
           <div className={`text-center py-12 ${colors.textMuted}`}>{t('common.loading')}</div>
         ) : serverList.length === 0 ? (
           <div className="text-center py-12">
@@ -107,6 +240,8 @@ function MCPManager() {
                 name={name}
                 config={config}
                 onToggle={(disabled) => handleToggle(name, disabled)}
+                onTest={() => setTestingServer(name)}
+                onTools={() => setToolsServer(name)}
                 onEdit={() => setEditingServer({ name, config })}
                 onDelete={() => handleDelete(name)}
               />
@@ -118,6 +253,7 @@ function MCPManager() {
       {/* 添加弹窗 */}
       {showAddModal && (
         <AddMCPModal
+          workspace={scope}
           onClose={() => setShowAddModal(false)}
           onSuccess={() => { setShowAddModal(false); loadConfig() }}
         />
@@ -128,10 +264,55 @@ function MCPManager() {
         <EditMCPModal
           name={editingServer.name}
           config={editingServer.config}
+          workspace={scope}
           onClose={() => setEditingServer(null)}
           onSuccess={() => { setEditingServer(null); loadConfig() }}
         />
       )}
+
+      {/* 测试弹窗 */}
+      {testingServer && (
+        <TestMCPModal
+          name={testingServer}
+          workspace={scope}
+          onClose={() => setTestingServer(null)}
+        />
+      )}
+
+      {/* 工具设置弹窗 */}
+      {toolsServer && (
+        <MCPToolsModal
+          name={toolsServer}
+          workspace={scope}
+          onClose={() => setToolsServer(null)}
+          onSaved={loadConfig}
+        />
+      )}
+
+      {/* 导入/导出弹窗 */}
+      {showImportExport && (
+        <ImportExportMCPModal
+          workspace={scope}
+          onClose={() => setShowImportExport(false)}
+          onImported={() => { setShowImportExport(false); loadConfig() }}
+        />
+      )}
+
+      {/* 密钥库弹窗 */}
+      {showSecrets && (
+        <MCPSecretsModal
+          onClose={() => setShowSecrets(false)}
+          onChanged={loadConfig}
+        />
+      )}
+
+      {/* 配置方案弹窗（仅用户级） */}
+      {showProfiles && (
+        <MCPProfilesModal
+          onClose={() => setShowProfiles(false)}
+          onApplied={() => { setShowProfiles(false); loadConfig() }}
+        />
+      )}
     </div>
   )
 }
@@ -5,6 +5,8 @@ import { Lock, Copy, Sun, Moon, Palette, Check, RefreshCw, Settings as SettingsI
 import { useTheme } from '../contexts/ThemeContext'
 import { useDialog } from '../contexts/DialogContext'
 import { useI18n } from '../i18n.jsx'
+import LocalApiSettings from './LocalApiSettings'
+import KiroInstallationsSettings from './KiroInstallationsSettings'
 
 function Settings() {
   const { theme, setTheme, colors } = useTheme()
@@ -31,6 +33,7 @@ function Settings() {
   
   // Kiro IDE 状态
   const [loading, setLoading] = useState(false)
+  const [ideCloseTimeout, setIdeCloseTimeout] = useState('10') // 秒
   
   // 系统机器码
   const [systemMachineInfo, setSystemMachineInfo] = useState(null)
@@ -68,6 +71,7 @@ function Settings() {
         setAutoRefreshInterval(appSettings.autoRefreshInterval ?? 50)
         setAutoChangeMachineId(appSettings.autoChangeMachineId ?? false)
         setBindMachineIdToAccount(appSettings.bindMachineIdToAccount ?? false)
+        setIdeCloseTimeout(String(appSettings.ideCloseTimeout ?? 10))
         const browser = appSettings.browserPath || ''
         setBrowserPath(browser)
         setOriginalBrowserPath(browser)
@@ -146,6 +150,18 @@ function Settings() {
     await saveAppSettings({ autoRefreshInterval: interval })
   }
 
+  // 关闭 IDE 时等待其正常退出的秒数，失焦时保存
+  const handleIdeCloseTimeoutBlur = async () => {
+    const seconds = parseInt(ideCloseTimeout)
+    if (!seconds || seconds < 1) {
+      setIdeCloseTimeout('10')
+      await saveAppSettings({ ideCloseTimeout: 10 })
+      return
+    }
+    setIdeCloseTimeout(String(seconds))
+    await saveAppSettings({ ideCloseTimeout: seconds })
+  }
+
   const handleAutoChangeMachineIdChange = async (checked) => {
     setAutoChangeMachineId(checked)
     await saveAppSettings({ autoChangeMachineId: checked })
@@ -242,7 +258,14 @@ function Settings() {
   const handleToggleKiro = async () => {
     try {
       if (kiroRunning) {
-        await invoke('close_kiro_ide')
+        // 返回 false 表示 IDE 未在超时内正常退出，确认后再强制关闭
+        const closed = await invoke('close_kiro_ide')
+        if (!closed) {
+          const force = await showConfirm(t('switch.forceCloseTitle'), t('switch.forceCloseMessage'), {
+            confirmText: t('switch.forceCloseBtn'),
+          })
+          if (force) await invoke('close_kiro_ide', { force: true })
+        }
       } else {
         await invoke('start_kiro_ide')
       }
@@ -661,6 +684,9 @@ function Settings() {
           </p>
         </section>
 
+        {/* 本地 HTTP API */}
+        <LocalApiSettings />
+
         {/* Kiro IDE 状态 */}
         <section className={`card-glow ${colors.card} rounded-2xl p-6 shadow-sm border ${colors.cardBorder} mb-6 animate-slide-in-left delay-500`}>
           <div className="flex items-center justify-between mb-5">
@@ -700,8 +726,24 @@ function Settings() {
               {kiroRunning ? t('settings.stop') : t('settings.start')}
             </button>
           </div>
+
+          <div className="mt-4">
+            <label className={`block text-sm ${colors.textMuted} mb-2`}>{t('settings.ideCloseTimeout')}</label>
+            <input
+              type="number"
+              min="1"
+              value={ideCloseTimeout}
+              onChange={(e) => setIdeCloseTimeout(e.target.value)}
+              onBlur={handleIdeCloseTimeoutBlur}
+              className={`w-32 px-4 py-2 border rounded-xl ${colors.text} ${colors.input} ${colors.inputFocus} focus:ring-2 transition-all`}
+            />
+            <p className={`text-xs ${colors.textMuted} mt-2`}>{t('settings.ideCloseTimeoutDesc')}</p>
+          </div>
         </section>
 
+        {/* Kiro IDE 安装登记 */}
+        <KiroInstallationsSettings />
+
         {/* 系统机器码管理 */}
         <section className={`card-glow ${colors.card} rounded-2xl p-6 shadow-sm border ${colors.cardBorder} mb-6 animate-slide-in-left delay-600`}>
           <div className="flex items-center gap-2 mb-1">